@@ -9,19 +9,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Extract all form field names
     for (_page_id, page_obj) in doc.get_pages() {
-        if let Ok(page) = doc.get_object(page_obj) {
-            if let Object::Dictionary(page_dict) = page {
-                if let Ok(Object::Reference(annots_ref)) = page_dict.get(b"Annots") {
-                    if let Ok(Object::Array(annots)) = doc.get_object(*annots_ref) {
-                        for annot_ref in annots {
-                            if let Object::Reference(ref_id) = annot_ref {
-                                if let Ok(Object::Dictionary(field_dict)) = doc.get_object(*ref_id) {
-                                    if let Ok(Object::Name(subtype)) = field_dict.get(b"Subtype") {
-                                        if subtype == b"Widget" {
-                                            if let Ok(Object::String(field_name, _)) = field_dict.get(b"T") {
-                                                let name_str = String::from_utf8_lossy(field_name);
-                                                field_names.push(name_str.to_string());
-                                            }
+        if let Ok(Object::Dictionary(page_dict)) = doc.get_object(page_obj) {
+            if let Ok(Object::Reference(annots_ref)) = page_dict.get(b"Annots") {
+                if let Ok(Object::Array(annots)) = doc.get_object(*annots_ref) {
+                    for annot_ref in annots {
+                        if let Object::Reference(ref_id) = annot_ref {
+                            if let Ok(Object::Dictionary(field_dict)) = doc.get_object(*ref_id) {
+                                if let Ok(Object::Name(subtype)) = field_dict.get(b"Subtype") {
+                                    if subtype == b"Widget" {
+                                        if let Ok(Object::String(field_name, _)) = field_dict.get(b"T") {
+                                            let name_str = String::from_utf8_lossy(field_name);
+                                            field_names.push(name_str.to_string());
                                         }
                                     }
                                 }
@@ -0,0 +1,145 @@
+use crate::character_model::{CharacterData, Equipment};
+use crate::markdown_exporter::MarkdownExporter;
+use serde_json::{Map, Value};
+
+/// Strings longer than this are summarized rather than quoted in a change
+const MAX_QUOTED_CHARS: usize = 60;
+
+/// Fields holding comma-separated lists, compared item by item
+const LIST_FIELDS: [&str; 2] = ["items", "treasure"];
+
+/// Describes what changed between two versions of a character in plain
+/// lines such as "Character / Level: 4 → 5"
+pub struct CharacterDiff;
+
+impl CharacterDiff {
+    /// Changes from `before` to `after`, grouped by section; empty when they match
+    pub fn between(before: &CharacterData, after: &CharacterData) -> Vec<String> {
+        let before = serde_json::to_value(before).unwrap_or(Value::Null);
+        let after = serde_json::to_value(after).unwrap_or(Value::Null);
+        let mut changes = Vec::new();
+        Self::compare(&mut Vec::new(), &before, &after, &mut changes);
+        changes
+    }
+
+    fn compare(path: &mut Vec<String>, before: &Value, after: &Value, changes: &mut Vec<String>) {
+        if before == after {
+            return;
+        }
+        let empty_object = Value::Object(Map::new());
+        let empty_array = Value::Array(Vec::new());
+        match (before, after) {
+            (Value::Object(_), Value::Null) => Self::compare(path, before, &empty_object, changes),
+            (Value::Null, Value::Object(_)) => Self::compare(path, &empty_object, after, changes),
+            (Value::Array(_), Value::Null) => Self::compare(path, before, &empty_array, changes),
+            (Value::Null, Value::Array(_)) => Self::compare(path, &empty_array, after, changes),
+            (Value::Object(before), Value::Object(after)) => {
+                let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+                keys.sort();
+                keys.dedup();
+                for key in keys {
+                    path.push(MarkdownExporter::title(key));
+                    let old = before.get(key).unwrap_or(&Value::Null);
+                    let new = after.get(key).unwrap_or(&Value::Null);
+                    Self::compare(path, old, new, changes);
+                    path.pop();
+                }
+            }
+            (Value::Array(before), Value::Array(after)) => Self::compare_lists(path, before, after, changes),
+            (Value::String(old), Value::String(new)) if Self::is_list_field(path) => {
+                let old = Equipment::split_items(old).into_iter().map(Value::String).collect::<Vec<_>>();
+                let new = Equipment::split_items(new).into_iter().map(Value::String).collect::<Vec<_>>();
+                Self::compare_lists(path, &old, &new, changes);
+            }
+            (old, new) => {
+                let label = path.join(" / ");
+                let change = match (old, new) {
+                    (Value::Null, new) => format!("{}: set to {}", label, Self::describe(new)),
+                    (old, Value::Null) => format!("{}: cleared (was {})", label, Self::describe(old)),
+                    (Value::String(old), Value::String(new))
+                        if old.chars().count() > MAX_QUOTED_CHARS || new.chars().count() > MAX_QUOTED_CHARS =>
+                    {
+                        format!("{}: rewritten ({} → {} characters)", label, old.chars().count(), new.chars().count())
+                    }
+                    (old, new) => format!("{}: {} → {}", label, Self::describe(old), Self::describe(new)),
+                };
+                changes.push(change);
+            }
+        }
+    }
+
+    /// Lists of named entries (spells, level history) are matched by name;
+    /// other lists report what was added and removed
+    fn compare_lists(path: &mut Vec<String>, before: &[Value], after: &[Value], changes: &mut Vec<String>) {
+        let label = path.join(" / ");
+        let name_of = |value: &Value| value.get("name").and_then(|n| n.as_str()).map(str::to_string);
+        let keyed = |values: &[Value]| values.iter().map(name_of).collect::<Option<Vec<String>>>();
+
+        if let (Some(old_names), Some(new_names)) = (keyed(before), keyed(after)) {
+            let added: Vec<&str> = new_names.iter().filter(|n| !old_names.contains(n)).map(String::as_str).collect();
+            let removed: Vec<&str> = old_names.iter().filter(|n| !new_names.contains(n)).map(String::as_str).collect();
+            Self::push_added_removed(&label, &added, &removed, changes);
+            for (old, name) in before.iter().zip(&old_names) {
+                if let Some(new) = new_names.iter().position(|n| n == name).map(|index| &after[index]) {
+                    path.push(name.clone());
+                    Self::compare(path, old, new, changes);
+                    path.pop();
+                }
+            }
+            return;
+        }
+
+        let entry = |value: &Value| match value {
+            Value::String(text) => text.trim().to_string(),
+            other => Self::describe(other),
+        };
+        let added: Vec<String> = after.iter().filter(|v| !before.contains(v)).map(entry).collect();
+        let removed: Vec<String> = before.iter().filter(|v| !after.contains(v)).map(entry).collect();
+        if added.is_empty() && removed.is_empty() {
+            if before.len() == after.len() {
+                changes.push(format!("{}: reordered", label));
+            } else {
+                changes.push(format!("{}: {} → {} entries", label, before.len(), after.len()));
+            }
+            return;
+        }
+        let added: Vec<&str> = added.iter().map(String::as_str).collect();
+        let removed: Vec<&str> = removed.iter().map(String::as_str).collect();
+        Self::push_added_removed(&label, &added, &removed, changes);
+    }
+
+    fn push_added_removed(label: &str, added: &[&str], removed: &[&str], changes: &mut Vec<String>) {
+        let mut parts = Vec::new();
+        if !added.is_empty() {
+            parts.push(format!("added {}", added.join(", ")));
+        }
+        if !removed.is_empty() {
+            parts.push(format!("removed {}", removed.join(", ")));
+        }
+        if !parts.is_empty() {
+            changes.push(format!("{}: {}", label, parts.join("; ")));
+        }
+    }
+
+    fn is_list_field(path: &[String]) -> bool {
+        path.len() == 2
+            && path[0] == "Equipment"
+            && LIST_FIELDS.iter().any(|field| MarkdownExporter::title(field) == path[1])
+    }
+
+    fn describe(value: &Value) -> String {
+        match value {
+            Value::String(text) if text.chars().count() > MAX_QUOTED_CHARS => {
+                let preview: String = text.chars().take(MAX_QUOTED_CHARS).collect();
+                format!("\"{}…\"", preview.trim_end())
+            }
+            Value::String(text) => format!("\"{}\"", text),
+            Value::Bool(true) => "yes".to_string(),
+            Value::Bool(false) => "no".to_string(),
+            Value::Object(_) | Value::Array(_) => {
+                value.get("name").and_then(|n| n.as_str()).map(str::to_string).unwrap_or_else(|| value.to_string())
+            }
+            other => other.to_string(),
+        }
+    }
+}
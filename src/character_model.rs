@@ -1,28 +1,76 @@
+use crate::schema_migration::SchemaMigration;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Character {
     pub name: String,
+    /// One of the 12 SRD classes, e.g. "Wizard"
     pub class: String,
+    #[schemars(range(min = 1, max = 20))]
     pub level: u8,
+    /// Subclass chosen at the class's subclass level, e.g. "Champion"
+    pub subclass: Option<String>,
     pub race: String,
     pub background: Option<String>,
     pub player_name: Option<String>,
+    /// e.g. "Lawful Good"
     pub alignment: Option<String>,
     pub experience_points: Option<u32>,
+    /// How the character levels up; `xp` when omitted
+    pub advancement_mode: Option<AdvancementMode>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `milestone` characters level up when the DM says so: their XP isn't
+/// checked against their level and is left off the sheet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AdvancementMode {
+    #[default]
+    Xp,
+    Milestone,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+/// Base ability scores, before improvements from `level_history`
 pub struct AbilityScores {
+    #[schemars(range(min = 1, max = 30))]
     pub strength: u8,
+    #[schemars(range(min = 1, max = 30))]
     pub dexterity: u8,
+    #[schemars(range(min = 1, max = 30))]
     pub constitution: u8,
+    #[schemars(range(min = 1, max = 30))]
     pub intelligence: u8,
+    #[schemars(range(min = 1, max = 30))]
     pub wisdom: u8,
+    #[schemars(range(min = 1, max = 30))]
     pub charisma: u8,
 }
 
 impl AbilityScores {
+    pub const NAMES: [&'static str; 6] = [
+        "strength",
+        "dexterity",
+        "constitution",
+        "intelligence",
+        "wisdom",
+        "charisma",
+    ];
+
+    pub fn get_ability_score_mut(&mut self, ability: &str) -> Option<&mut u8> {
+        match ability {
+            "strength" => Some(&mut self.strength),
+            "dexterity" => Some(&mut self.dexterity),
+            "constitution" => Some(&mut self.constitution),
+            "intelligence" => Some(&mut self.intelligence),
+            "wisdom" => Some(&mut self.wisdom),
+            "charisma" => Some(&mut self.charisma),
+            _ => None,
+        }
+    }
+
     pub fn modifier(&self, ability: &str) -> i8 {
         let score = match ability {
             "strength" => self.strength,
@@ -73,6 +121,28 @@ pub fn calculate_skill_bonus(ability_score: u8, level: u8, is_proficient: bool)
     modifier + proficiency_bonus
 }
 
+/// The 18 skills in sheet order, lowercase with underscores
+pub const SKILL_NAMES: [&str; 18] = [
+    "acrobatics",
+    "animal_handling",
+    "arcana",
+    "athletics",
+    "deception",
+    "history",
+    "insight",
+    "intimidation",
+    "investigation",
+    "medicine",
+    "nature",
+    "perception",
+    "performance",
+    "persuasion",
+    "religion",
+    "sleight_of_hand",
+    "stealth",
+    "survival",
+];
+
 // Standard D&D 5e skill-to-ability mappings
 pub fn get_skill_ability(skill: &str) -> &'static str {
     match skill {
@@ -108,13 +178,13 @@ pub fn get_skill_ability(skill: &str) -> &'static str {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Skills {
     pub proficient_skills: Vec<String>,
     pub expertise_skills: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Combat {
     pub armor_class: Option<u8>,
     pub initiative: Option<i8>,
@@ -122,20 +192,36 @@ pub struct Combat {
     pub hit_point_maximum: Option<u16>,
     pub current_hit_points: Option<u16>,
     pub temporary_hit_points: Option<u16>,
+    /// e.g. "3d10"
     pub hit_dice: Option<String>,
     pub hit_dice_total: Option<u8>,
+    #[schemars(range(max = 3))]
+    pub death_save_successes: Option<u8>,
+    #[schemars(range(max = 3))]
+    pub death_save_failures: Option<u8>,
+    /// Heroic inspiration
+    pub inspiration: Option<bool>,
+    /// Exhaustion level 0-6
+    #[schemars(range(max = 6))]
+    pub exhaustion_level: Option<u8>,
+    /// Active conditions such as "Poisoned" or "Prone"
+    pub conditions: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Spell {
     pub name: String,
+    /// Spell level, 0 for cantrips; must match the list the spell is in
+    #[schemars(range(max = 9))]
     pub level: u8,
     pub prepared: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+/// Every spell level list must be present, even if empty
 pub struct Spells {
     pub spellcasting_class: Option<String>,
+    /// "Intelligence", "Wisdom" or "Charisma"
     pub spellcasting_ability: Option<String>,
     pub cantrips: Vec<Spell>,
     pub first_level: Vec<Spell>,
@@ -149,19 +235,112 @@ pub struct Spells {
     pub ninth_level: Vec<Spell>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Spells {
+    /// The spell list for a spell level (0 = cantrips)
+    pub fn level_mut(&mut self, level: u8) -> Option<&mut Vec<Spell>> {
+        match level {
+            0 => Some(&mut self.cantrips),
+            1 => Some(&mut self.first_level),
+            2 => Some(&mut self.second_level),
+            3 => Some(&mut self.third_level),
+            4 => Some(&mut self.fourth_level),
+            5 => Some(&mut self.fifth_level),
+            6 => Some(&mut self.sixth_level),
+            7 => Some(&mut self.seventh_level),
+            8 => Some(&mut self.eighth_level),
+            9 => Some(&mut self.ninth_level),
+            _ => None,
+        }
+    }
+}
+
+/// Saving throw and skill proficiencies plus languages, armor, weapons and tools
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Proficiencies {
+    /// Lowercase ability names, e.g. "wisdom"
     pub saving_throws: Vec<String>,
+    /// Lowercase skill names with underscores, e.g. "sleight_of_hand"
     pub skills: Vec<String>,
+    #[serde(flatten)]
+    pub other: OtherProficiencies,
+    /// Proficiencies granted by race, class, background, etc. Merged with the
+    /// top-level lists when rendering the Other Proficiencies & Languages box.
+    #[serde(default)]
+    pub sources: Vec<ProficiencySource>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Languages plus armor, weapon and tool proficiencies
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct OtherProficiencies {
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub armor: Vec<String>,
+    #[serde(default)]
+    pub weapons: Vec<String>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Tools the character has expertise with (implies proficiency)
+    #[serde(default)]
+    pub tool_expertise: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProficiencySourceKind {
+    Race,
+    Class,
+    Background,
+    Feat,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProficiencySource {
+    pub source: ProficiencySourceKind,
+    /// Name of the granting race, class, background or feat (e.g. "High Elf")
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub proficiencies: OtherProficiencies,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Equipment {
     pub currency: Option<Currency>,
+    /// Comma-separated list of equipment
     pub items: Option<String>,
+    /// Gems, art objects and other valuables (page 2 Treasure box)
+    pub treasure: Option<String>,
+}
+
+impl Equipment {
+    /// Split the comma-separated item list, leaving commas inside parentheses alone
+    pub fn split_items(items: &str) -> Vec<String> {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut depth = 0usize;
+        for c in items.chars() {
+            match c {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    parts.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        parts.push(current);
+        parts
+            .into_iter()
+            .map(|part| part.trim().to_string())
+            .filter(|part| !part.is_empty())
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Currency {
     pub cp: u32,
     pub sp: u32,
@@ -170,22 +349,129 @@ pub struct Currency {
     pub pp: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CharacterNarrative {
     pub personality_traits: Option<String>,
     pub ideals: Option<String>,
     pub bonds: Option<String>,
     pub flaws: Option<String>,
+    pub backstory: Option<String>,
+    /// Allies & Organizations text
+    pub allies: Option<String>,
+    pub faction_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Physical description shown at the top of page 2
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Appearance {
+    pub age: Option<String>,
+    pub height: Option<String>,
+    pub weight: Option<String>,
+    pub eyes: Option<String>,
+    pub skin: Option<String>,
+    pub hair: Option<String>,
+}
+
+/// An image supplied either as a file path or as base64-encoded PNG/JPEG bytes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSource {
+    /// Path to a PNG or JPEG file
+    Path(String),
+    /// Base64 PNG or JPEG bytes, optionally as a data URL
+    Base64(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CharacterImages {
+    pub portrait: Option<ImageSource>,
+    pub faction_symbol: Option<ImageSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FeaturesTraits {
     pub features: Option<Vec<String>>,
     pub traits: Option<Vec<String>>,
+    /// Page 2 Additional Features & Traits
+    pub additional: Option<Vec<String>>,
+}
+
+/// What was taken at a level that grants an Ability Score Improvement
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Improvement {
+    /// +2 to one ability or +1 to two, keyed by lowercase ability name
+    AbilityScoreImprovement { increases: BTreeMap<String, u8> },
+    /// A feat in place of the ASI; `ability_increases` records any ability
+    /// choice the feat offers (e.g. Resilient's +1)
+    Feat {
+        name: String,
+        #[serde(default)]
+        ability_increases: BTreeMap<String, u8>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LevelAdvancement {
+    /// Class level at which the improvement was taken
+    pub level: u8,
+    /// Class that granted the improvement; defaults to the character's class
+    pub class: Option<String>,
+    pub improvement: Improvement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatSource {
+    /// Full definition from the System Reference Document
+    Srd,
+    /// Mechanics supplied by the user (non-SRD feats)
+    UserSupplied,
+}
+
+/// Mechanical effect of a feat that feeds into derived stats
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeatEffect {
+    AbilityIncrease { ability: String, amount: u8 },
+    /// Increase one of `options`, chosen via the advancement's `ability_increases`
+    AbilityIncreaseChoice { options: Vec<String>, amount: u8 },
+    InitiativeBonus { bonus: i8 },
+    /// Bonus to a passive score, e.g. "perception" or "investigation"
+    PassiveBonus { skill: String, bonus: i8 },
+    HitPointsPerLevel { amount: u8 },
+    /// Rules text shown in Features & Traits
+    Note { text: String },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeatDefinition {
+    pub name: String,
+    pub source: FeatSource,
+    pub prerequisite: Option<String>,
+    #[serde(default)]
+    pub effects: Vec<FeatEffect>,
+}
+
+/// Layout version of the character_data written by this release
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+fn current_schema_version() -> u32 {
+    CURRENT_SCHEMA_VERSION
+}
+
+/// Complete D&D 5e character information used to fill the character sheet
+///
+/// Deserializing accepts every earlier `schema_version` and migrates it
+/// forward first (see `SchemaMigration`), so loaded data is always current.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(remote = "Self")]
+#[schemars(example = crate::schema::example_character())]
 pub struct CharacterData {
+    /// Layout version; files without one are version 1
+    #[serde(default = "current_schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
     pub character: Character,
     pub abilities: AbilityScores,
     pub proficiencies: Option<Proficiencies>,
@@ -194,4 +480,25 @@ pub struct CharacterData {
     pub equipment: Option<Equipment>,
     pub narrative: Option<CharacterNarrative>,
     pub features_traits: Option<FeaturesTraits>,
+    pub appearance: Option<Appearance>,
+    pub images: Option<CharacterImages>,
+    /// ASIs and feats taken per class level, applied on top of `abilities`
+    pub level_history: Option<Vec<LevelAdvancement>>,
+    /// Custom or non-SRD feat definitions; override catalog entries by name
+    pub feat_definitions: Option<Vec<FeatDefinition>>,
+}
+
+impl Serialize for CharacterData {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CharacterData::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CharacterData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        SchemaMigration::migrate(&mut value).map_err(D::Error::custom)?;
+        CharacterData::deserialize(value).map_err(D::Error::custom)
+    }
 }
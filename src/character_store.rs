@@ -0,0 +1,426 @@
+use crate::character_model::CharacterData;
+use crate::errors::StoreError;
+use crate::logging::Logger;
+use crate::dnd_validator::DndValidator;
+use crate::party::Party;
+use crate::session_log::Session;
+use crate::sheet_writer::SheetWriter;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+const LOGGER: &str = "character_store";
+
+/// Directory under the store root holding `<id>/0001.json`, `<id>/0002.json`, ...
+const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Directory under the store root holding `<party id>.json`
+const PARTY_DIR: &str = "parties";
+
+/// Directory under the store root holding `0001.json`, `0002.json`, ... per session
+const SESSION_DIR: &str = "sessions";
+
+/// One saved character, as listed by `CharacterStore::list`
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StoredCharacter {
+    pub id: String,
+    pub name: String,
+    pub class: String,
+    pub level: u8,
+    /// Number of the newest snapshot; every save adds one
+    pub latest_version: u32,
+    /// When the character was last saved, e.g. "2026-03-14T19:02:11Z"
+    pub updated: Option<String>,
+    /// Has the experience for a higher level than their own
+    pub ready_to_level_up: bool,
+}
+
+/// What `CharacterStore::save` wrote, and the character it replaced
+#[derive(Debug, Clone)]
+pub struct SavedCharacter {
+    pub id: String,
+    pub version: u32,
+    /// The current character before this save, read with the store locked
+    pub previous: Option<CharacterData>,
+}
+
+/// The store's lock, held by `CharacterStore::lock`; saves made with it
+/// go through `save_locked`
+pub struct StoreLock<'a> {
+    _guard: MutexGuard<'a, ()>,
+}
+
+/// A directory of characters keyed by id: `<id>.json` holds the current
+/// character_data and every save also keeps a numbered snapshot. Parties
+/// and session records are kept alongside in `parties/` and `sessions/`
+pub struct CharacterStore {
+    root: PathBuf,
+    /// Held while a save picks its version or a session is applied, so
+    /// concurrent requests don't collide
+    lock: Mutex<()>,
+}
+
+impl CharacterStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Id derived from a character's name: "Erilon Silverleaf" -> "erilon-silverleaf"
+    pub fn id_for(name: &str) -> String {
+        let slug: Vec<String> = name
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_lowercase())
+            .collect();
+        match slug.is_empty() {
+            true => "character".to_string(),
+            false => slug.join("-"),
+        }
+    }
+
+    /// Save `character` as the current version of `id` (or the id derived
+    /// from its name) and snapshot it; returns the id, the new version and
+    /// the character it replaced.
+    /// Without an `id`, a different character already saved under the
+    /// derived id is an error rather than a new version of it
+    pub fn save(&self, id: Option<&str>, character: &CharacterData) -> Result<SavedCharacter, StoreError> {
+        let lock = self.lock();
+        self.save_locked(&lock, id, character)
+    }
+
+    /// `save`, for a caller already holding the store's lock
+    pub fn save_locked(
+        &self,
+        _lock: &StoreLock<'_>,
+        id: Option<&str>,
+        character: &CharacterData,
+    ) -> Result<SavedCharacter, StoreError> {
+        let id = match id {
+            Some(id) => Self::checked_id(id)?.to_string(),
+            None => self.derived_id(character)?,
+        };
+        let snapshot_dir = self.snapshot_dir(&id);
+        fs::create_dir_all(&snapshot_dir).map_err(|e| StoreError::WriteFailed {
+            path: snapshot_dir.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        // An unreadable current file is replaced rather than diffed against
+        let current = self.current_path(&id);
+        let previous = Self::read(&current).ok();
+        let first = self.versions(&id)?.last().copied().unwrap_or(0) + 1;
+        let version = self.write_snapshot(&id, first, character)?;
+        Self::write_json(&current, character, true)?;
+
+        Logger::info(LOGGER, &format!("Saved {} as version {}", id, version));
+        Ok(SavedCharacter { id, version, previous })
+    }
+
+    /// The current character, or the snapshot `version`, with the version loaded
+    pub fn load(&self, id: &str, version: Option<u32>) -> Result<(CharacterData, u32), StoreError> {
+        let id = Self::checked_id(id)?;
+        let versions = self.versions(id)?;
+        let current = self.current_path(id);
+        if !current.is_file() && versions.is_empty() {
+            return Err(StoreError::NotFound(id.to_string()));
+        }
+        let (path, version) = match version {
+            None => (current, versions.last().copied().unwrap_or(0)),
+            Some(version) if versions.contains(&version) => (self.snapshot_path(id, version), version),
+            Some(version) => return Err(StoreError::VersionNotFound { id: id.to_string(), version }),
+        };
+        Ok((Self::read(&path)?, version))
+    }
+
+    /// Snapshot numbers saved for `id`, oldest first
+    pub fn versions(&self, id: &str) -> Result<Vec<u32>, StoreError> {
+        let dir = self.snapshot_dir(Self::checked_id(id)?);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+        let mut versions: Vec<u32> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                name.strip_suffix(".json").and_then(|stem| stem.parse().ok())
+            })
+            .collect();
+        versions.sort_unstable();
+        Ok(versions)
+    }
+
+    /// Every saved character, by id; files that no longer parse are skipped
+    /// with a warning
+    pub fn list(&self) -> Result<Vec<StoredCharacter>, StoreError> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(StoreError::ReadFailed {
+                    path: self.root.display().to_string(),
+                    message: e.to_string(),
+                })
+            }
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        let mut characters = Vec::new();
+        for path in paths {
+            let id = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            if Self::checked_id(&id).is_err() {
+                continue;
+            }
+            let character: CharacterData = match Self::read(&path) {
+                Ok(character) => character,
+                Err(e) => {
+                    Logger::warning(LOGGER, &format!("Skipping {}: {}", path.display(), e));
+                    continue;
+                }
+            };
+            let updated = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(|time| DateTime::<Utc>::from(time).format("%Y-%m-%dT%H:%M:%SZ").to_string());
+            characters.push(StoredCharacter {
+                latest_version: self.versions(&id)?.last().copied().unwrap_or(0),
+                ready_to_level_up: DndValidator::ready_to_level_up(&character),
+                id,
+                name: character.character.name,
+                class: character.character.class,
+                level: character.character.level,
+                updated,
+            });
+        }
+        Ok(characters)
+    }
+
+    /// Remove `id` and all its snapshots; returns how many snapshots went with it
+    pub fn delete(&self, id: &str) -> Result<usize, StoreError> {
+        let id = Self::checked_id(id)?;
+        let current = self.current_path(id);
+        let snapshot_dir = self.snapshot_dir(id);
+        if !current.is_file() && !snapshot_dir.is_dir() {
+            return Err(StoreError::NotFound(id.to_string()));
+        }
+        let snapshots = self.versions(id)?.len();
+        let removed = match current.is_file() {
+            true => fs::remove_file(&current).map_err(|e| (current.clone(), e)),
+            false => Ok(()),
+        }
+        .and_then(|_| match snapshot_dir.is_dir() {
+            true => fs::remove_dir_all(&snapshot_dir).map_err(|e| (snapshot_dir.clone(), e)),
+            false => Ok(()),
+        });
+        removed.map_err(|(path, e)| StoreError::WriteFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        Logger::info(LOGGER, &format!("Deleted {} and {} snapshot(s)", id, snapshots));
+        Ok(snapshots)
+    }
+
+    /// Keep other saves and session applies in this process waiting until
+    /// the returned lock is dropped
+    pub fn lock(&self) -> StoreLock<'_> {
+        StoreLock {
+            _guard: self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner()),
+        }
+    }
+
+    /// Save `party` under the id derived from its name, replacing any
+    /// earlier version; returns the id
+    pub fn save_party(&self, party: &Party) -> Result<String, StoreError> {
+        let id = Self::id_for(&party.name);
+        let dir = self.root.join(PARTY_DIR);
+        fs::create_dir_all(&dir).map_err(|e| StoreError::WriteFailed {
+            path: dir.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::write_json(&self.party_path(&id), party, true)?;
+        Logger::info(LOGGER, &format!("Saved party {}", id));
+        Ok(id)
+    }
+
+    /// The party with this name or id
+    pub fn load_party(&self, party: &str) -> Result<Party, StoreError> {
+        let id = Self::id_for(party);
+        let path = self.party_path(&id);
+        if !path.is_file() {
+            return Err(StoreError::PartyNotFound(id));
+        }
+        Self::read(&path)
+    }
+
+    /// Every saved party with its id, by id; like `list`, unreadable files
+    /// are skipped with a warning
+    pub fn parties(&self) -> Result<Vec<(String, Party)>, StoreError> {
+        let Ok(entries) = fs::read_dir(self.root.join(PARTY_DIR)) else {
+            return Ok(Vec::new());
+        };
+        let mut ids: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_string_lossy().strip_suffix(".json").map(str::to_string))
+            .collect();
+        ids.sort();
+
+        let mut parties = Vec::new();
+        for id in ids {
+            let path = self.party_path(&id);
+            match Self::read(&path) {
+                Ok(party) => parties.push((id, party)),
+                Err(e) => Logger::warning(LOGGER, &format!("Skipping {}: {}", path.display(), e)),
+            }
+        }
+        Ok(parties)
+    }
+
+    /// Save `session`, replacing an earlier record with the same number
+    pub fn save_session(&self, session: &Session) -> Result<(), StoreError> {
+        let dir = self.root.join(SESSION_DIR);
+        fs::create_dir_all(&dir).map_err(|e| StoreError::WriteFailed {
+            path: dir.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::write_json(&self.session_path(session.number), session, true)?;
+        Logger::info(LOGGER, &format!("Saved session {}", session.number));
+        Ok(())
+    }
+
+    pub fn load_session(&self, number: u32) -> Result<Session, StoreError> {
+        let path = self.session_path(number);
+        if !path.is_file() {
+            return Err(StoreError::SessionNotFound(number));
+        }
+        Self::read(&path)
+    }
+
+    /// Numbers of the recorded sessions, in order
+    pub fn session_numbers(&self) -> Vec<u32> {
+        let Ok(entries) = fs::read_dir(self.root.join(SESSION_DIR)) else {
+            return Vec::new();
+        };
+        let mut numbers: Vec<u32> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_string_lossy().strip_suffix(".json").and_then(|stem| stem.parse().ok()))
+            .collect();
+        numbers.sort_unstable();
+        numbers
+    }
+
+    /// Write the snapshot as `version`, or the next free number when another
+    /// process got there first; snapshots are never replaced
+    fn write_snapshot(&self, id: &str, version: u32, character: &CharacterData) -> Result<u32, StoreError> {
+        let mut version = version;
+        loop {
+            let path = self.snapshot_path(id, version);
+            let write_failed = |e: std::io::Error| StoreError::WriteFailed {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            };
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(Self::to_json(&path, character)?.as_bytes()).map_err(write_failed)?;
+                    return Ok(version);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => version += 1,
+                Err(e) => return Err(write_failed(e)),
+            }
+        }
+    }
+
+    /// The id derived from the character's name, unless someone else is saved under it
+    fn derived_id(&self, character: &CharacterData) -> Result<String, StoreError> {
+        let id = Self::id_for(&character.character.name);
+        let current = self.current_path(&id);
+        if current.is_file() {
+            let saved: CharacterData = Self::read(&current)?;
+            let (saved, new) = (&saved.character, &character.character);
+            let same = saved.name == new.name
+                && saved.class == new.class
+                && saved.race == new.race
+                && saved.player_name == new.player_name;
+            if !same {
+                return Err(StoreError::IdTaken { id, name: saved.name.clone() });
+            }
+        }
+        Ok(id)
+    }
+
+    /// Ids become file names, so only lowercase letters, digits and dashes pass
+    fn checked_id(id: &str) -> Result<&str, StoreError> {
+        let valid = !id.is_empty()
+            && id.len() <= 100
+            && !id.starts_with('-')
+            && id.chars().all(|c| (c.is_alphanumeric() && !c.is_uppercase()) || c == '-');
+        match valid {
+            true => Ok(id),
+            false => Err(StoreError::InvalidId(id.to_string())),
+        }
+    }
+
+    fn write_json<T: Serialize>(path: &Path, value: &T, overwrite: bool) -> Result<(), StoreError> {
+        let json = Self::to_json(path, value)?;
+        SheetWriter::write(path, json.as_bytes(), overwrite).map_err(|e| StoreError::WriteFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Ok(())
+    }
+
+    fn to_json<T: Serialize>(path: &Path, value: &T) -> Result<String, StoreError> {
+        let mut json = serde_json::to_string_pretty(value).map_err(|e| StoreError::WriteFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        json.push('\n');
+        Ok(json)
+    }
+
+    fn read<T: DeserializeOwned>(path: &Path) -> Result<T, StoreError> {
+        let text = fs::read_to_string(path).map_err(|e| StoreError::ReadFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        serde_json::from_str(&text).map_err(|e| StoreError::Corrupt {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    fn current_path(&self, id: &str) -> PathBuf {
+        self.root.join(format!("{}.json", id))
+    }
+
+    fn snapshot_dir(&self, id: &str) -> PathBuf {
+        self.root.join(SNAPSHOT_DIR).join(id)
+    }
+
+    fn snapshot_path(&self, id: &str, version: u32) -> PathBuf {
+        self.snapshot_dir(id).join(format!("{:04}.json", version))
+    }
+
+    fn session_path(&self, number: u32) -> PathBuf {
+        self.root.join(SESSION_DIR).join(format!("{:04}.json", number))
+    }
+
+    fn party_path(&self, id: &str) -> PathBuf {
+        self.root.join(PARTY_DIR).join(format!("{}.json", id))
+    }
+}
@@ -0,0 +1,151 @@
+use crate::spell_system::CasterType;
+use serde::Serialize;
+
+/// SRD class reference data used for derived stats, validation and leveling
+#[derive(Debug, Clone, Serialize)]
+pub struct ClassInfo {
+    pub name: &'static str,
+    pub hit_die: u8,
+    pub saving_throws: [&'static str; 2],
+    /// Class levels that grant an Ability Score Improvement (or a feat instead)
+    pub asi_levels: &'static [u8],
+    /// Class level at which the subclass is chosen
+    pub subclass_level: u8,
+    pub spellcasting_ability: Option<&'static str>,
+    /// Spell slot progression, also behind `SpellSystem::get_caster_type`
+    #[serde(skip)]
+    pub caster_type: CasterType,
+}
+
+const STANDARD_ASI_LEVELS: &[u8] = &[4, 8, 12, 16, 19];
+
+const CLASSES: &[ClassInfo] = &[
+    ClassInfo {
+        name: "Barbarian",
+        hit_die: 12,
+        saving_throws: ["strength", "constitution"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 3,
+        spellcasting_ability: None,
+        caster_type: CasterType::None,
+    },
+    ClassInfo {
+        name: "Bard",
+        hit_die: 8,
+        saving_throws: ["dexterity", "charisma"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 3,
+        spellcasting_ability: Some("Charisma"),
+        caster_type: CasterType::Full,
+    },
+    ClassInfo {
+        name: "Cleric",
+        hit_die: 8,
+        saving_throws: ["wisdom", "charisma"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 1,
+        spellcasting_ability: Some("Wisdom"),
+        caster_type: CasterType::Full,
+    },
+    ClassInfo {
+        name: "Druid",
+        hit_die: 8,
+        saving_throws: ["intelligence", "wisdom"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 2,
+        spellcasting_ability: Some("Wisdom"),
+        caster_type: CasterType::Full,
+    },
+    ClassInfo {
+        name: "Fighter",
+        hit_die: 10,
+        saving_throws: ["strength", "constitution"],
+        asi_levels: &[4, 6, 8, 12, 14, 16, 19],
+        subclass_level: 3,
+        spellcasting_ability: None,
+        caster_type: CasterType::Third, // Assumes Eldritch Knight/Arcane Trickster
+    },
+    ClassInfo {
+        name: "Monk",
+        hit_die: 8,
+        saving_throws: ["strength", "dexterity"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 3,
+        spellcasting_ability: None,
+        caster_type: CasterType::None,
+    },
+    ClassInfo {
+        name: "Paladin",
+        hit_die: 10,
+        saving_throws: ["wisdom", "charisma"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 3,
+        spellcasting_ability: Some("Charisma"),
+        caster_type: CasterType::Half,
+    },
+    ClassInfo {
+        name: "Ranger",
+        hit_die: 10,
+        saving_throws: ["strength", "dexterity"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 3,
+        spellcasting_ability: Some("Wisdom"),
+        caster_type: CasterType::Half,
+    },
+    ClassInfo {
+        name: "Rogue",
+        hit_die: 8,
+        saving_throws: ["dexterity", "intelligence"],
+        asi_levels: &[4, 8, 10, 12, 16, 19],
+        subclass_level: 3,
+        spellcasting_ability: None,
+        caster_type: CasterType::Third, // Assumes Eldritch Knight/Arcane Trickster
+    },
+    ClassInfo {
+        name: "Sorcerer",
+        hit_die: 6,
+        saving_throws: ["constitution", "charisma"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 1,
+        spellcasting_ability: Some("Charisma"),
+        caster_type: CasterType::Full,
+    },
+    ClassInfo {
+        name: "Warlock",
+        hit_die: 8,
+        saving_throws: ["wisdom", "charisma"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 1,
+        spellcasting_ability: Some("Charisma"),
+        caster_type: CasterType::None,
+    },
+    ClassInfo {
+        name: "Wizard",
+        hit_die: 6,
+        saving_throws: ["intelligence", "wisdom"],
+        asi_levels: STANDARD_ASI_LEVELS,
+        subclass_level: 2,
+        spellcasting_ability: Some("Intelligence"),
+        caster_type: CasterType::Full,
+    },
+];
+
+pub struct ClassCatalog;
+
+impl ClassCatalog {
+    /// Look up a class by name (case-insensitive)
+    pub fn get(class: &str) -> Option<&'static ClassInfo> {
+        CLASSES
+            .iter()
+            .find(|info| info.name.eq_ignore_ascii_case(class.trim()))
+    }
+
+    pub fn all() -> &'static [ClassInfo] {
+        CLASSES
+    }
+
+    /// Average hit points gained per level after 1st (rounded up, as in the PHB)
+    pub fn average_hit_points_per_level(hit_die: u8) -> u8 {
+        hit_die / 2 + 1
+    }
+}
@@ -0,0 +1,194 @@
+use crate::errors::{ConfigError, LoggingError};
+use crate::logging::{LogConfig, LogLevel, LogTarget};
+use crate::mcp_server::PDF_TEMPLATE;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub const CONFIG_ENV: &str = "DND_MCP_CONFIG";
+pub const DEFAULT_CONFIG_FILE: &str = "dnd-mcp.toml";
+
+/// Settings shared by the CLI and the MCP server, read from a TOML file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Where sheets are written when no output path, or a relative one, is given
+    pub output_dir: Option<PathBuf>,
+    /// PDF template used instead of the bundled 5e character sheet
+    pub template: Option<PathBuf>,
+    /// Default for `allow_rule_violations` when a request doesn't set it
+    pub allow_rule_violations: bool,
+    /// Directories besides `output_dir` that sheets may be written to
+    pub allowed_output_roots: Vec<PathBuf>,
+    /// Where saved characters and their snapshots live
+    pub store_dir: Option<PathBuf>,
+    pub logging: LoggingSettings,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingSettings {
+    pub level: Option<LogLevel>,
+    /// Log file; stderr when unset
+    pub file: Option<PathBuf>,
+    pub max_file_bytes: Option<u64>,
+    pub max_files: Option<usize>,
+}
+
+impl ServerConfig {
+    /// Load `path`, else `$DND_MCP_CONFIG`, else `./dnd-mcp.toml` when it
+    /// exists; with none of those the defaults are used
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        if let Some(path) = path {
+            return Self::from_file(path);
+        }
+        if let Ok(path) = std::env::var(CONFIG_ENV) {
+            if !path.trim().is_empty() {
+                return Self::from_file(Path::new(&path));
+            }
+        }
+        let default_file = Path::new(DEFAULT_CONFIG_FILE);
+        if default_file.is_file() {
+            return Self::from_file(default_file);
+        }
+        Ok(Self::default())
+    }
+
+    /// Parse a config file; relative paths in it are relative to the file
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|e| ConfigError::ReadFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let mut config: Self = toml::from_str(&text).map_err(|e| ConfigError::ParseFailed {
+            path: path.display().to_string(),
+            message: e.message().to_string(),
+        })?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        let resolve = |p: &mut PathBuf| *p = absolute(&base.join(&*p));
+        config.output_dir.iter_mut().for_each(resolve);
+        config.template.iter_mut().for_each(resolve);
+        config.allowed_output_roots.iter_mut().for_each(resolve);
+        config.store_dir.iter_mut().for_each(resolve);
+        config.logging.file.iter_mut().for_each(resolve);
+        Ok(config)
+    }
+
+    /// Configured output directory, or the current directory
+    pub fn output_dir(&self) -> PathBuf {
+        match &self.output_dir {
+            Some(dir) => absolute(dir),
+            None => absolute(Path::new(".")),
+        }
+    }
+
+    /// Configured character store, or `characters` under the output directory
+    pub fn store_dir(&self) -> PathBuf {
+        match &self.store_dir {
+            Some(dir) => absolute(dir),
+            None => self.output_dir().join("characters"),
+        }
+    }
+
+    /// Bytes of the configured template, or the bundled character sheet
+    pub fn template_bytes(&self) -> Result<Cow<'static, [u8]>, ConfigError> {
+        match &self.template {
+            Some(path) => fs::read(path).map(Cow::Owned).map_err(|e| ConfigError::ReadFailed {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            }),
+            None => Ok(Cow::Borrowed(PDF_TEMPLATE)),
+        }
+    }
+
+    /// Where a sheet should be written: `requested` (or `default_name`)
+    /// under the output directory, confined to it or another allowed root.
+    /// `..` is rejected outright, as are symlinks (the file itself or a
+    /// directory on the way) that lead outside the roots, or nowhere
+    pub fn resolve_output(&self, requested: Option<&str>, default_name: &str) -> Result<PathBuf, ConfigError> {
+        let requested = requested.filter(|p| !p.trim().is_empty()).unwrap_or(default_name);
+        if Path::new(requested).components().any(|c| c == Component::ParentDir) {
+            return Err(ConfigError::OutputPathTraversal(requested.to_string()));
+        }
+        let path = absolute(&self.output_dir().join(requested));
+        let not_allowed = || ConfigError::OutputNotAllowed(path.display().to_string());
+
+        let roots = self.output_roots();
+        if !roots.iter().any(|root| path.starts_with(root)) {
+            return Err(not_allowed());
+        }
+        // Compare real locations too, in case the file or a directory is a
+        // symlink; a file that doesn't exist yet is judged by its directory
+        let real_path = match fs::symlink_metadata(&path) {
+            Ok(_) => Some(path.canonicalize().map_err(|_| not_allowed())?),
+            Err(_) => path.parent().and_then(|parent| parent.canonicalize().ok()),
+        };
+        if let Some(real_path) = real_path {
+            let inside = roots.iter().any(|root| {
+                let real_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+                real_path.starts_with(real_root)
+            });
+            if !inside {
+                return Err(not_allowed());
+            }
+        }
+        Ok(path)
+    }
+
+    /// A file to read, held to the same directories sheets may be written to
+    pub fn resolve_input(&self, requested: &str) -> Result<PathBuf, ConfigError> {
+        self.resolve_output(Some(requested), requested).map_err(|e| match e {
+            ConfigError::OutputNotAllowed(_) | ConfigError::OutputPathTraversal(_) => {
+                ConfigError::InputNotAllowed(requested.to_string())
+            }
+            other => other,
+        })
+    }
+
+    /// The output directory plus `allowed_output_roots`
+    pub fn output_roots(&self) -> Vec<PathBuf> {
+        let mut roots = vec![self.output_dir()];
+        roots.extend(self.allowed_output_roots.iter().map(|root| absolute(root)));
+        roots
+    }
+
+    /// Logging settings from the file, overridden by the environment
+    pub fn log_config(&self) -> Result<LogConfig, LoggingError> {
+        let mut config = LogConfig::default();
+        if let Some(level) = self.logging.level {
+            config.level = level;
+        }
+        if let Some(file) = &self.logging.file {
+            config.target = LogTarget::File(file.clone());
+        }
+        if let Some(max_file_bytes) = self.logging.max_file_bytes {
+            config.max_file_bytes = max_file_bytes;
+        }
+        if let Some(max_files) = self.logging.max_files {
+            config.max_files = max_files;
+        }
+        config.with_env()
+    }
+}
+
+/// Absolute path with `.` and `..` resolved lexically, so it works for files
+/// that don't exist yet
+fn absolute(path: &Path) -> PathBuf {
+    let joined = match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => std::env::current_dir().unwrap_or_default().join(path),
+    };
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
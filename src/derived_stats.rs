@@ -0,0 +1,244 @@
+use crate::character_model::{
+    get_proficiency_bonus, AbilityScores, CharacterData, Equipment, FeatDefinition, FeatEffect, FeatSource,
+    Improvement,
+};
+use crate::class_catalog::ClassCatalog;
+use crate::feat_catalog::FeatCatalog;
+use crate::proficiency_handler::ProficiencyHandler;
+use crate::weapon_catalog::{WeaponCatalog, WeaponInfo};
+
+/// Stats computed from the character data plus level-history improvements
+/// and feat effects. Explicit values in `combat` still take precedence when
+/// filling the sheet.
+#[derive(Debug, Clone)]
+pub struct DerivedStats {
+    /// Ability scores after every ASI and feat increase
+    pub abilities: AbilityScores,
+    pub proficiency_bonus: u8,
+    pub initiative: i8,
+    pub passive_perception: i8,
+    pub passive_investigation: i8,
+    /// Computed from the class hit die when the class is known
+    pub hit_point_maximum: Option<u16>,
+    /// Extra hit points per level from feats such as Tough
+    pub hit_point_bonus_per_level: u16,
+    pub feats: Vec<FeatDefinition>,
+}
+
+/// An attack row for a weapon in the inventory
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaponAttack {
+    pub name: String,
+    pub attack_bonus: i8,
+    /// e.g. "1d8+3 slashing"
+    pub damage: String,
+    pub properties: Vec<String>,
+}
+
+impl DerivedStats {
+    pub fn calculate(character_data: &CharacterData) -> Self {
+        let abilities = Self::apply_level_history(character_data);
+        let feats = Self::feats_taken(character_data);
+        let level = character_data.character.level;
+        let proficiency_bonus = get_proficiency_bonus(level);
+
+        let mut initiative = abilities.modifier("dexterity");
+        let mut passive_perception_bonus = 0;
+        let mut passive_investigation_bonus = 0;
+        let mut hit_points_per_level = 0u16;
+
+        for effect in feats.iter().flat_map(|feat| &feat.effects) {
+            match effect {
+                FeatEffect::InitiativeBonus { bonus } => initiative += bonus,
+                FeatEffect::PassiveBonus { skill, bonus } => match skill.as_str() {
+                    "perception" => passive_perception_bonus += bonus,
+                    "investigation" => passive_investigation_bonus += bonus,
+                    _ => {}
+                },
+                FeatEffect::HitPointsPerLevel { amount } => hit_points_per_level += *amount as u16,
+                _ => {}
+            }
+        }
+
+        let proficient_in = |skill: &str| {
+            character_data
+                .proficiencies
+                .as_ref()
+                .map(|p| p.skills.iter().any(|s| s == skill))
+                .unwrap_or(false)
+        };
+        let passive = |ability: &str, skill: &str, bonus: i8| {
+            let proficiency = if proficient_in(skill) { proficiency_bonus as i8 } else { 0 };
+            10 + abilities.modifier(ability) + proficiency + bonus
+        };
+        let passive_perception = passive("wisdom", "perception", passive_perception_bonus);
+        let passive_investigation =
+            passive("intelligence", "investigation", passive_investigation_bonus);
+
+        let hit_point_maximum = ClassCatalog::get(&character_data.character.class).map(|class| {
+            let con_mod = abilities.modifier("constitution") as i16;
+            let first_level = (class.hit_die as i16 + con_mod).max(1);
+            let later_levels = (ClassCatalog::average_hit_points_per_level(class.hit_die) as i16
+                + con_mod)
+                .max(1)
+                * (level.max(1) as i16 - 1);
+            (first_level + later_levels) as u16 + hit_points_per_level * level as u16
+        });
+
+        Self {
+            abilities,
+            proficiency_bonus,
+            initiative,
+            passive_perception,
+            passive_investigation,
+            hit_point_maximum,
+            hit_point_bonus_per_level: hit_points_per_level,
+            feats,
+        }
+    }
+
+    /// Attack rows for the SRD weapons in the inventory: Strength for melee,
+    /// Dexterity for ranged and the better of the two for finesse weapons,
+    /// adding the proficiency bonus when the weapon or its category is proficient
+    pub fn attacks(&self, character_data: &CharacterData) -> Vec<WeaponAttack> {
+        let items = character_data.equipment.as_ref().and_then(|e| e.items.as_deref()).unwrap_or_default();
+        let mut attacks: Vec<WeaponAttack> = Vec::new();
+        for item in Equipment::split_items(items) {
+            // "Dagger (2)" is two daggers
+            let name = item
+                .strip_suffix(')')
+                .and_then(|rest| rest.rsplit_once(" ("))
+                .filter(|(_, count)| count.parse::<u32>().is_ok())
+                .map_or(item.as_str(), |(name, _)| name);
+            let Some(weapon) = WeaponCatalog::get(name) else {
+                continue;
+            };
+            if attacks.iter().any(|attack| attack.name == weapon.name) {
+                continue;
+            }
+            let ability = match (weapon.ranged, WeaponCatalog::is_finesse(weapon)) {
+                (_, true) if self.abilities.modifier("strength") > self.abilities.modifier("dexterity") => "strength",
+                (true, _) | (_, true) => "dexterity",
+                _ => "strength",
+            };
+            let modifier = self.abilities.modifier(ability);
+            let proficient = Self::weapon_proficient(character_data, weapon);
+            let damage = match modifier {
+                0 => format!("{} {}", weapon.damage, weapon.damage_type),
+                _ => format!("{}{:+} {}", weapon.damage, modifier, weapon.damage_type),
+            };
+            attacks.push(WeaponAttack {
+                name: weapon.name.to_string(),
+                attack_bonus: modifier + if proficient { self.proficiency_bonus as i8 } else { 0 },
+                damage,
+                properties: weapon.properties.iter().map(|p| p.to_string()).collect(),
+            });
+        }
+        attacks
+    }
+
+    /// "Martial weapons", "All weapons", "Longswords" and "Longsword" all count
+    fn weapon_proficient(character_data: &CharacterData, weapon: &WeaponInfo) -> bool {
+        let Some(proficiencies) = &character_data.proficiencies else {
+            return false;
+        };
+        ProficiencyHandler::merge(proficiencies).weapons.iter().any(|entry| {
+            let entry = entry.trim().to_lowercase();
+            let category = weapon.category.to_lowercase();
+            WeaponCatalog::get(&entry).is_some_and(|known| known.name == weapon.name)
+                || entry == format!("{} weapons", category)
+                || entry == "all weapons"
+        })
+    }
+
+    /// Apply every ASI and feat ability increase, in level order, to the base scores
+    pub fn apply_level_history(character_data: &CharacterData) -> AbilityScores {
+        let mut abilities = character_data.abilities.clone();
+        let custom_feats = character_data.feat_definitions.as_deref();
+
+        for advancement in Self::sorted_history(character_data) {
+            for (ability, amount) in Self::ability_increases(&advancement.improvement, custom_feats) {
+                if let Some(score) = abilities.get_ability_score_mut(&ability) {
+                    *score = score.saturating_add(amount);
+                }
+            }
+        }
+
+        abilities
+    }
+
+    /// All ability increases granted by a single improvement
+    pub fn ability_increases(
+        improvement: &Improvement,
+        custom_feats: Option<&[FeatDefinition]>,
+    ) -> Vec<(String, u8)> {
+        match improvement {
+            Improvement::AbilityScoreImprovement { increases } => increases
+                .iter()
+                .map(|(ability, amount)| (ability.to_lowercase(), *amount))
+                .collect(),
+            Improvement::Feat { name, ability_increases } => {
+                let mut increases: Vec<(String, u8)> = ability_increases
+                    .iter()
+                    .map(|(ability, amount)| (ability.to_lowercase(), *amount))
+                    .collect();
+                if let Some(feat) = FeatCatalog::resolve(name, custom_feats) {
+                    for effect in &feat.effects {
+                        if let FeatEffect::AbilityIncrease { ability, amount } = effect {
+                            increases.push((ability.to_lowercase(), *amount));
+                        }
+                    }
+                }
+                increases
+            }
+        }
+    }
+
+    /// Feat rules notes for the Features & Traits box, e.g. "War Caster: ..."
+    pub fn feat_notes(&self) -> Vec<String> {
+        self.feats
+            .iter()
+            .map(|feat| {
+                let notes: Vec<&str> = feat
+                    .effects
+                    .iter()
+                    .filter_map(|effect| match effect {
+                        FeatEffect::Note { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                if notes.is_empty() {
+                    feat.name.clone()
+                } else {
+                    format!("{}: {}", feat.name, notes.join("; "))
+                }
+            })
+            .collect()
+    }
+
+    fn feats_taken(character_data: &CharacterData) -> Vec<FeatDefinition> {
+        let custom_feats = character_data.feat_definitions.as_deref();
+        Self::sorted_history(character_data)
+            .into_iter()
+            .filter_map(|advancement| match &advancement.improvement {
+                Improvement::Feat { name, .. } => Some(
+                    FeatCatalog::resolve(name, custom_feats).unwrap_or_else(|| FeatDefinition {
+                        name: name.clone(),
+                        source: FeatSource::UserSupplied,
+                        prerequisite: None,
+                        effects: Vec::new(),
+                    }),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn sorted_history(
+        character_data: &CharacterData,
+    ) -> Vec<&crate::character_model::LevelAdvancement> {
+        let mut history: Vec<_> = character_data.level_history.iter().flatten().collect();
+        history.sort_by_key(|advancement| advancement.level);
+        history
+    }
+}
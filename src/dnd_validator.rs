@@ -1,17 +1,37 @@
-use crate::character_model::{AbilityScores, CharacterData};
+use crate::character_model::{AbilityScores, AdvancementMode, CharacterData, Combat, FeatEffect, Improvement};
+use crate::class_catalog::ClassCatalog;
+use crate::derived_stats::DerivedStats;
+use crate::feat_catalog::FeatCatalog;
 use crate::errors::ValidationError;
 
 pub struct DndValidator {
     pub allow_rule_violations: bool,
+    /// Overrides each character's own `advancement_mode`
+    pub advancement_mode: Option<AdvancementMode>,
 }
 
 impl DndValidator {
     pub fn new(allow_rule_violations: bool) -> Self {
         Self {
             allow_rule_violations,
+            advancement_mode: None,
         }
     }
 
+    /// Treat every character as levelling by `mode`, whatever they say themselves
+    pub fn with_advancement_mode(mut self, mode: Option<AdvancementMode>) -> Self {
+        self.advancement_mode = mode;
+        self
+    }
+
+    /// The mode `character` levels by: this validator's override, then the
+    /// character's own, then XP
+    pub fn advancement_mode(&self, character: &CharacterData) -> AdvancementMode {
+        self.advancement_mode
+            .or(character.character.advancement_mode)
+            .unwrap_or_default()
+    }
+
     pub fn validate(
         &self,
         character: &CharacterData,
@@ -24,9 +44,11 @@ impl DndValidator {
         // Validate character level
         self.validate_level(character.character.level, &mut errors);
 
-        // Validate experience points if provided
+        // Validate experience points if provided; milestone levels don't follow XP
         if let Some(xp) = character.character.experience_points {
-            self.validate_experience_points(xp, character.character.level, &mut errors);
+            if self.advancement_mode(character) == AdvancementMode::Xp {
+                self.validate_experience_points(xp, character.character.level, &mut errors);
+            }
         }
 
         // Validate spells if provided
@@ -34,6 +56,16 @@ impl DndValidator {
             self.validate_spells(spells, &mut errors);
         }
 
+        // Validate death saves and exhaustion if provided
+        if let Some(combat) = &character.combat {
+            self.validate_combat_state(combat, &mut errors);
+        }
+
+        // Validate ASIs and feats taken by level if provided
+        if character.level_history.is_some() {
+            self.validate_level_history(character, &mut errors);
+        }
+
         if errors.is_empty() || self.allow_rule_violations {
             Ok(errors)
         } else {
@@ -56,7 +88,7 @@ impl DndValidator {
         ];
 
         for (name, score) in scores {
-            if score < 1 || score > 20 {
+            if !(1..=20).contains(&score) {
                 errors.push(ValidationError::AbilityScore {
                     ability: name.to_string(),
                     value: score,
@@ -67,7 +99,7 @@ impl DndValidator {
     }
 
     fn validate_level(&self, level: u8, errors: &mut Vec<ValidationError>) {
-        if level < 1 || level > 20 {
+        if !(1..=20).contains(&level) {
             errors.push(ValidationError::Level {
                 level,
                 message: "must be between 1 and 20".to_string(),
@@ -76,28 +108,8 @@ impl DndValidator {
     }
 
     fn validate_experience_points(&self, xp: u32, level: u8, errors: &mut Vec<ValidationError>) {
-        let required_xp = match level {
-            1 => 0,
-            2 => 300,
-            3 => 900,
-            4 => 2700,
-            5 => 6500,
-            6 => 14000,
-            7 => 23000,
-            8 => 34000,
-            9 => 48000,
-            10 => 64000,
-            11 => 85000,
-            12 => 100000,
-            13 => 120000,
-            14 => 140000,
-            15 => 165000,
-            16 => 195000,
-            17 => 225000,
-            18 => 265000,
-            19 => 305000,
-            20 => 355000,
-            _ => return,
+        let Some(required_xp) = Self::experience_for_level(level) else {
+            return;
         };
 
         if xp < required_xp {
@@ -154,6 +166,224 @@ impl DndValidator {
         }
     }
 
+    fn validate_combat_state(&self, combat: &Combat, errors: &mut Vec<ValidationError>) {
+        let death_saves = [
+            ("Death save successes", combat.death_save_successes),
+            ("Death save failures", combat.death_save_failures),
+        ];
+
+        for (name, count) in death_saves {
+            if let Some(count) = count {
+                if count > 3 {
+                    errors.push(ValidationError::CombatState {
+                        field: name.to_string(),
+                        value: count,
+                        message: "must be between 0 and 3".to_string(),
+                    });
+                }
+            }
+        }
+
+        if let Some(exhaustion) = combat.exhaustion_level {
+            if exhaustion > 6 {
+                errors.push(ValidationError::CombatState {
+                    field: "Exhaustion level".to_string(),
+                    value: exhaustion,
+                    message: "must be between 0 and 6".to_string(),
+                });
+            }
+        }
+    }
+
+    fn validate_level_history(&self, character: &CharacterData, errors: &mut Vec<ValidationError>) {
+        let history = character.level_history.as_deref().unwrap_or_default();
+        let custom_feats = character.feat_definitions.as_deref();
+        let mut seen_levels = Vec::new();
+
+        for advancement in history {
+            let level = advancement.level;
+            if seen_levels.contains(&level) {
+                errors.push(ValidationError::Advancement {
+                    level,
+                    message: "is listed more than once".to_string(),
+                });
+            }
+            seen_levels.push(level);
+
+            if level > character.character.level {
+                errors.push(ValidationError::Advancement {
+                    level,
+                    message: format!(
+                        "is above the character's level {}",
+                        character.character.level
+                    ),
+                });
+            }
+
+            let class_name = advancement.class.as_deref().unwrap_or(&character.character.class);
+            if let Some(class) = ClassCatalog::get(class_name) {
+                if !class.asi_levels.contains(&level) {
+                    errors.push(ValidationError::Advancement {
+                        level,
+                        message: format!("is not an Ability Score Improvement level for {}", class.name),
+                    });
+                }
+            }
+
+            match &advancement.improvement {
+                Improvement::AbilityScoreImprovement { increases } => {
+                    let total: u32 = increases.values().map(|&amount| amount as u32).sum();
+                    if total != 2 {
+                        errors.push(ValidationError::Advancement {
+                            level,
+                            message: format!("must increase ability scores by 2 in total, got {}", total),
+                        });
+                    }
+                    self.validate_ability_names(level, increases.keys(), errors);
+                }
+                Improvement::Feat { name, ability_increases } => {
+                    self.validate_ability_names(level, ability_increases.keys(), errors);
+                    let Some(feat) = FeatCatalog::resolve(name, custom_feats) else {
+                        if !ability_increases.is_empty() {
+                            errors.push(ValidationError::Advancement {
+                                level,
+                                message: format!(
+                                    "feat '{}' is not in the feat catalog; describe it in feat_definitions to check its ability increases",
+                                    name
+                                ),
+                            });
+                        }
+                        continue;
+                    };
+                    let choices: Vec<(&Vec<String>, u8)> = feat
+                        .effects
+                        .iter()
+                        .filter_map(|effect| match effect {
+                            FeatEffect::AbilityIncreaseChoice { options, amount } => Some((options, *amount)),
+                            _ => None,
+                        })
+                        .collect();
+                    for ability in ability_increases.keys() {
+                        let allowed = choices
+                            .iter()
+                            .any(|(options, _)| options.iter().any(|o| o.eq_ignore_ascii_case(ability)));
+                        if !allowed {
+                            errors.push(ValidationError::Advancement {
+                                level,
+                                message: format!("feat '{}' does not allow increasing {}", feat.name, ability),
+                            });
+                        }
+                    }
+                    if choices.is_empty() {
+                        continue;
+                    }
+                    if ability_increases.is_empty() {
+                        errors.push(ValidationError::Advancement {
+                            level,
+                            message: format!("feat '{}' requires an ability score choice", feat.name),
+                        });
+                        continue;
+                    }
+                    // Each choice raises one ability by its amount
+                    let expected: u32 = choices.iter().map(|&(_, amount)| amount as u32).sum();
+                    let total: u32 = ability_increases.values().map(|&amount| amount as u32).sum();
+                    if total != expected || ability_increases.len() > choices.len() {
+                        let got: Vec<String> = ability_increases
+                            .iter()
+                            .map(|(ability, amount)| format!("{} +{}", ability, amount))
+                            .collect();
+                        let allowed = match choices.len() {
+                            1 => format!("one ability score by {}", expected),
+                            n => format!("up to {} ability scores by {} in total", n, expected),
+                        };
+                        errors.push(ValidationError::Advancement {
+                            level,
+                            message: format!("feat '{}' increases {}, got {}", feat.name, allowed, got.join(", ")),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Improvements can't raise a score above 20
+        let effective = DerivedStats::apply_level_history(character);
+        for name in AbilityScores::NAMES {
+            let score = effective.get_ability_score(name);
+            if score > 20 {
+                errors.push(ValidationError::AbilityScore {
+                    ability: Self::capitalize(name),
+                    value: score,
+                    message: "exceeds 20 after ability score improvements".to_string(),
+                });
+            }
+        }
+    }
+
+    fn validate_ability_names<'a>(
+        &self,
+        level: u8,
+        abilities: impl Iterator<Item = &'a String>,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        for ability in abilities {
+            if !AbilityScores::NAMES.contains(&ability.to_lowercase().as_str()) {
+                errors.push(ValidationError::Advancement {
+                    level,
+                    message: format!("references unknown ability '{}'", ability),
+                });
+            }
+        }
+    }
+
+    fn capitalize(name: &str) -> String {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+
+    /// Experience needed to reach `level` (1-20)
+    pub fn experience_for_level(level: u8) -> Option<u32> {
+        let required_xp = match level {
+            1 => 0,
+            2 => 300,
+            3 => 900,
+            4 => 2700,
+            5 => 6500,
+            6 => 14000,
+            7 => 23000,
+            8 => 34000,
+            9 => 48000,
+            10 => 64000,
+            11 => 85000,
+            12 => 100000,
+            13 => 120000,
+            14 => 140000,
+            15 => 165000,
+            16 => 195000,
+            17 => 225000,
+            18 => 265000,
+            19 => 305000,
+            20 => 355000,
+            _ => return None,
+        };
+        Some(required_xp)
+    }
+
+    /// Whether a character has the experience for a higher level than their
+    /// own; never for milestone characters
+    pub fn ready_to_level_up(character: &CharacterData) -> bool {
+        if character.character.advancement_mode == Some(AdvancementMode::Milestone) {
+            return false;
+        }
+        let next_level = character.character.level.saturating_add(1);
+        match (character.character.experience_points, Self::experience_for_level(next_level)) {
+            (Some(xp), Some(required_xp)) => xp >= required_xp,
+            _ => false,
+        }
+    }
+
     pub fn proficiency_bonus(level: u8) -> u8 {
         match level {
             1..=4 => 2,
@@ -167,7 +397,7 @@ impl DndValidator {
 
     /// Validate that ability scores are within valid D&D 5e range (1-30)
     pub fn validate_ability_score_range(score: u8, ability_name: &str) -> Result<(), ValidationError> {
-        if score < 1 || score > 30 {
+        if !(1..=30).contains(&score) {
             Err(ValidationError::AbilityScore {
                 ability: ability_name.to_string(),
                 value: score,
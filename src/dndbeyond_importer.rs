@@ -0,0 +1,494 @@
+use crate::character_model::{
+    AbilityScores, Appearance, Character, CharacterData, CharacterNarrative, Combat, Currency,
+    Equipment, FeaturesTraits, OtherProficiencies, Proficiencies, Spell, Spells, SKILL_NAMES, CURRENT_SCHEMA_VERSION,
+};
+use crate::class_catalog::ClassCatalog;
+use crate::errors::ImportError;
+use serde_json::Value;
+
+/// A character read from D&D Beyond, plus everything that had no place in `CharacterData`
+#[derive(Debug, Clone)]
+pub struct DndBeyondImport {
+    pub character_data: CharacterData,
+    /// e.g. "modifier from race: Resistance (Poison)"
+    pub unmapped: Vec<String>,
+}
+
+/// Indexed by `alignmentId` - 1
+const ALIGNMENTS: [&str; 9] = [
+    "Lawful Good",
+    "Neutral Good",
+    "Chaotic Good",
+    "Lawful Neutral",
+    "Neutral",
+    "Chaotic Neutral",
+    "Lawful Evil",
+    "Neutral Evil",
+    "Chaotic Evil",
+];
+
+/// Condition ids 1-15; 4 (Exhaustion) becomes `exhaustion_level`
+const CONDITIONS: [&str; 15] = [
+    "Blinded",
+    "Charmed",
+    "Deafened",
+    "Exhaustion",
+    "Frightened",
+    "Grappled",
+    "Incapacitated",
+    "Invisible",
+    "Paralyzed",
+    "Petrified",
+    "Poisoned",
+    "Prone",
+    "Restrained",
+    "Stunned",
+    "Unconscious",
+];
+
+const ARMOR: [&str; 4] = ["light-armor", "medium-armor", "heavy-armor", "shields"];
+
+const INSTRUMENTS: [&str; 10] = [
+    "bagpipes", "drum", "dulcimer", "flute", "lute", "lyre", "horn", "pan-flute", "shawm", "viol",
+];
+
+/// Classes that choose prepared spells from their whole list each day
+const PREPARED_CASTERS: [&str; 5] = ["Artificer", "Cleric", "Druid", "Paladin", "Wizard"];
+
+/// Racial traits D&D Beyond lists that are already reflected in the stats
+const RULES_TRAITS: [&str; 6] = ["Ability Score Increase", "Age", "Alignment", "Size", "Speed", "Languages"];
+
+/// Maps a saved D&D Beyond character (the character service's JSON, with or
+/// without its `{"data": ...}` wrapper) to `CharacterData`. Ability scores
+/// include every racial, feat, class and item bonus, since D&D Beyond doesn't
+/// record the level each increase was taken at.
+pub struct DndBeyondImporter {
+    unmapped: Vec<String>,
+}
+
+impl DndBeyondImporter {
+    pub fn import(json: &Value) -> Result<DndBeyondImport, Vec<ImportError>> {
+        let data = json.get("data").filter(|data| data.is_object()).unwrap_or(json);
+        let mut errors = Vec::new();
+        for field in ["name", "stats", "classes", "race"] {
+            if data[field].is_null() {
+                errors.push(ImportError::AtField {
+                    field: field.to_string(),
+                    message: "missing; is this a D&D Beyond character file?".to_string(),
+                });
+            }
+        }
+        let classes = data["classes"].as_array().map(Vec::as_slice).unwrap_or_default();
+        if classes.is_empty() && errors.is_empty() {
+            errors.push(ImportError::AtField {
+                field: "classes".to_string(),
+                message: "the character has no class".to_string(),
+            });
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut importer = Self { unmapped: Vec::new() };
+        let character_data = importer.character(data, classes);
+        Ok(DndBeyondImport {
+            character_data,
+            unmapped: importer.unmapped,
+        })
+    }
+
+    fn character(&mut self, data: &Value, classes: &[Value]) -> CharacterData {
+        // The model holds one class: the highest level one, at the total character level
+        let primary = classes
+            .iter()
+            .max_by_key(|class| (class["level"].as_u64().unwrap_or(0), class["isStartingClass"] == true))
+            .unwrap_or(&Value::Null);
+        let level: u64 = classes.iter().filter_map(|class| class["level"].as_u64()).sum();
+        let class_name = text(&primary["definition"]["name"]).unwrap_or_default();
+        for class in classes.iter().filter(|class| !std::ptr::eq(*class, primary)) {
+            self.note(format!(
+                "multiclass: {} {} (the sheet uses {} at level {})",
+                text(&class["definition"]["name"]).unwrap_or_default(),
+                class["level"],
+                class_name,
+                level
+            ));
+        }
+
+        let modifiers = Self::modifiers(data);
+        let abilities = Self::abilities(data, &modifiers);
+        let proficiencies = self.proficiencies(&modifiers);
+
+        let background = &data["background"];
+        let character = Character {
+            name: text(&data["name"]).unwrap_or_default(),
+            class: class_name.clone(),
+            level: level.clamp(1, 20) as u8,
+            subclass: text(&primary["subclassDefinition"]["name"]),
+            race: text(&data["race"]["fullName"]).or_else(|| text(&data["race"]["baseName"])).unwrap_or_default(),
+            background: text(&background["definition"]["name"]).or_else(|| text(&background["customBackground"]["name"])),
+            player_name: None,
+            alignment: data["alignmentId"]
+                .as_u64()
+                .and_then(|id| ALIGNMENTS.get((id as usize).wrapping_sub(1)))
+                .map(|alignment| alignment.to_string()),
+            experience_points: data["currentXp"].as_u64().map(|xp| xp as u32),
+            advancement_mode: None,
+        };
+
+        CharacterData {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            combat: Some(self.combat(data, classes, &abilities, &modifiers)),
+            spells: self.spells(data, classes, primary),
+            equipment: self.equipment(data),
+            narrative: Self::narrative(data),
+            features_traits: self.features(data, classes),
+            appearance: Self::appearance(data),
+            images: None,
+            level_history: None,
+            feat_definitions: None,
+            character,
+            abilities,
+            proficiencies: Some(proficiencies),
+        }
+    }
+
+    /// Every modifier with the source it came from ("race", "feat", ...)
+    fn modifiers(data: &Value) -> Vec<(&str, &Value)> {
+        let mut all = Vec::new();
+        for (source, list) in data["modifiers"].as_object().into_iter().flatten() {
+            for modifier in list.as_array().into_iter().flatten() {
+                all.push((source.as_str(), modifier));
+            }
+        }
+        all
+    }
+
+    /// Base + bonus stats + score bonuses, unless overridden; "set" modifiers
+    /// (e.g. a Belt of Giant Strength) raise a score to their value
+    fn abilities(data: &Value, modifiers: &[(&str, &Value)]) -> AbilityScores {
+        let stat = |list: &str, id: usize| {
+            data[list]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|entry| entry["id"].as_u64() == Some(id as u64))
+                .and_then(|entry| entry["value"].as_i64())
+        };
+        let mut abilities = AbilityScores {
+            strength: 0,
+            dexterity: 0,
+            constitution: 0,
+            intelligence: 0,
+            wisdom: 0,
+            charisma: 0,
+        };
+        for (index, ability) in AbilityScores::NAMES.iter().enumerate() {
+            let score_type = &format!("{}-score", ability);
+            let mut score = stat("stats", index + 1).unwrap_or(10) + stat("bonusStats", index + 1).unwrap_or(0);
+            let score_modifiers = |kind: &'static str| {
+                modifiers
+                    .iter()
+                    .filter(move |(_, m)| m["type"] == kind && m["subType"] == score_type.as_str())
+                    .filter_map(|(_, m)| m["value"].as_i64())
+            };
+            score += score_modifiers("bonus").sum::<i64>();
+            score = score_modifiers("set").fold(score, i64::max);
+            if let Some(override_score) = stat("overrideStats", index + 1) {
+                score = override_score;
+            }
+            if let Some(slot) = abilities.get_ability_score_mut(ability) {
+                *slot = score.clamp(1, 30) as u8;
+            }
+        }
+        abilities
+    }
+
+    fn proficiencies(&mut self, modifiers: &[(&str, &Value)]) -> Proficiencies {
+        let mut proficiencies = Proficiencies {
+            saving_throws: Vec::new(),
+            skills: Vec::new(),
+            other: OtherProficiencies::default(),
+            sources: Vec::new(),
+        };
+        for (source, modifier) in modifiers {
+            let sub_type = modifier["subType"].as_str().unwrap_or_default();
+            let name = text(&modifier["friendlySubtypeName"]).unwrap_or_else(|| sub_type.replace('-', " "));
+            let skill = sub_type.replace('-', "_");
+            let is_skill = SKILL_NAMES.contains(&skill.as_str());
+            let other = &mut proficiencies.other;
+            match modifier["type"].as_str().unwrap_or_default() {
+                "proficiency" | "expertise" if sub_type.ends_with("-saving-throws") => {
+                    push_unique(&mut proficiencies.saving_throws, sub_type.trim_end_matches("-saving-throws"))
+                }
+                "proficiency" if is_skill => push_unique(&mut proficiencies.skills, &skill),
+                "expertise" if is_skill => {
+                    push_unique(&mut proficiencies.skills, &skill);
+                    self.note(format!("expertise in {} (recorded as proficiency)", name));
+                }
+                "proficiency" if ARMOR.contains(&sub_type) => push_unique(&mut other.armor, &name),
+                "proficiency" | "expertise" if Self::is_tool(sub_type) => {
+                    push_unique(&mut other.tools, &name);
+                    if modifier["type"] == "expertise" {
+                        push_unique(&mut other.tool_expertise, &name);
+                    }
+                }
+                "proficiency" => push_unique(&mut other.weapons, &name),
+                "language" => push_unique(&mut other.languages, &name),
+                "bonus" if sub_type.ends_with("-score") || sub_type == "hit-points-per-level" => {}
+                "set" if sub_type.ends_with("-score") => {}
+                _ => {
+                    let kind = text(&modifier["friendlyTypeName"]).unwrap_or_else(|| modifier["type"].as_str().unwrap_or_default().to_string());
+                    self.note(format!("modifier from {}: {} ({})", source, kind, name));
+                }
+            }
+        }
+        proficiencies
+    }
+
+    fn is_tool(sub_type: &str) -> bool {
+        ["-tools", "-kit", "-supplies", "-utensils", "-set"].iter().any(|suffix| sub_type.ends_with(suffix))
+            || sub_type.starts_with("vehicles-")
+            || INSTRUMENTS.contains(&sub_type)
+    }
+
+    fn combat(&mut self, data: &Value, classes: &[Value], abilities: &AbilityScores, modifiers: &[(&str, &Value)]) -> Combat {
+        let level: u64 = classes.iter().filter_map(|class| class["level"].as_u64()).sum();
+        let number = |value: &Value| value.as_i64();
+        let per_level: i64 = modifiers
+            .iter()
+            .filter(|(_, m)| m["type"] == "bonus" && m["subType"] == "hit-points-per-level")
+            .filter_map(|(_, m)| m["value"].as_i64())
+            .sum();
+        let maximum = number(&data["overrideHitPoints"]).unwrap_or_else(|| {
+            number(&data["baseHitPoints"]).unwrap_or(0)
+                + number(&data["bonusHitPoints"]).unwrap_or(0)
+                + (abilities.modifier("constitution") as i64 + per_level) * level as i64
+        });
+        let current = maximum - number(&data["removedHitPoints"]).unwrap_or(0);
+
+        let mut conditions = Vec::new();
+        let mut exhaustion_level = None;
+        for condition in data["conditions"].as_array().into_iter().flatten() {
+            match condition["id"].as_u64() {
+                Some(4) => exhaustion_level = condition["level"].as_u64().map(|level| level as u8),
+                Some(id @ 1..=15) => conditions.push(CONDITIONS[id as usize - 1].to_string()),
+                _ => self.note(format!("condition {}", condition["id"])),
+            }
+        }
+        self.note("armor class (D&D Beyond calculates it from equipped items; set combat.armor_class)".to_string());
+
+        let walk = data["race"]["weightSpeeds"]["normal"]["walk"].as_u64();
+        // Hit dice of the sheet's class, like the level
+        let die = classes
+            .iter()
+            .max_by_key(|class| class["level"].as_u64().unwrap_or(0))
+            .and_then(|class| class["definition"]["hitDice"].as_u64());
+        let used: u64 = classes.iter().filter_map(|class| class["hitDiceUsed"].as_u64()).sum();
+        let death_saves = &data["deathSaves"];
+        Combat {
+            armor_class: None,
+            initiative: None,
+            speed: walk.map(|speed| speed as u8),
+            hit_point_maximum: Some(maximum.max(1) as u16),
+            current_hit_points: Some(current.max(0) as u16),
+            temporary_hit_points: number(&data["temporaryHitPoints"]).filter(|hp| *hp > 0).map(|hp| hp as u16),
+            hit_dice: die.map(|die| format!("{}d{}", level.saturating_sub(used), die)),
+            hit_dice_total: die.map(|_| level as u8),
+            death_save_successes: number(&death_saves["successCount"]).filter(|n| *n > 0).map(|n| n as u8),
+            death_save_failures: number(&death_saves["failCount"]).filter(|n| *n > 0).map(|n| n as u8),
+            inspiration: data["inspiration"].as_bool().filter(|inspired| *inspired),
+            exhaustion_level,
+            conditions: (!conditions.is_empty()).then_some(conditions),
+        }
+    }
+
+    /// Spells of classes that prepare keep their prepared state; known
+    /// spells, cantrips and racial, feat and item spells are always ready
+    fn spells(&mut self, data: &Value, classes: &[Value], primary: &Value) -> Option<Spells> {
+        let class_name = text(&primary["definition"]["name"]).unwrap_or_default();
+        let ability = ClassCatalog::get(&class_name).and_then(|class| class.spellcasting_ability);
+        let mut spells = Spells {
+            spellcasting_class: ability.map(|_| class_name.clone()),
+            spellcasting_ability: ability.map(String::from),
+            ..Spells::default()
+        };
+
+        let prepares = |class_id: &Value| {
+            classes
+                .iter()
+                .find(|class| class["id"] == *class_id)
+                .and_then(|class| class["definition"]["name"].as_str())
+                .is_some_and(|name| PREPARED_CASTERS.iter().any(|caster| caster.eq_ignore_ascii_case(name)))
+        };
+        let class_spells = data["classSpells"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|entry| {
+                let always_ready = !prepares(&entry["characterClassId"]);
+                entry["spells"].as_array().into_iter().flatten().map(move |spell| (spell, always_ready))
+            });
+        let other_spells = data["spells"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, list)| list.as_array().into_iter().flatten())
+            .map(|spell| (spell, true));
+
+        let mut found = false;
+        for (spell, always_ready) in class_spells.chain(other_spells) {
+            let Some(name) = text(&spell["definition"]["name"]) else {
+                continue;
+            };
+            let level = spell["definition"]["level"].as_u64().unwrap_or(0) as u8;
+            let prepared = always_ready || level == 0 || spell["prepared"] == true || spell["alwaysPrepared"] == true;
+            let Some(list) = spells.level_mut(level) else {
+                self.note(format!("spell {} (level {})", name, level));
+                continue;
+            };
+            match list.iter_mut().find(|known| known.name.eq_ignore_ascii_case(&name)) {
+                Some(known) => known.prepared |= prepared,
+                None => list.push(Spell { name, level, prepared }),
+            }
+            found = true;
+        }
+        (found || spells.spellcasting_ability.is_some()).then_some(spells)
+    }
+
+    fn equipment(&mut self, data: &Value) -> Option<Equipment> {
+        let items: Vec<String> = data["inventory"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let name = text(&item["definition"]["name"])?;
+                Some(match item["quantity"].as_u64().unwrap_or(1) {
+                    0 | 1 => name,
+                    quantity => format!("{} ({})", name, quantity),
+                })
+            })
+            .collect();
+        for item in data["customItems"].as_array().into_iter().flatten() {
+            self.note(format!("custom item {}", text(&item["name"]).unwrap_or_default()));
+        }
+        let coins = &data["currencies"];
+        let coin = |key: &str| coins[key].as_u64().unwrap_or(0) as u32;
+        let currency = Currency { cp: coin("cp"), sp: coin("sp"), ep: coin("ep"), gp: coin("gp"), pp: coin("pp") };
+        let has_coins = [currency.cp, currency.sp, currency.ep, currency.gp, currency.pp].iter().any(|c| *c > 0);
+        let notes = &data["notes"];
+        let treasure = [text(&notes["personalPossessions"]), text(&notes["otherHoldings"])]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        (has_coins || !items.is_empty() || !treasure.is_empty()).then(|| Equipment {
+            currency: has_coins.then_some(currency),
+            items: (!items.is_empty()).then(|| items.join(", ")),
+            treasure: (!treasure.is_empty()).then(|| treasure.join("\n\n")),
+        })
+    }
+
+    /// Class features up to each class's level, racial traits, and feats
+    fn features(&mut self, data: &Value, classes: &[Value]) -> Option<FeaturesTraits> {
+        let mut features = Vec::new();
+        for class in classes {
+            let level = class["level"].as_u64().unwrap_or(0);
+            for feature in class["classFeatures"].as_array().into_iter().flatten() {
+                let definition = &feature["definition"];
+                if definition["requiredLevel"].as_u64().unwrap_or(1) <= level {
+                    if let Some(name) = text(&definition["name"]).filter(|name| !name.starts_with("Hit Points") && !name.starts_with("Proficiencies")) {
+                        push_unique(&mut features, &name);
+                    }
+                }
+            }
+        }
+        let traits: Vec<String> = data["race"]["racialTraits"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| text(&entry["definition"]["name"]))
+            .filter(|name| !RULES_TRAITS.contains(&name.as_str()))
+            .collect();
+        let mut feats = Vec::new();
+        for feat in data["feats"].as_array().into_iter().flatten() {
+            let Some(name) = text(&feat["definition"]["name"]) else {
+                continue;
+            };
+            self.note(format!(
+                "feat {} has no level in D&D Beyond; its ability increases are in the scores, add it to level_history for its other effects",
+                name
+            ));
+            feats.push(match text(&feat["definition"]["snippet"]) {
+                Some(snippet) => format!("{}: {}", name, snippet),
+                None => name,
+            });
+        }
+        let list = |entries: Vec<String>| (!entries.is_empty()).then_some(entries);
+        (!features.is_empty() || !traits.is_empty() || !feats.is_empty()).then(|| FeaturesTraits {
+            features: list(features),
+            traits: list(traits),
+            additional: list(feats),
+        })
+    }
+
+    fn narrative(data: &Value) -> Option<CharacterNarrative> {
+        let traits = &data["traits"];
+        let notes = &data["notes"];
+        let narrative = CharacterNarrative {
+            personality_traits: text(&traits["personalityTraits"]),
+            ideals: text(&traits["ideals"]),
+            bonds: text(&traits["bonds"]),
+            flaws: text(&traits["flaws"]),
+            backstory: text(&notes["backstory"]),
+            allies: text(&notes["allies"]),
+            faction_name: text(&notes["organizations"]),
+        };
+        let fields = [
+            &narrative.personality_traits,
+            &narrative.ideals,
+            &narrative.bonds,
+            &narrative.flaws,
+            &narrative.backstory,
+            &narrative.allies,
+            &narrative.faction_name,
+        ];
+        fields.iter().any(|field| field.is_some()).then_some(narrative)
+    }
+
+    fn appearance(data: &Value) -> Option<Appearance> {
+        // Age and weight are numbers in D&D Beyond
+        let field = |key: &str| match &data[key] {
+            Value::Number(number) if key == "weight" => Some(format!("{} lb", number)),
+            Value::Number(number) => Some(number.to_string()),
+            value => text(value),
+        };
+        let appearance = Appearance {
+            age: field("age"),
+            height: field("height"),
+            weight: field("weight"),
+            eyes: field("eyes"),
+            skin: field("skin"),
+            hair: field("hair"),
+        };
+        [&appearance.age, &appearance.height, &appearance.weight, &appearance.eyes, &appearance.skin, &appearance.hair]
+            .iter()
+            .any(|field| field.is_some())
+            .then_some(appearance)
+    }
+
+    fn note(&mut self, message: String) {
+        if !self.unmapped.contains(&message) {
+            self.unmapped.push(message);
+        }
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    value.as_str().map(str::trim).filter(|s| !s.is_empty()).map(String::from)
+}
+
+fn push_unique(list: &mut Vec<String>, entry: &str) {
+    if !list.iter().any(|existing| existing.eq_ignore_ascii_case(entry)) {
+        list.push(entry.to_string());
+    }
+}
@@ -35,6 +35,15 @@ pub enum ValidationError {
         ability: String,
         message: String,
     },
+    CombatState {
+        field: String,
+        value: u8,
+        message: String,
+    },
+    Advancement {
+        level: u8,
+        message: String,
+    },
 }
 
 impl fmt::Display for ValidationError {
@@ -77,6 +86,12 @@ impl fmt::Display for ValidationError {
             ValidationError::SkillCalculation { skill, ability, message } => {
                 write!(f, "Skill '{}' ({}) {}", skill, ability, message)
             }
+            ValidationError::CombatState { field, value, message } => {
+                write!(f, "{} {} {}", field, value, message)
+            }
+            ValidationError::Advancement { level, message } => {
+                write!(f, "Level {} improvement {}", level, message)
+            }
         }
     }
 }
@@ -90,6 +105,9 @@ pub enum PdfError {
     #[allow(dead_code)]
     FieldNotFound(String),
     WriteError(String),
+    ImageError(String),
+    ValidationFailed(Vec<ValidationError>),
+    Output(OutputError),
 }
 
 impl fmt::Display for PdfError {
@@ -99,9 +117,288 @@ impl fmt::Display for PdfError {
             PdfError::ParseError(msg) => write!(f, "PDF parse error: {}", msg),
             PdfError::FieldNotFound(field) => write!(f, "PDF field not found: {}", field),
             PdfError::WriteError(msg) => write!(f, "PDF write error: {}", msg),
+            PdfError::ImageError(msg) => write!(f, "PDF image error: {}", msg),
+            PdfError::Output(e) => write!(f, "{}", e),
+            PdfError::ValidationFailed(errors) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "Validation failed: {}", messages.join("; "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LevelUpError {
+    MaxLevel(u8),
+    UnknownClass(String),
+    InvalidHitPointRoll { value: u8, hit_die: u8 },
+    UnexpectedImprovement { level: u8, class: String },
+    SpellLevelTooHigh { spell: String, level: u8, max_level: u8 },
+    InvalidSpellLevel { spell: String, level: u8 },
+    NoSpellcasting { class: String, level: u8 },
+}
+
+impl fmt::Display for LevelUpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelUpError::MaxLevel(level) => {
+                write!(f, "Character is already level {} and cannot level up", level)
+            }
+            LevelUpError::UnknownClass(class) => write!(f, "Unknown class '{}'", class),
+            LevelUpError::InvalidHitPointRoll { value, hit_die } => {
+                write!(f, "Hit point roll {} is not possible on a d{}", value, hit_die)
+            }
+            LevelUpError::UnexpectedImprovement { level, class } => write!(
+                f,
+                "{} level {} does not grant an Ability Score Improvement",
+                class, level
+            ),
+            LevelUpError::SpellLevelTooHigh {
+                spell,
+                level,
+                max_level,
+            } => write!(
+                f,
+                "Spell '{}' is level {} but the highest castable spell level is {}",
+                spell, level, max_level
+            ),
+            LevelUpError::InvalidSpellLevel { spell, level } => {
+                write!(f, "Spell '{}' has level {}; spell levels run from 0 (cantrip) to 9", spell, level)
+            }
+            LevelUpError::NoSpellcasting { class, level } => {
+                write!(f, "{} level {} has no spellcasting to learn new spells with", class, level)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ResourceError {
+    NotFound(String),
+    ReadFailed(String),
+}
+
+impl fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResourceError::NotFound(uri) => write!(f, "Resource not found: {}", uri),
+            ResourceError::ReadFailed(msg) => write!(f, "Resource read failed: {}", msg),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PromptError {
+    UnknownPrompt(String),
+    MissingArgument(String),
+    InvalidArgument { argument: String, message: String },
+}
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PromptError::UnknownPrompt(name) => write!(f, "Unknown prompt: {}", name),
+            PromptError::MissingArgument(argument) => {
+                write!(f, "Missing required argument: {}", argument)
+            }
+            PromptError::InvalidArgument { argument, message } => {
+                write!(f, "Invalid argument '{}': {}", argument, message)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LoggingError {
+    InvalidLevel(String),
+    OpenFailed { path: String, message: String },
+}
+
+impl fmt::Display for LoggingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoggingError::InvalidLevel(level) => write!(
+                f,
+                "Invalid log level '{}' (expected debug, info, notice, warning, error, critical, alert or emergency)",
+                level
+            ),
+            LoggingError::OpenFailed { path, message } => {
+                write!(f, "Cannot open log file {}: {}", path, message)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ConfigError {
+    ReadFailed { path: String, message: String },
+    ParseFailed { path: String, message: String },
+    OutputNotAllowed(String),
+    OutputPathTraversal(String),
+    InputNotAllowed(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::ReadFailed { path, message } => {
+                write!(f, "Cannot read {}: {}", path, message)
+            }
+            ConfigError::ParseFailed { path, message } => {
+                write!(f, "Invalid config file {}: {}", path, message)
+            }
+            ConfigError::OutputNotAllowed(path) => write!(
+                f,
+                "Output path {} is outside the allowed output directories",
+                path
+            ),
+            ConfigError::OutputPathTraversal(path) => {
+                write!(f, "Output path {} must not contain '..'", path)
+            }
+            ConfigError::InputNotAllowed(path) => write!(
+                f,
+                "Cannot read {}: only files in the output directory or allowed_output_roots can be read",
+                path
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputError {
+    DirectoryMissing(String),
+    NotWritable { path: String, message: String },
+    WriteFailed { path: String, message: String },
+    NoFreeName(String),
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputError::DirectoryMissing(dir) => {
+                write!(f, "Output directory {} does not exist", dir)
+            }
+            OutputError::NotWritable { path, message } => {
+                write!(f, "Output directory {} is not writable: {}", path, message)
+            }
+            OutputError::WriteFailed { path, message } => {
+                write!(f, "Failed to write {}: {}", path, message)
+            }
+            OutputError::NoFreeName(path) => {
+                write!(f, "No free file name left for {}", path)
+            }
+        }
+    }
+}
+
+/// A problem found while importing a character from another format
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    /// Something wrong at a 1-based line of a text file
+    AtLine { line: usize, message: String },
+    /// Something wrong at a field of a JSON document, e.g. "items[3].system.level"
+    AtField { field: String, message: String },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::AtLine { line, message } => write!(f, "line {}: {}", line, message),
+            ImportError::AtField { field, message } => write!(f, "{}: {}", field, message),
+        }
+    }
+}
+
+/// A character_data document whose `schema_version` can't be migrated
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    NotAnObject,
+    InvalidVersion(String),
+    /// Written by a newer release than this one
+    NewerVersion { found: u64, current: u32 },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrationError::NotAnObject => write!(f, "character_data must be a JSON object"),
+            MigrationError::InvalidVersion(found) => {
+                write!(f, "schema_version must be a whole number from 1, found {}", found)
+            }
+            MigrationError::NewerVersion { found, current } => write!(
+                f,
+                "schema_version {} is newer than this release supports ({}); upgrade to read it",
+                found, current
+            ),
+        }
+    }
+}
+
+/// A problem reading or writing the local character store
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreError {
+    /// Ids are lowercase letters, digits and dashes, e.g. "erilon-silverleaf"
+    InvalidId(String),
+    NotFound(String),
+    /// Saving without an id, when the name's id holds a different character
+    IdTaken { id: String, name: String },
+    VersionNotFound { id: String, version: u32 },
+    PartyNotFound(String),
+    SessionNotFound(u32),
+    /// A session whose XP and loot were already given out
+    SessionApplied(u32),
+    /// A session record that can't be saved or applied as given
+    InvalidSession(String),
+    ReadFailed { path: String, message: String },
+    /// A stored document that no longer parses
+    Corrupt { path: String, message: String },
+    WriteFailed { path: String, message: String },
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreError::InvalidId(id) => write!(
+                f,
+                "Invalid character id '{}': use lowercase letters, digits and dashes",
+                id
+            ),
+            StoreError::NotFound(id) => write!(f, "No saved character with id '{}'", id),
+            StoreError::IdTaken { id, name } => write!(
+                f,
+                "A different {} is already saved as '{}'; give an id to save this character",
+                name, id
+            ),
+            StoreError::VersionNotFound { id, version } => {
+                write!(f, "Character '{}' has no saved version {}", id, version)
+            }
+            StoreError::PartyNotFound(id) => write!(f, "No saved party with id '{}'", id),
+            StoreError::SessionNotFound(number) => write!(f, "No recorded session {}", number),
+            StoreError::SessionApplied(number) => {
+                write!(f, "Session {} has already been applied", number)
+            }
+            StoreError::InvalidSession(message) => write!(f, "Invalid session: {}", message),
+            StoreError::ReadFailed { path, message } => {
+                write!(f, "Failed to read {}: {}", path, message)
+            }
+            StoreError::Corrupt { path, message } => {
+                write!(f, "Failed to parse {}: {}", path, message)
+            }
+            StoreError::WriteFailed { path, message } => {
+                write!(f, "Failed to write {}: {}", path, message)
+            }
         }
     }
 }
 
 impl std::error::Error for ValidationError {}
 impl std::error::Error for PdfError {}
+impl std::error::Error for LevelUpError {}
+impl std::error::Error for ResourceError {}
+impl std::error::Error for PromptError {}
+impl std::error::Error for LoggingError {}
+impl std::error::Error for ConfigError {}
+impl std::error::Error for OutputError {}
+impl std::error::Error for ImportError {}
+impl std::error::Error for MigrationError {}
+impl std::error::Error for StoreError {}
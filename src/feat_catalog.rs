@@ -0,0 +1,167 @@
+use crate::character_model::{FeatDefinition, FeatEffect, FeatSource};
+
+pub struct FeatCatalog;
+
+impl FeatCatalog {
+    /// Built-in feats: Grappler is the only feat in the SRD; the rest are
+    /// common PHB feats whose mechanics are user-supplied summaries
+    pub fn builtin() -> Vec<FeatDefinition> {
+        vec![
+            Self::feat(
+                "Grappler",
+                FeatSource::Srd,
+                Some("Strength 13 or higher"),
+                vec![
+                    Self::note("Advantage on attack rolls against a creature you are grappling"),
+                    Self::note("Can use an action to try to pin a creature grappled by you"),
+                ],
+            ),
+            Self::feat(
+                "Alert",
+                FeatSource::UserSupplied,
+                None,
+                vec![
+                    FeatEffect::InitiativeBonus { bonus: 5 },
+                    Self::note("Can't be surprised while conscious"),
+                ],
+            ),
+            Self::feat(
+                "Observant",
+                FeatSource::UserSupplied,
+                None,
+                vec![
+                    Self::choice(&["intelligence", "wisdom"]),
+                    FeatEffect::PassiveBonus { skill: "perception".to_string(), bonus: 5 },
+                    FeatEffect::PassiveBonus { skill: "investigation".to_string(), bonus: 5 },
+                ],
+            ),
+            Self::feat(
+                "Tough",
+                FeatSource::UserSupplied,
+                None,
+                vec![FeatEffect::HitPointsPerLevel { amount: 2 }],
+            ),
+            Self::feat(
+                "War Caster",
+                FeatSource::UserSupplied,
+                Some("The ability to cast at least one spell"),
+                vec![
+                    Self::note("Advantage on Constitution saves to maintain concentration"),
+                    Self::note("Somatic components while holding weapons or a shield"),
+                    Self::note("Can cast a spell as an opportunity attack"),
+                ],
+            ),
+            Self::feat(
+                "Resilient",
+                FeatSource::UserSupplied,
+                None,
+                vec![
+                    Self::choice(&[
+                        "strength",
+                        "dexterity",
+                        "constitution",
+                        "intelligence",
+                        "wisdom",
+                        "charisma",
+                    ]),
+                    Self::note("Proficiency in saving throws of the chosen ability"),
+                ],
+            ),
+            Self::feat(
+                "Lucky",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::note("3 luck points per long rest")],
+            ),
+            Self::feat(
+                "Mobile",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::note("Speed +10 ft; no opportunity attacks from creatures you attack")],
+            ),
+            Self::feat(
+                "Sentinel",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::note("Opportunity attacks reduce the target's speed to 0")],
+            ),
+            Self::feat(
+                "Sharpshooter",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::note("Ranged attacks ignore cover and long-range disadvantage; -5/+10 option")],
+            ),
+            Self::feat(
+                "Great Weapon Master",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::note("Bonus-action attack on crit or kill; -5/+10 option with heavy weapons")],
+            ),
+            Self::feat(
+                "Athlete",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::choice(&["strength", "dexterity"])],
+            ),
+            Self::feat(
+                "Actor",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::increase("charisma")],
+            ),
+            Self::feat(
+                "Durable",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::increase("constitution")],
+            ),
+            Self::feat(
+                "Keen Mind",
+                FeatSource::UserSupplied,
+                None,
+                vec![Self::increase("intelligence")],
+            ),
+        ]
+    }
+
+    /// Resolve a feat by name, preferring the character's own definitions
+    pub fn resolve(name: &str, custom: Option<&[FeatDefinition]>) -> Option<FeatDefinition> {
+        custom
+            .and_then(|feats| feats.iter().find(|feat| feat.name.eq_ignore_ascii_case(name.trim())))
+            .cloned()
+            .or_else(|| {
+                Self::builtin()
+                    .into_iter()
+                    .find(|feat| feat.name.eq_ignore_ascii_case(name.trim()))
+            })
+    }
+
+    fn feat(
+        name: &str,
+        source: FeatSource,
+        prerequisite: Option<&str>,
+        effects: Vec<FeatEffect>,
+    ) -> FeatDefinition {
+        FeatDefinition {
+            name: name.to_string(),
+            source,
+            prerequisite: prerequisite.map(str::to_string),
+            effects,
+        }
+    }
+
+    fn note(text: &str) -> FeatEffect {
+        FeatEffect::Note { text: text.to_string() }
+    }
+
+    fn increase(ability: &str) -> FeatEffect {
+        FeatEffect::AbilityIncrease { ability: ability.to_string(), amount: 1 }
+    }
+
+    fn choice(options: &[&str]) -> FeatEffect {
+        FeatEffect::AbilityIncreaseChoice {
+            options: options.iter().map(|option| option.to_string()).collect(),
+            amount: 1,
+        }
+    }
+}
@@ -4,6 +4,12 @@ pub struct FieldMapper {
     field_map: HashMap<String, String>,
 }
 
+impl Default for FieldMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FieldMapper {
     pub fn new() -> Self {
         let mut field_map = HashMap::new();
@@ -41,6 +47,15 @@ impl FieldMapper {
         field_map.insert("hit_point_maximum".to_string(), "HPMax".to_string());
         field_map.insert("current_hit_points".to_string(), "HPCurrent".to_string());
         field_map.insert("temporary_hit_points".to_string(), "HPTemp".to_string());
+        field_map.insert("inspiration".to_string(), "Inspiration".to_string());
+
+        // Death save checkboxes (successes row above failures row)
+        field_map.insert("death_save_success_1".to_string(), "Check Box 12".to_string());
+        field_map.insert("death_save_success_2".to_string(), "Check Box 13".to_string());
+        field_map.insert("death_save_success_3".to_string(), "Check Box 14".to_string());
+        field_map.insert("death_save_failure_1".to_string(), "Check Box 15".to_string());
+        field_map.insert("death_save_failure_2".to_string(), "Check Box 16".to_string());
+        field_map.insert("death_save_failure_3".to_string(), "Check Box 17".to_string());
 
         // Skills
         field_map.insert("acrobatics".to_string(), "Acrobatics".to_string());
@@ -69,6 +84,9 @@ impl FieldMapper {
         field_map.insert("hit_dice_total".to_string(), "HDTotal".to_string());
         field_map.insert("hit_dice_type".to_string(), "HD".to_string());
 
+        // Other Proficiencies & Languages
+        field_map.insert("other_proficiencies".to_string(), "ProficienciesLang".to_string());
+
         // Features & Traits
         field_map.insert("features_traits".to_string(), "Features and Traits".to_string());
 
@@ -78,6 +96,24 @@ impl FieldMapper {
         field_map.insert("bonds".to_string(), "Bonds".to_string());
         field_map.insert("flaws".to_string(), "Flaws".to_string());
 
+        // Page 2: character details
+        field_map.insert("character_name_2".to_string(), "CharacterName 2".to_string());
+        field_map.insert("age".to_string(), "Age".to_string());
+        field_map.insert("height".to_string(), "Height".to_string());
+        field_map.insert("weight".to_string(), "Weight".to_string());
+        field_map.insert("eyes".to_string(), "Eyes".to_string());
+        field_map.insert("skin".to_string(), "Skin".to_string());
+        field_map.insert("hair".to_string(), "Hair".to_string());
+        field_map.insert("backstory".to_string(), "Backstory".to_string());
+        field_map.insert("allies".to_string(), "Allies".to_string());
+        field_map.insert("faction_name".to_string(), "FactionName".to_string());
+        field_map.insert("additional_features_traits".to_string(), "Feat+Traits".to_string());
+        field_map.insert("treasure".to_string(), "Treasure".to_string());
+
+        // Page 2: image buttons
+        field_map.insert("portrait_image".to_string(), "CHARACTER IMAGE".to_string());
+        field_map.insert("faction_symbol_image".to_string(), "Faction Symbol Image".to_string());
+
         // Currency
         field_map.insert("copper_pieces".to_string(), "CP".to_string());
         field_map.insert("silver_pieces".to_string(), "SP".to_string());
@@ -250,7 +286,7 @@ impl FieldMapper {
         match level {
             0 => {
                 // Cantrips don't need preparation checkboxes
-                format!("No checkbox needed for cantrips")
+                "No checkbox needed for cantrips".to_string()
             },
             1 => {
                 // Level 1: Check Box 251, 309, 3010-3019 (12 total)
@@ -0,0 +1,596 @@
+use crate::character_model::{
+    AbilityScores, AdvancementMode, Character, CharacterData, Combat, Currency, Equipment, FeaturesTraits,
+    Improvement, LevelAdvancement, OtherProficiencies, Proficiencies, Spell, Spells, SKILL_NAMES, CURRENT_SCHEMA_VERSION,
+};
+use crate::class_catalog::ClassCatalog;
+use crate::derived_stats::DerivedStats;
+use crate::errors::ImportError;
+use crate::markdown_exporter::MarkdownExporter;
+use crate::proficiency_handler::ProficiencyHandler;
+use crate::spell_catalog::SpellCatalog;
+use crate::spell_system::SpellSystem;
+use roxmltree::{Document, Node};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Fight Club 5 proficiency id of the first skill; skills follow in `SKILL_NAMES` order
+const FIRST_SKILL_ID: usize = 100;
+
+/// Coins are written as `$` items
+const COINS: [(&str, &str); 5] = [
+    ("cp", "Copper Pieces"),
+    ("sp", "Silver Pieces"),
+    ("ep", "Electrum Pieces"),
+    ("gp", "Gold Pieces"),
+    ("pp", "Platinum Pieces"),
+];
+
+const SCHOOLS: [(&str, &str); 8] = [
+    ("A", "Abjuration"),
+    ("C", "Conjuration"),
+    ("D", "Divination"),
+    ("EN", "Enchantment"),
+    ("EV", "Evocation"),
+    ("I", "Illusion"),
+    ("N", "Necromancy"),
+    ("T", "Transmutation"),
+];
+
+/// Converts characters to and from the Fight Club 5 / Game Master 5 character
+/// XML (`<pc version="5">`). Proficiencies are encoded as ids (1-6 for saving
+/// throws, 100-117 for skills) and each level-history improvement becomes a
+/// `<feat>` whose ability `<modifier>`s the app applies to the base scores.
+pub struct FightClubExporter;
+
+impl FightClubExporter {
+    pub fn export(character_data: &CharacterData) -> String {
+        let character = &character_data.character;
+        let derived = DerivedStats::calculate(character_data);
+        let combat = character_data.combat.clone().unwrap_or_default();
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<pc version=\"5\">\n  <character>\n");
+
+        Self::element(&mut out, 2, "version", "5");
+        Self::element(&mut out, 2, "name", &character.name);
+        let abilities = &character_data.abilities;
+        let scores: String = AbilityScores::NAMES
+            .iter()
+            .map(|ability| format!("{},", abilities.get_ability_score(ability)))
+            .collect();
+        Self::element(&mut out, 2, "abilities", &scores);
+        if let Some(hp) = combat.hit_point_maximum.or(derived.hit_point_maximum) {
+            Self::element(&mut out, 2, "hpMax", &hp.to_string());
+            Self::element(&mut out, 2, "hpCurrent", &combat.current_hit_points.unwrap_or(hp).to_string());
+        }
+        // The app has no milestone setting, so it travels in an element the app ignores
+        match character.advancement_mode {
+            Some(AdvancementMode::Milestone) => Self::element(&mut out, 2, "advancement", "milestone"),
+            _ => Self::optional(&mut out, 2, "xp", character.experience_points.map(|xp| xp.to_string())),
+        }
+        Self::optional(&mut out, 2, "alignment", character.alignment.clone());
+
+        let features = character_data.features_traits.as_ref();
+        out.push_str("    <race>\n");
+        Self::element(&mut out, 3, "name", &character.race);
+        for entry in features.and_then(|f| f.traits.as_ref()).into_iter().flatten() {
+            Self::named(&mut out, 3, "trait", entry, None);
+        }
+        out.push_str("    </race>\n");
+        if let Some(background) = &character.background {
+            out.push_str("    <background>\n");
+            Self::element(&mut out, 3, "name", background);
+            out.push_str("    </background>\n");
+        }
+
+        out.push_str("    <class>\n");
+        Self::element(&mut out, 3, "name", &character.class);
+        Self::element(&mut out, 3, "level", &character.level.to_string());
+        Self::optional(&mut out, 3, "subclass", character.subclass.clone());
+        let hit_dice = combat.hit_dice.as_deref().and_then(|dice| dice.split('d').next()?.trim().parse::<u8>().ok());
+        Self::element(&mut out, 3, "hdCurrent", &hit_dice.unwrap_or(character.level).to_string());
+        for entry in features.and_then(|f| f.features.as_ref()).into_iter().flatten() {
+            Self::named(&mut out, 3, "feature", entry, None);
+        }
+        if let Some(spells) = &character_data.spells {
+            Self::spells(&mut out, character_data, spells);
+        }
+        out.push_str("    </class>\n");
+
+        for id in Self::proficiency_ids(character_data.proficiencies.as_ref()) {
+            Self::element(&mut out, 2, "proficiency", &id.to_string());
+        }
+        if let Some(proficiencies) = &character_data.proficiencies {
+            let other = ProficiencyHandler::merge(proficiencies);
+            let tools: Vec<String> = other
+                .tools
+                .iter()
+                .map(|tool| match other.tool_expertise.contains(tool) {
+                    true => format!("{} (expertise)", tool),
+                    false => tool.clone(),
+                })
+                .collect();
+            for (name, list) in [("armor", &other.armor), ("weapons", &other.weapons), ("tools", &tools), ("languages", &other.languages)] {
+                if !list.is_empty() {
+                    Self::element(&mut out, 2, name, &list.join(", "));
+                }
+            }
+        }
+
+        for advancement in character_data.level_history.iter().flatten() {
+            let increases = DerivedStats::ability_increases(&advancement.improvement, character_data.feat_definitions.as_deref());
+            let name = match &advancement.improvement {
+                Improvement::AbilityScoreImprovement { .. } => "Ability Score Improvement",
+                Improvement::Feat { name, .. } => name,
+            };
+            let taken = match &advancement.class {
+                Some(class) => format!("Taken at {} level {}.", class, advancement.level),
+                None => format!("Taken at level {}.", advancement.level),
+            };
+            let _ = writeln!(out, "    <feat>");
+            Self::element(&mut out, 3, "name", name);
+            Self::element(&mut out, 3, "text", &taken);
+            for (ability, amount) in increases {
+                let _ = writeln!(
+                    out,
+                    "      <modifier category=\"ability score\">{} +{}</modifier>",
+                    MarkdownExporter::title(&ability),
+                    amount
+                );
+            }
+            let _ = writeln!(out, "    </feat>");
+        }
+        for entry in features.and_then(|f| f.additional.as_ref()).into_iter().flatten() {
+            Self::named(&mut out, 2, "feat", entry, None);
+        }
+
+        if let Some(equipment) = &character_data.equipment {
+            for item in equipment.items.as_deref().map(Equipment::split_items).unwrap_or_default() {
+                let (name, quantity) = Self::quantity(&item);
+                Self::named(&mut out, 2, "item", name, Some(("quantity", quantity)));
+            }
+            if let Some(currency) = &equipment.currency {
+                let amounts = [currency.cp, currency.sp, currency.ep, currency.gp, currency.pp];
+                for ((_, name), amount) in COINS.iter().zip(amounts).filter(|(_, amount)| *amount > 0) {
+                    let _ = writeln!(out, "    <item>");
+                    Self::element(&mut out, 3, "name", name);
+                    Self::element(&mut out, 3, "type", "$");
+                    Self::element(&mut out, 3, "quantity", &amount.to_string());
+                    let _ = writeln!(out, "    </item>");
+                }
+            }
+        }
+
+        out.push_str("  </character>\n</pc>\n");
+        out
+    }
+
+    /// Spells by slot level, with SRD details where the catalog has the spell
+    fn spells(out: &mut String, character_data: &CharacterData, spells: &Spells) {
+        let slots = SpellSystem::calculate_spell_slots(character_data);
+        let mut counts = vec![spells.cantrips.len().to_string()];
+        counts.extend((1..=9).map(|level| slots.get(&format!("spell_slots_{}", level)).copied().unwrap_or(0).to_string()));
+        Self::element(out, 3, "slots", &counts.join(","));
+
+        for (level, list) in SpellSystem::organize_spells_by_level(spells) {
+            for spell in list {
+                let _ = writeln!(out, "      <spell>");
+                Self::element(out, 4, "name", &spell.name);
+                Self::element(out, 4, "level", &level.to_string());
+                if let Some(info) = SpellCatalog::get(&spell.name) {
+                    let school = SCHOOLS.iter().find(|(_, name)| *name == info.school).map(|(code, _)| *code);
+                    Self::optional(out, 4, "school", school.map(String::from));
+                    if info.ritual {
+                        Self::element(out, 4, "ritual", "YES");
+                    }
+                    Self::element(out, 4, "time", info.casting_time);
+                    Self::element(out, 4, "range", info.range);
+                    Self::element(out, 4, "components", info.components);
+                    Self::element(out, 4, "duration", info.duration);
+                    Self::element(out, 4, "classes", &info.classes.join(", "));
+                }
+                if level > 0 {
+                    Self::element(out, 4, "prepared", if spell.prepared { "1" } else { "0" });
+                }
+                let _ = writeln!(out, "      </spell>");
+            }
+        }
+    }
+
+    /// Saving throw ids 1-6 then skill ids 100-117
+    fn proficiency_ids(proficiencies: Option<&Proficiencies>) -> Vec<usize> {
+        let Some(proficiencies) = proficiencies else {
+            return Vec::new();
+        };
+        let has = |list: &[String], name: &str| list.iter().any(|entry| entry.eq_ignore_ascii_case(name));
+        let saves = AbilityScores::NAMES
+            .iter()
+            .enumerate()
+            .filter(|(_, ability)| has(&proficiencies.saving_throws, ability))
+            .map(|(index, _)| index + 1);
+        let skills = SKILL_NAMES
+            .iter()
+            .enumerate()
+            .filter(|(_, skill)| has(&proficiencies.skills, skill))
+            .map(|(index, _)| FIRST_SKILL_ID + index);
+        saves.chain(skills).collect()
+    }
+
+    /// `<tag><name>..</name><text>..</text></tag>` from "Name: text"
+    fn named(out: &mut String, depth: usize, tag: &str, entry: &str, quantity: Option<(&str, u32)>) {
+        let (name, text) = entry.split_once(": ").unwrap_or((entry, ""));
+        let indent = "  ".repeat(depth);
+        let _ = writeln!(out, "{}<{}>", indent, tag);
+        Self::element(out, depth + 1, "name", name);
+        if !text.is_empty() {
+            Self::element(out, depth + 1, "text", text);
+        }
+        if let Some((field, amount)) = quantity {
+            Self::element(out, depth + 1, field, &amount.to_string());
+        }
+        let _ = writeln!(out, "{}</{}>", indent, tag);
+    }
+
+    fn element(out: &mut String, depth: usize, tag: &str, text: &str) {
+        let _ = writeln!(out, "{}<{}>{}</{}>", "  ".repeat(depth), tag, Self::escape(text), tag);
+    }
+
+    fn optional(out: &mut String, depth: usize, tag: &str, text: Option<String>) {
+        if let Some(text) = text.filter(|t| !t.trim().is_empty()) {
+            Self::element(out, depth, tag, &text);
+        }
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    /// "Arrows (20)" -> ("Arrows", 20)
+    fn quantity(item: &str) -> (&str, u32) {
+        item.strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once(" ("))
+            .and_then(|(name, count)| count.parse().ok().map(|count| (name, count)))
+            .unwrap_or((item, 1))
+    }
+
+    pub fn import(xml: &str) -> Result<CharacterData, Vec<ImportError>> {
+        let document = Document::parse(xml).map_err(|e| {
+            vec![ImportError::AtLine {
+                line: e.pos().row as usize,
+                message: e.to_string(),
+            }]
+        })?;
+        let mut importer = Importer {
+            document: &document,
+            errors: Vec::new(),
+        };
+        let character_data = importer.character();
+        match (character_data, importer.errors.is_empty()) {
+            (Some(character_data), true) => Ok(character_data),
+            _ => Err(importer.errors),
+        }
+    }
+}
+
+struct Importer<'a, 'input> {
+    document: &'a Document<'input>,
+    errors: Vec<ImportError>,
+}
+
+impl<'a, 'input> Importer<'a, 'input> {
+    fn character(&mut self) -> Option<CharacterData> {
+        let root = self.document.root_element();
+        let character = match root.tag_name().name() {
+            "character" => Some(root),
+            _ => Self::child(root, "character"),
+        };
+        let Some(character) = character else {
+            self.error(root, "expected a <pc><character> document");
+            return None;
+        };
+
+        let Some(class) = Self::child(character, "class") else {
+            self.error(character, "missing <class>");
+            return None;
+        };
+        if let Some(extra) = Self::children(character, "class").nth(1) {
+            self.error(extra, "multiclass characters aren't supported; keep one <class>");
+        }
+
+        let class_name = Self::text(class, "name").unwrap_or_default();
+        let level = self.number(class, "level").unwrap_or(1);
+        let race = Self::child(character, "race");
+        let info = Character {
+            name: Self::text(character, "name").unwrap_or_default(),
+            class: class_name.clone(),
+            level,
+            subclass: Self::text(class, "subclass"),
+            race: race.and_then(|race| Self::text(race, "name")).unwrap_or_default(),
+            background: Self::child(character, "background").and_then(|b| Self::text(b, "name")),
+            player_name: None,
+            alignment: Self::text(character, "alignment"),
+            experience_points: self.number(character, "xp"),
+            advancement_mode: match Self::text(character, "advancement").as_deref() {
+                Some("milestone") => Some(AdvancementMode::Milestone),
+                _ => None,
+            },
+        };
+
+        let abilities = self.abilities(character)?;
+        let proficiencies = self.proficiencies(character);
+
+        // Improvements carry their level in the text; other feats are plain features
+        let mut level_history = Vec::new();
+        let mut features = FeaturesTraits {
+            features: None,
+            traits: None,
+            additional: None,
+        };
+        for feat in Self::children(character, "feat") {
+            match self.advancement(feat) {
+                Some(advancement) => level_history.push(advancement),
+                None => features.additional.get_or_insert_with(Vec::new).push(Self::entry(feat)),
+            }
+        }
+        for feature in class.descendants().filter(|node| node.has_tag_name("feature")) {
+            features.features.get_or_insert_with(Vec::new).push(Self::entry(feature));
+        }
+        for node in race.into_iter().chain(Self::child(character, "background")) {
+            for entry in Self::children(node, "trait") {
+                let list = match node.has_tag_name("race") {
+                    true => &mut features.traits,
+                    false => &mut features.additional,
+                };
+                list.get_or_insert_with(Vec::new).push(Self::entry(entry));
+            }
+        }
+
+        let spells = self.spells(character, &class_name);
+        let equipment = self.equipment(character);
+        let combat = Combat {
+            hit_point_maximum: self.number(character, "hpMax"),
+            current_hit_points: self.number(character, "hpCurrent"),
+            hit_dice: self
+                .number::<u8>(class, "hdCurrent")
+                .zip(ClassCatalog::get(&class_name))
+                .map(|(dice, class)| format!("{}d{}", dice, class.hit_die)),
+            ..Combat::default()
+        };
+
+        Some(CharacterData {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            character: info,
+            abilities,
+            proficiencies: Some(proficiencies),
+            combat: Some(combat),
+            spells,
+            equipment,
+            narrative: None,
+            features_traits: (features.features.is_some() || features.traits.is_some() || features.additional.is_some())
+                .then_some(features),
+            appearance: None,
+            images: None,
+            level_history: (!level_history.is_empty()).then_some(level_history),
+            feat_definitions: None,
+        })
+    }
+
+    /// "15,14,13,12,10,8," in Str, Dex, Con, Int, Wis, Cha order
+    fn abilities(&mut self, character: Node) -> Option<AbilityScores> {
+        let Some(node) = Self::child(character, "abilities") else {
+            self.error(character, "missing <abilities>");
+            return None;
+        };
+        let values: Vec<Option<u8>> = node
+            .text()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.parse().ok())
+            .collect();
+        let scores: Vec<u8> = values.iter().flatten().copied().collect();
+        if values.len() != 6 || scores.len() != 6 {
+            self.error(node, "expected six comma-separated scores (Str, Dex, Con, Int, Wis, Cha)");
+            return None;
+        }
+        Some(AbilityScores {
+            strength: scores[0],
+            dexterity: scores[1],
+            constitution: scores[2],
+            intelligence: scores[3],
+            wisdom: scores[4],
+            charisma: scores[5],
+        })
+    }
+
+    fn proficiencies(&mut self, character: Node) -> Proficiencies {
+        let mut proficiencies = Proficiencies {
+            saving_throws: Vec::new(),
+            skills: Vec::new(),
+            other: OtherProficiencies::default(),
+            sources: Vec::new(),
+        };
+        for node in character.descendants().filter(|node| node.has_tag_name("proficiency")) {
+            let text = node.text().unwrap_or_default().trim();
+            match text.parse::<usize>() {
+                Ok(id @ 1..=6) => proficiencies.saving_throws.push(AbilityScores::NAMES[id - 1].to_string()),
+                Ok(id) if (FIRST_SKILL_ID..FIRST_SKILL_ID + SKILL_NAMES.len()).contains(&id) => {
+                    proficiencies.skills.push(SKILL_NAMES[id - FIRST_SKILL_ID].to_string())
+                }
+                Ok(id) => self.error(node, &format!("unknown proficiency id {}", id)),
+                // Compendium entries list skill names instead of ids
+                Err(_) => {
+                    for name in text.split(',').map(|name| name.trim().to_lowercase().replace(' ', "_")) {
+                        if SKILL_NAMES.contains(&name.as_str()) {
+                            proficiencies.skills.push(name);
+                        }
+                    }
+                }
+            }
+        }
+        proficiencies.saving_throws.dedup();
+        proficiencies.skills.dedup();
+
+        let list = |tag: &str| -> Vec<String> {
+            Self::text(character, tag)
+                .map(|text| text.split(',').map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect())
+                .unwrap_or_default()
+        };
+        let other = &mut proficiencies.other;
+        other.armor = list("armor");
+        other.weapons = list("weapons");
+        other.languages = list("languages");
+        for tool in list("tools") {
+            match tool.strip_suffix("(expertise)") {
+                Some(name) => {
+                    other.tools.push(name.trim().to_string());
+                    other.tool_expertise.push(name.trim().to_string());
+                }
+                None => other.tools.push(tool),
+            }
+        }
+        proficiencies
+    }
+
+    /// A `<feat>` whose text says "Taken at level N." (optionally "at Fighter level N")
+    fn advancement(&mut self, feat: Node) -> Option<LevelAdvancement> {
+        let text = Self::text(feat, "text").unwrap_or_default();
+        let taken = text.strip_prefix("Taken at ")?;
+        let (class, level) = taken.split_once("level ")?;
+        let level = level.trim_end_matches('.').trim().parse().ok()?;
+        let name = Self::text(feat, "name").unwrap_or_default();
+
+        let mut increases = BTreeMap::new();
+        for modifier in Self::children(feat, "modifier") {
+            let text = modifier.text().unwrap_or_default().trim();
+            let parsed = text.rsplit_once(' ').and_then(|(ability, amount)| {
+                let ability = ability.trim().to_lowercase();
+                let amount = amount.trim_start_matches('+').parse::<u8>().ok()?;
+                AbilityScores::NAMES.contains(&ability.as_str()).then_some((ability, amount))
+            });
+            match parsed {
+                Some((ability, amount)) => *increases.entry(ability).or_insert(0) += amount,
+                None => self.error(modifier, &format!("expected an ability modifier like \"Strength +1\", found \"{}\"", text)),
+            }
+        }
+
+        let improvement = match name.eq_ignore_ascii_case("Ability Score Improvement") {
+            true => Improvement::AbilityScoreImprovement { increases },
+            false => {
+                // Fixed increases come from the feat catalog, so only the choice is kept
+                let fixed = Improvement::Feat {
+                    name: name.clone(),
+                    ability_increases: BTreeMap::new(),
+                };
+                for (ability, amount) in DerivedStats::ability_increases(&fixed, None) {
+                    if let Some(value) = increases.get_mut(&ability) {
+                        *value = value.saturating_sub(amount);
+                    }
+                }
+                increases.retain(|_, amount| *amount > 0);
+                Improvement::Feat {
+                    name,
+                    ability_increases: increases,
+                }
+            }
+        };
+        Some(LevelAdvancement {
+            level,
+            class: Some(class.trim()).filter(|c| !c.is_empty()).map(String::from),
+            improvement,
+        })
+    }
+
+    fn spells(&mut self, character: Node, class: &str) -> Option<Spells> {
+        let nodes: Vec<Node> = character.descendants().filter(|node| node.has_tag_name("spell")).collect();
+        if nodes.is_empty() {
+            return None;
+        }
+        let mut spells = Spells {
+            spellcasting_class: Some(class.to_string()),
+            spellcasting_ability: ClassCatalog::get(class)
+                .and_then(|info| info.spellcasting_ability)
+                .map(String::from),
+            ..Spells::default()
+        };
+        for node in nodes {
+            let name = Self::text(node, "name").unwrap_or_default();
+            let level = self
+                .number::<u8>(node, "level")
+                .or_else(|| SpellCatalog::get(&name).map(|info| info.level));
+            let prepared = Self::text(node, "prepared").is_some_and(|p| matches!(p.to_uppercase().as_str(), "1" | "YES" | "TRUE"));
+            match level.and_then(|level| spells.level_mut(level).map(|list| (level, list))) {
+                Some((level, list)) => list.push(Spell {
+                    name,
+                    level,
+                    prepared: prepared || level == 0,
+                }),
+                None => self.error(node, &format!("spell \"{}\" needs a <level> from 0 to 9", name)),
+            }
+        }
+        Some(spells)
+    }
+
+    fn equipment(&mut self, character: Node) -> Option<Equipment> {
+        let mut items = Vec::new();
+        let mut coins = [0u32; 5];
+        for item in Self::children(character, "item") {
+            let name = Self::text(item, "name").unwrap_or_default();
+            let quantity: u32 = self.number(item, "quantity").unwrap_or(1);
+            let coin = COINS.iter().position(|(_, coin)| coin.eq_ignore_ascii_case(&name));
+            match (Self::text(item, "type").as_deref(), coin) {
+                (Some("$"), Some(index)) => coins[index] += quantity,
+                _ if quantity > 1 => items.push(format!("{} ({})", name, quantity)),
+                _ => items.push(name),
+            }
+        }
+        let [cp, sp, ep, gp, pp] = coins;
+        let has_coins = coins.iter().any(|amount| *amount > 0);
+        (has_coins || !items.is_empty()).then(|| Equipment {
+            currency: has_coins.then_some(Currency { cp, sp, ep, gp, pp }),
+            items: (!items.is_empty()).then(|| items.join(", ")),
+            treasure: None,
+        })
+    }
+
+    /// "Name: text" from a node's `<name>` and `<text>`
+    fn entry(node: Node) -> String {
+        let name = Self::text(node, "name").unwrap_or_default();
+        match Self::text(node, "text") {
+            Some(text) => format!("{}: {}", name, text),
+            None => name,
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, parent: Node, tag: &str) -> Option<T> {
+        let node = Self::child(parent, tag)?;
+        let text = node.text().unwrap_or_default().trim();
+        let parsed = text.parse().ok();
+        if parsed.is_none() {
+            self.error(node, &format!("expected a number in <{}>, found \"{}\"", tag, text));
+        }
+        parsed
+    }
+
+    fn child<'n, 'i>(parent: Node<'n, 'i>, tag: &str) -> Option<Node<'n, 'i>> {
+        parent.children().find(|node| node.has_tag_name(tag))
+    }
+
+    fn children<'n, 'i>(parent: Node<'n, 'i>, tag: &'static str) -> impl Iterator<Item = Node<'n, 'i>> {
+        parent.children().filter(move |node| node.has_tag_name(tag))
+    }
+
+    fn text(parent: Node, tag: &str) -> Option<String> {
+        Self::child(parent, tag)
+            .and_then(|node| node.text())
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(String::from)
+    }
+
+    fn error(&mut self, node: Node, message: &str) {
+        self.errors.push(ImportError::AtLine {
+            line: self.document.text_pos_at(node.range().start).row as usize,
+            message: message.to_string(),
+        });
+    }
+}
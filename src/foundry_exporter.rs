@@ -0,0 +1,698 @@
+use crate::character_model::{
+    get_skill_ability, AbilityScores, Appearance, Character, CharacterData, CharacterImages,
+    CharacterNarrative, Combat, Currency, Equipment, FeaturesTraits, ImageSource,
+    OtherProficiencies, Proficiencies, Spell, Spells, SKILL_NAMES, CURRENT_SCHEMA_VERSION,
+};
+use crate::class_catalog::ClassCatalog;
+use crate::derived_stats::DerivedStats;
+use crate::errors::ImportError;
+use crate::proficiency_handler::ProficiencyHandler;
+use crate::race_catalog::RaceCatalog;
+use crate::spell_system::SpellSystem;
+use serde_json::{json, Map, Value};
+
+/// Module scope for the `flags` that carry what the dnd5e system has no field for
+pub const FLAGS_SCOPE: &str = "dnd-character-sheet-filler";
+/// dnd5e system version the actor is written for; Foundry migrates older data on import
+pub const SYSTEM_VERSION: &str = "3.3.1";
+
+const DEFAULT_IMAGE: &str = "icons/svg/mystery-man.svg";
+
+/// dnd5e skill ids in `SKILL_NAMES` order
+const SKILL_IDS: [&str; 18] = [
+    "acr", "ani", "arc", "ath", "dec", "his", "ins", "itm", "inv", "med", "nat", "prc", "prf",
+    "per", "rel", "slt", "ste", "sur",
+];
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("common", "Common"),
+    ("dwarvish", "Dwarvish"),
+    ("elvish", "Elvish"),
+    ("giant", "Giant"),
+    ("gnomish", "Gnomish"),
+    ("goblin", "Goblin"),
+    ("halfling", "Halfling"),
+    ("orc", "Orc"),
+    ("abyssal", "Abyssal"),
+    ("celestial", "Celestial"),
+    ("deep", "Deep Speech"),
+    ("draconic", "Draconic"),
+    ("infernal", "Infernal"),
+    ("primordial", "Primordial"),
+    ("sylvan", "Sylvan"),
+    ("undercommon", "Undercommon"),
+    ("druidic", "Druidic"),
+    ("cant", "Thieves' Cant"),
+];
+
+const ARMOR: &[(&str, &str)] = &[
+    ("lgt", "Light armor"),
+    ("med", "Medium armor"),
+    ("hvy", "Heavy armor"),
+    ("shl", "Shields"),
+];
+
+const WEAPONS: &[(&str, &str)] = &[("sim", "Simple weapons"), ("mar", "Martial weapons")];
+
+const TOOLS: &[(&str, &str)] = &[
+    ("alchemist", "Alchemist's supplies"),
+    ("bagpipes", "Bagpipes"),
+    ("brewer", "Brewer's supplies"),
+    ("calligrapher", "Calligrapher's supplies"),
+    ("card", "Playing card set"),
+    ("carpenter", "Carpenter's tools"),
+    ("cartographer", "Cartographer's tools"),
+    ("chess", "Dragonchess set"),
+    ("cobbler", "Cobbler's tools"),
+    ("cook", "Cook's utensils"),
+    ("dice", "Dice set"),
+    ("disg", "Disguise kit"),
+    ("drum", "Drum"),
+    ("dulcimer", "Dulcimer"),
+    ("flute", "Flute"),
+    ("forg", "Forgery kit"),
+    ("glassblower", "Glassblower's tools"),
+    ("herb", "Herbalism kit"),
+    ("horn", "Horn"),
+    ("jeweler", "Jeweler's tools"),
+    ("leatherworker", "Leatherworker's tools"),
+    ("lute", "Lute"),
+    ("lyre", "Lyre"),
+    ("mason", "Mason's tools"),
+    ("navg", "Navigator's tools"),
+    ("painter", "Painter's supplies"),
+    ("panflute", "Pan flute"),
+    ("pois", "Poisoner's kit"),
+    ("potter", "Potter's tools"),
+    ("shawm", "Shawm"),
+    ("smith", "Smith's tools"),
+    ("thief", "Thieves' tools"),
+    ("tinker", "Tinker's tools"),
+    ("viol", "Viol"),
+    ("weaver", "Weaver's tools"),
+    ("woodcarver", "Woodcarver's tools"),
+];
+
+/// Inventory item types read back into `equipment.items`
+const INVENTORY_TYPES: [&str; 6] = ["weapon", "equipment", "consumable", "tool", "container", "loot"];
+
+/// Converts characters to and from Foundry VTT dnd5e actor JSON, the file
+/// format behind an actor's "Import Data" and "Export Data". Stats are written
+/// after level-history increases; the base scores and level history travel in
+/// `flags` so an exported actor reads back unchanged.
+pub struct FoundryExporter;
+
+impl FoundryExporter {
+    pub fn export(character_data: &CharacterData) -> Value {
+        let derived = DerivedStats::calculate(character_data);
+        let character = &character_data.character;
+        let combat = character_data.combat.clone().unwrap_or_default();
+        let proficiencies = character_data.proficiencies.as_ref();
+        let proficient = |list: fn(&Proficiencies) -> &Vec<String>, name: &str| {
+            proficiencies.is_some_and(|p| list(p).iter().any(|entry| entry.eq_ignore_ascii_case(name)))
+        };
+
+        let mut abilities = Map::new();
+        for ability in AbilityScores::NAMES {
+            let saves = proficient(|p| &p.saving_throws, ability);
+            abilities.insert(
+                Self::ability_id(ability).to_string(),
+                json!({"value": derived.abilities.get_ability_score(ability), "proficient": saves as u8}),
+            );
+        }
+        let mut skills = Map::new();
+        for (skill, id) in SKILL_NAMES.iter().zip(SKILL_IDS) {
+            skills.insert(
+                id.to_string(),
+                json!({"value": proficient(|p| &p.skills, skill) as u8, "ability": Self::ability_id(get_skill_ability(skill))}),
+            );
+        }
+
+        let hit_point_maximum = combat.hit_point_maximum.or(derived.hit_point_maximum);
+        let dexterity = derived.abilities.modifier("dexterity");
+        let initiative_bonus = combat.initiative.unwrap_or(derived.initiative) - dexterity;
+        let armor_class = match combat.armor_class {
+            Some(ac) => json!({"calc": "flat", "flat": ac}),
+            None => json!({"calc": "default", "flat": null}),
+        };
+        let spellcasting = character_data
+            .spells
+            .as_ref()
+            .and_then(|spells| spells.spellcasting_ability.as_deref())
+            .map(|ability| Self::ability_id(&ability.to_lowercase()).to_string())
+            .unwrap_or_default();
+
+        let other = proficiencies.map(ProficiencyHandler::merge).unwrap_or_default();
+        let (tools, other_tools) = Self::tools(&other);
+        let narrative = character_data.narrative.clone().unwrap_or(CharacterNarrative {
+            personality_traits: None,
+            ideals: None,
+            bonds: None,
+            flaws: None,
+            backstory: None,
+            allies: None,
+            faction_name: None,
+        });
+        let appearance = character_data.appearance.clone().unwrap_or_default();
+        let currency = character_data.equipment.as_ref().and_then(|e| e.currency.clone());
+
+        let mut spell_slots = Map::new();
+        let slots = SpellSystem::calculate_spell_slots(character_data);
+        for level in 1..=9 {
+            let available = slots.get(&format!("spell_slots_{}", level)).copied().unwrap_or(0);
+            spell_slots.insert(format!("spell{}", level), json!({"value": available, "override": null}));
+        }
+
+        let mut flags = Map::new();
+        flags.insert("abilities".into(), json!(character_data.abilities));
+        let mut flag = |key: &str, value: Value| {
+            if !value.is_null() {
+                flags.insert(key.to_string(), value);
+            }
+        };
+        flag("level_history", json!(character_data.level_history));
+        flag("feat_definitions", json!(character_data.feat_definitions));
+        flag("player_name", json!(character.player_name));
+        flag("advancement_mode", json!(character.advancement_mode));
+        flag("spellcasting_class", json!(character_data.spells.as_ref().and_then(|s| s.spellcasting_class.clone())));
+        flag("hit_dice", json!(combat.hit_dice));
+        flag("hit_dice_total", json!(combat.hit_dice_total));
+        flag("treasure", json!(character_data.equipment.as_ref().and_then(|e| e.treasure.clone())));
+        flag("allies", json!(narrative.allies));
+        flag("faction_name", json!(narrative.faction_name));
+        if !other_tools.is_empty() {
+            flag("tools", json!(other_tools));
+        }
+
+        let image = match character_data.images.as_ref().and_then(|images| images.portrait.as_ref()) {
+            Some(ImageSource::Path(path)) => path.clone(),
+            _ => DEFAULT_IMAGE.to_string(),
+        };
+        let size = match RaceCatalog::get(&character.race).map(|race| race.size) {
+            Some("Small") => "sm",
+            _ => "med",
+        };
+
+        json!({
+            "name": character.name,
+            "type": "character",
+            "img": image,
+            "system": {
+                "abilities": abilities,
+                "attributes": {
+                    "ac": armor_class,
+                    "hp": {
+                        "value": combat.current_hit_points.or(hit_point_maximum),
+                        "max": hit_point_maximum,
+                        "temp": combat.temporary_hit_points.unwrap_or(0),
+                        "tempmax": 0
+                    },
+                    "init": {"ability": "", "bonus": Self::signed(initiative_bonus)},
+                    "movement": {"walk": combat.speed.unwrap_or(30), "units": "ft"},
+                    "death": {
+                        "success": combat.death_save_successes.unwrap_or(0),
+                        "failure": combat.death_save_failures.unwrap_or(0)
+                    },
+                    "exhaustion": combat.exhaustion_level.unwrap_or(0),
+                    "inspiration": combat.inspiration.unwrap_or(false),
+                    "spellcasting": spellcasting
+                },
+                "details": {
+                    "alignment": character.alignment.clone().unwrap_or_default(),
+                    "xp": {"value": character.experience_points.unwrap_or(0)},
+                    "biography": {"value": Self::html(narrative.backstory.as_deref()), "public": ""},
+                    "trait": narrative.personality_traits.unwrap_or_default(),
+                    "ideal": narrative.ideals.unwrap_or_default(),
+                    "bond": narrative.bonds.unwrap_or_default(),
+                    "flaw": narrative.flaws.unwrap_or_default(),
+                    "age": appearance.age.unwrap_or_default(),
+                    "height": appearance.height.unwrap_or_default(),
+                    "weight": appearance.weight.unwrap_or_default(),
+                    "eyes": appearance.eyes.unwrap_or_default(),
+                    "skin": appearance.skin.unwrap_or_default(),
+                    "hair": appearance.hair.unwrap_or_default()
+                },
+                "traits": {
+                    "size": size,
+                    "languages": Self::trait_list(&other.languages, LANGUAGES),
+                    "armorProf": Self::trait_list(&other.armor, ARMOR),
+                    "weaponProf": Self::trait_list(&other.weapons, WEAPONS)
+                },
+                "tools": tools,
+                "skills": skills,
+                "currency": currency.map(|c| json!({"pp": c.pp, "gp": c.gp, "ep": c.ep, "sp": c.sp, "cp": c.cp}))
+                    .unwrap_or_else(|| json!({"pp": 0, "gp": 0, "ep": 0, "sp": 0, "cp": 0})),
+                "spells": spell_slots
+            },
+            "items": Self::items(character_data, &derived),
+            "effects": Self::conditions(&combat),
+            "flags": {FLAGS_SCOPE: flags},
+            "_stats": {"systemId": "dnd5e", "systemVersion": SYSTEM_VERSION}
+        })
+    }
+
+    fn items(character_data: &CharacterData, derived: &DerivedStats) -> Vec<Value> {
+        let character = &character_data.character;
+        let class_id = Self::identifier(&character.class);
+        let hit_die = ClassCatalog::get(&character.class).map(|class| format!("d{}", class.hit_die));
+        let mut items = vec![json!({
+            "name": character.class,
+            "type": "class",
+            "system": {"identifier": class_id, "levels": character.level, "hitDice": hit_die.unwrap_or_else(|| "d8".into()), "hitDiceUsed": 0}
+        })];
+        if let Some(subclass) = &character.subclass {
+            items.push(json!({
+                "name": subclass,
+                "type": "subclass",
+                "system": {"identifier": Self::identifier(subclass), "classIdentifier": class_id}
+            }));
+        }
+        items.push(json!({"name": character.race, "type": "race", "system": {}}));
+        if let Some(background) = &character.background {
+            items.push(json!({"name": background, "type": "background", "system": {}}));
+        }
+
+        // Features and racial traits are feat items; their kind tells them apart on the sheet
+        if let Some(features) = &character_data.features_traits {
+            for (list, kind) in [(&features.features, "class"), (&features.traits, "race"), (&features.additional, "")] {
+                for entry in list.iter().flatten() {
+                    let (name, description) = entry.split_once(": ").unwrap_or((entry.as_str(), ""));
+                    items.push(json!({
+                        "name": name,
+                        "type": "feat",
+                        "system": {"type": {"value": kind}, "description": {"value": Self::html(Some(description))}}
+                    }));
+                }
+            }
+        }
+        for note in derived.feat_notes() {
+            let (name, description) = note.split_once(": ").unwrap_or((note.as_str(), ""));
+            items.push(json!({
+                "name": name,
+                "type": "feat",
+                "system": {"type": {"value": "feat"}, "description": {"value": Self::html(Some(description))}}
+            }));
+        }
+
+        if let Some(spells) = &character_data.spells {
+            let mode = match character.class.eq_ignore_ascii_case("warlock") {
+                true => "pact",
+                false => "prepared",
+            };
+            for (level, list) in SpellSystem::organize_spells_by_level(spells) {
+                for spell in list {
+                    items.push(json!({
+                        "name": spell.name,
+                        "type": "spell",
+                        "system": {"level": level, "preparation": {"mode": mode, "prepared": spell.prepared || level == 0}}
+                    }));
+                }
+            }
+        }
+
+        if let Some(list) = character_data.equipment.as_ref().and_then(|e| e.items.as_deref()) {
+            for item in Equipment::split_items(list) {
+                let (name, quantity) = Self::quantity(&item);
+                items.push(json!({"name": name, "type": "loot", "system": {"quantity": quantity}}));
+            }
+        }
+        items
+    }
+
+    /// Active conditions as status effects
+    fn conditions(combat: &Combat) -> Vec<Value> {
+        combat
+            .conditions
+            .iter()
+            .flatten()
+            .map(|condition| json!({"name": condition, "statuses": [condition.to_lowercase()], "disabled": false}))
+            .collect()
+    }
+
+    /// Known tools as dnd5e tool ids (value 2 for expertise), and the rest
+    fn tools(other: &OtherProficiencies) -> (Map<String, Value>, Vec<String>) {
+        let mut tools = Map::new();
+        let mut unknown = Vec::new();
+        for tool in &other.tools {
+            let expertise = other.tool_expertise.iter().any(|t| t.eq_ignore_ascii_case(tool));
+            match TOOLS.iter().find(|(_, name)| name.eq_ignore_ascii_case(tool)) {
+                Some((id, _)) => {
+                    tools.insert(id.to_string(), json!({"value": if expertise { 2 } else { 1 }, "ability": "int"}));
+                }
+                None => unknown.push(tool.clone()),
+            }
+        }
+        (tools, unknown)
+    }
+
+    /// Standard entries by id, anything else in the `;`-separated custom text
+    fn trait_list(entries: &[String], known: &[(&str, &str)]) -> Value {
+        let mut value = Vec::new();
+        let mut custom = Vec::new();
+        for entry in entries {
+            match known.iter().find(|(_, name)| name.eq_ignore_ascii_case(entry)) {
+                Some((id, _)) => value.push(*id),
+                None => custom.push(entry.as_str()),
+            }
+        }
+        json!({"value": value, "custom": custom.join(";")})
+    }
+
+    pub fn import(actor: &Value) -> Result<CharacterData, Vec<ImportError>> {
+        let mut errors = Vec::new();
+        if actor["type"] != "character" {
+            Self::error(&mut errors, "type", "expected a \"character\" actor");
+        }
+        let system = &actor["system"];
+        let flags = &actor["flags"][FLAGS_SCOPE];
+        let items: &[Value] = actor["items"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let items_of = |kind: &'static str| items.iter().filter(move |item| item["type"] == kind);
+        let text = |value: &Value| value.as_str().map(str::trim).filter(|s| !s.is_empty()).map(String::from);
+        let flag_text = |key: &str| text(&flags[key]);
+
+        let Some(class) = items_of("class").next() else {
+            Self::error(&mut errors, "items", "no class item");
+            return Err(errors);
+        };
+        let character = Character {
+            name: text(&actor["name"]).unwrap_or_default(),
+            class: text(&class["name"]).unwrap_or_default(),
+            level: class["system"]["levels"].as_u64().unwrap_or(1) as u8,
+            subclass: items_of("subclass").next().and_then(|item| text(&item["name"])),
+            race: items_of("race").next().and_then(|item| text(&item["name"]))
+                .or_else(|| text(&system["details"]["race"]))
+                .unwrap_or_default(),
+            background: items_of("background").next().and_then(|item| text(&item["name"]))
+                .or_else(|| text(&system["details"]["background"])),
+            player_name: flag_text("player_name"),
+            alignment: text(&system["details"]["alignment"]),
+            experience_points: system["details"]["xp"]["value"].as_u64().filter(|xp| *xp > 0).map(|xp| xp as u32),
+            advancement_mode: serde_json::from_value(flags["advancement_mode"].clone()).ok(),
+        };
+
+        // Scores as Foundry shows them, unless the base scores travelled in flags
+        let mut abilities = AbilityScores {
+            strength: 0,
+            dexterity: 0,
+            constitution: 0,
+            intelligence: 0,
+            wisdom: 0,
+            charisma: 0,
+        };
+        let mut saving_throws = Vec::new();
+        for ability in AbilityScores::NAMES {
+            let entry = &system["abilities"][Self::ability_id(ability)];
+            match entry["value"].as_u64() {
+                Some(score) => {
+                    if let Some(slot) = abilities.get_ability_score_mut(ability) {
+                        *slot = score as u8;
+                    }
+                }
+                None => Self::error(&mut errors, &format!("system.abilities.{}.value", Self::ability_id(ability)), "expected a score"),
+            }
+            if entry["proficient"].as_f64().unwrap_or(0.0) >= 1.0 {
+                saving_throws.push(ability.to_string());
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let level_history = serde_json::from_value(flags["level_history"].clone()).ok();
+        if let Ok(base) = serde_json::from_value(flags["abilities"].clone()) {
+            abilities = base;
+        }
+
+        let skills = SKILL_NAMES
+            .iter()
+            .zip(SKILL_IDS)
+            .filter(|(_, id)| system["skills"][*id]["value"].as_f64().unwrap_or(0.0) >= 1.0)
+            .map(|(skill, _)| skill.to_string())
+            .collect();
+        let traits = &system["traits"];
+        let mut other = OtherProficiencies {
+            languages: Self::trait_names(&traits["languages"], LANGUAGES),
+            armor: Self::trait_names(&traits["armorProf"], ARMOR),
+            weapons: Self::trait_names(&traits["weaponProf"], WEAPONS),
+            tools: Vec::new(),
+            tool_expertise: Vec::new(),
+        };
+        for (id, tool) in system["tools"].as_object().into_iter().flatten() {
+            let name = TOOLS.iter().find(|(key, _)| key == id).map(|(_, name)| name.to_string()).unwrap_or_else(|| id.clone());
+            let value = tool["value"].as_f64().unwrap_or(0.0);
+            if value >= 1.0 {
+                other.tools.push(name.clone());
+            }
+            if value >= 2.0 {
+                other.tool_expertise.push(name);
+            }
+        }
+        if let Some(extra) = flags["tools"].as_array() {
+            other.tools.extend(extra.iter().filter_map(|tool| tool.as_str().map(String::from)));
+        }
+
+        let attributes = &system["attributes"];
+        let number = |value: &Value| value.as_u64();
+        let mut combat = Combat {
+            armor_class: (attributes["ac"]["calc"] == "flat").then(|| number(&attributes["ac"]["flat"])).flatten().map(|ac| ac as u8),
+            initiative: None,
+            speed: number(&attributes["movement"]["walk"]).map(|speed| speed as u8),
+            hit_point_maximum: number(&attributes["hp"]["max"]).map(|hp| hp as u16),
+            current_hit_points: number(&attributes["hp"]["value"]).map(|hp| hp as u16),
+            temporary_hit_points: number(&attributes["hp"]["temp"]).filter(|hp| *hp > 0).map(|hp| hp as u16),
+            hit_dice: flag_text("hit_dice"),
+            hit_dice_total: number(&flags["hit_dice_total"]).map(|dice| dice as u8),
+            death_save_successes: number(&attributes["death"]["success"]).filter(|n| *n > 0).map(|n| n as u8),
+            death_save_failures: number(&attributes["death"]["failure"]).filter(|n| *n > 0).map(|n| n as u8),
+            inspiration: attributes["inspiration"].as_bool().filter(|inspired| *inspired),
+            exhaustion_level: number(&attributes["exhaustion"]).filter(|n| *n > 0).map(|n| n as u8),
+            conditions: None,
+        };
+        let conditions: Vec<String> = actor["effects"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|effect| effect["statuses"].as_array().is_some_and(|s| !s.is_empty()))
+            .filter_map(|effect| text(&effect["name"]).or_else(|| text(&effect["label"])))
+            .collect();
+        if !conditions.is_empty() {
+            combat.conditions = Some(conditions);
+        }
+
+        let mut spells = Spells {
+            spellcasting_class: flag_text("spellcasting_class"),
+            spellcasting_ability: AbilityScores::NAMES
+                .iter()
+                .find(|ability| attributes["spellcasting"] == Self::ability_id(ability))
+                .map(|ability| capitalize(ability)),
+            ..Spells::default()
+        };
+        for (index, item) in items.iter().enumerate() {
+            if item["type"] != "spell" {
+                continue;
+            }
+            let level = item["system"]["level"].as_u64().unwrap_or(10) as u8;
+            let preparation = &item["system"]["preparation"];
+            let prepared = level == 0 || preparation["prepared"] == true || preparation["mode"] == "always";
+            match spells.level_mut(level) {
+                Some(list) => list.push(Spell {
+                    name: text(&item["name"]).unwrap_or_default(),
+                    level,
+                    prepared,
+                }),
+                None => Self::error(&mut errors, &format!("items[{}].system.level", index), "expected a spell level from 0 to 9"),
+            }
+        }
+        let has_spells = SpellSystem::organize_spells_by_level(&spells).iter().any(|(_, list)| !list.is_empty());
+
+        // Feats come back from level_history when it travelled in flags
+        let mut features = FeaturesTraits {
+            features: None,
+            traits: None,
+            additional: None,
+        };
+        for item in items_of("feat") {
+            let name = text(&item["name"]).unwrap_or_default();
+            let entry = match Self::plain_text(item["system"]["description"]["value"].as_str()) {
+                Some(description) => format!("{}: {}", name, description),
+                None => name,
+            };
+            let list = match item["system"]["type"]["value"].as_str().unwrap_or_default() {
+                "class" => &mut features.features,
+                "race" => &mut features.traits,
+                "feat" if level_history.is_some() => continue,
+                _ => &mut features.additional,
+            };
+            list.get_or_insert_with(Vec::new).push(entry);
+        }
+
+        let inventory: Vec<String> = items
+            .iter()
+            .filter(|item| INVENTORY_TYPES.iter().any(|kind| item["type"] == *kind))
+            .map(|item| {
+                let name = text(&item["name"]).unwrap_or_default();
+                match item["system"]["quantity"].as_u64().unwrap_or(1) {
+                    0 | 1 => name,
+                    quantity => format!("{} ({})", name, quantity),
+                }
+            })
+            .collect();
+        let coins = &system["currency"];
+        let coin = |key: &str| coins[key].as_u64().unwrap_or(0) as u32;
+        let currency = Currency { cp: coin("cp"), sp: coin("sp"), ep: coin("ep"), gp: coin("gp"), pp: coin("pp") };
+        let has_coins = currency.cp + currency.sp + currency.ep + currency.gp + currency.pp > 0;
+        let treasure = flag_text("treasure");
+        let equipment = (has_coins || !inventory.is_empty() || treasure.is_some()).then(|| Equipment {
+            currency: has_coins.then_some(currency),
+            items: (!inventory.is_empty()).then(|| inventory.join(", ")),
+            treasure,
+        });
+
+        let details = &system["details"];
+        let narrative = CharacterNarrative {
+            personality_traits: text(&details["trait"]),
+            ideals: text(&details["ideal"]),
+            bonds: text(&details["bond"]),
+            flaws: text(&details["flaw"]),
+            backstory: Self::plain_text(details["biography"]["value"].as_str()),
+            allies: flag_text("allies"),
+            faction_name: flag_text("faction_name"),
+        };
+        let appearance = Appearance {
+            age: text(&details["age"]),
+            height: text(&details["height"]),
+            weight: text(&details["weight"]),
+            eyes: text(&details["eyes"]),
+            skin: text(&details["skin"]),
+            hair: text(&details["hair"]),
+        };
+        let portrait = text(&actor["img"]).filter(|img| img != DEFAULT_IMAGE);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let mut character_data = CharacterData {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            character,
+            abilities,
+            proficiencies: Some(Proficiencies {
+                saving_throws,
+                skills,
+                other,
+                sources: Vec::new(),
+            }),
+            combat: None,
+            spells: (has_spells || spells.spellcasting_ability.is_some()).then_some(spells),
+            equipment,
+            narrative: serde_json::to_value(&narrative).is_ok_and(|v| v.as_object().is_some_and(|o| o.values().any(|v| !v.is_null())))
+                .then_some(narrative),
+            features_traits: (features.features.is_some() || features.traits.is_some() || features.additional.is_some())
+                .then_some(features),
+            appearance: serde_json::to_value(&appearance).is_ok_and(|v| v.as_object().is_some_and(|o| o.values().any(|v| !v.is_null())))
+                .then_some(appearance),
+            images: portrait.map(|path| CharacterImages {
+                portrait: Some(ImageSource::Path(path)),
+                faction_symbol: None,
+            }),
+            level_history,
+            feat_definitions: serde_json::from_value(flags["feat_definitions"].clone()).ok(),
+        };
+
+        // Initiative is Dexterity plus a bonus; keep it only where it differs from the derived value
+        let derived = DerivedStats::calculate(&character_data);
+        let bonus = attributes["init"]["bonus"].as_str().and_then(|b| b.trim().trim_start_matches('+').parse::<i8>().ok())
+            .or_else(|| attributes["init"]["bonus"].as_i64().map(|b| b as i8))
+            .unwrap_or(0);
+        let initiative = derived.abilities.modifier("dexterity") + bonus;
+        if initiative != derived.initiative {
+            combat.initiative = Some(initiative);
+        }
+        character_data.combat = Some(combat);
+        Ok(character_data)
+    }
+
+    fn error(errors: &mut Vec<ImportError>, field: &str, message: &str) {
+        errors.push(ImportError::AtField {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn trait_names(list: &Value, known: &[(&str, &str)]) -> Vec<String> {
+        let ids = list["value"].as_array().into_iter().flatten().filter_map(Value::as_str);
+        let mut names: Vec<String> = ids
+            .map(|id| known.iter().find(|(key, _)| *key == id).map(|(_, name)| name.to_string()).unwrap_or_else(|| capitalize(id)))
+            .collect();
+        let custom = list["custom"].as_str().unwrap_or_default();
+        names.extend(custom.split(';').map(str::trim).filter(|c| !c.is_empty()).map(String::from));
+        names
+    }
+
+    /// "strength" -> "str"
+    fn ability_id(ability: &str) -> &str {
+        ability.get(..3).unwrap_or(ability)
+    }
+
+    /// "Eldritch Knight" -> "eldritch-knight"
+    fn identifier(name: &str) -> String {
+        name.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect::<Vec<_>>().join("-")
+    }
+
+    fn signed(value: i8) -> String {
+        match value {
+            0 => String::new(),
+            v if v > 0 => format!("+{}", v),
+            v => v.to_string(),
+        }
+    }
+
+    /// "Arrows (20)" -> ("Arrows", 20)
+    fn quantity(item: &str) -> (&str, u32) {
+        item.strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once(" ("))
+            .and_then(|(name, count)| count.parse().ok().map(|count| (name, count)))
+            .unwrap_or((item, 1))
+    }
+
+    /// Paragraphs as escaped `<p>` elements
+    fn html(text: Option<&str>) -> String {
+        let text = text.map(str::trim).unwrap_or_default();
+        if text.is_empty() {
+            return String::new();
+        }
+        text.split("\n\n")
+            .map(|paragraph| {
+                let escaped = paragraph.trim().replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+                format!("<p>{}</p>", escaped.replace('\n', "<br>"))
+            })
+            .collect()
+    }
+
+    /// Text from Foundry's HTML fields: paragraphs become blank lines, other tags are dropped
+    fn plain_text(html: Option<&str>) -> Option<String> {
+        let html = html?
+            .replace("</p>", "\n\n")
+            .replace("<br>", "\n")
+            .replace("<br />", "\n");
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in html.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' if in_tag => in_tag = false,
+                _ if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        let text = text.replace("&lt;", "<").replace("&gt;", ">").replace("&nbsp;", " ").replace("&amp;", "&");
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,502 @@
+use crate::character_model::{get_skill_ability, AbilityScores, AdvancementMode, CharacterData, Equipment, SKILL_NAMES};
+use crate::derived_stats::DerivedStats;
+use crate::markdown_exporter::{MarkdownExporter, NOT_PROFICIENT, PROFICIENT};
+use crate::proficiency_handler::ProficiencyHandler;
+use crate::spell_system::SpellSystem;
+use std::fmt::Write;
+
+/// Print and screen styles; the page needs no other assets
+const STYLESHEET: &str = r#"
+:root { --ink: #1f1a17; --muted: #6b625b; --line: #b9ad9f; --panel: #f7f3ee; --accent: #7a1f1f; }
+* { box-sizing: border-box; }
+body { margin: 0; background: #e9e4dc; color: var(--ink); font: 14px/1.4 Georgia, "Times New Roman", serif; }
+.sheet, .party { max-width: 60rem; margin: 1.5rem auto; padding: 1.5rem 2rem; background: #fff; box-shadow: 0 1px 4px rgba(0, 0, 0, 0.2); }
+h1, h2, h3 { font-family: "Palatino Linotype", Palatino, Georgia, serif; color: var(--accent); margin: 0 0 0.5rem; }
+h1 { font-size: 2rem; }
+h2 { font-size: 1.1rem; text-transform: uppercase; letter-spacing: 0.05em; border-bottom: 2px solid var(--accent); padding-bottom: 0.15rem; }
+h3 { font-size: 1rem; margin-top: 0.75rem; }
+section { margin-bottom: 1rem; break-inside: avoid; }
+.details { display: grid; grid-template-columns: repeat(auto-fill, minmax(11rem, 1fr)); gap: 0.25rem 1rem; margin: 0 0 1rem; }
+.details div { border-bottom: 1px solid var(--line); }
+.details dt { font-size: 0.7rem; text-transform: uppercase; color: var(--muted); }
+.details dd { margin: 0; }
+.columns { display: grid; grid-template-columns: 9rem 1fr 1fr; gap: 1rem; align-items: start; }
+.abilities { display: flex; flex-direction: column; gap: 0.5rem; }
+.ability { border: 2px solid var(--ink); border-radius: 0.5rem; text-align: center; padding: 0.25rem; background: var(--panel); }
+.ability .label { display: block; font-size: 0.7rem; text-transform: uppercase; letter-spacing: 0.05em; }
+.ability .modifier { display: block; font-size: 1.6rem; font-weight: bold; }
+.ability .score { display: inline-block; min-width: 2.5rem; border: 1px solid var(--ink); border-radius: 1rem; background: #fff; }
+.stats { display: grid; grid-template-columns: repeat(3, 1fr); gap: 0.5rem; margin-bottom: 0.75rem; }
+.stat { border: 1px solid var(--ink); border-radius: 0.35rem; text-align: center; padding: 0.25rem; background: var(--panel); }
+.stat .value { display: block; font-size: 1.3rem; font-weight: bold; }
+.stat .label { display: block; font-size: 0.65rem; text-transform: uppercase; color: var(--muted); }
+ul.checks, ul.spells { list-style: none; margin: 0; padding: 0; }
+ul.checks li, ul.spells li { display: flex; gap: 0.4rem; border-bottom: 1px dotted var(--line); }
+ul.checks .bonus { min-width: 2rem; text-align: right; font-weight: bold; }
+ul.checks .ability-name { color: var(--muted); font-size: 0.8rem; }
+.dot { color: var(--accent); }
+ul.list { margin: 0; padding-left: 1.1rem; }
+table { width: 100%; border-collapse: collapse; }
+th { text-align: left; font-size: 0.7rem; text-transform: uppercase; color: var(--muted); border-bottom: 1px solid var(--ink); }
+td { border-bottom: 1px dotted var(--line); padding: 0.15rem 0.25rem 0.15rem 0; vertical-align: top; }
+.currency { display: grid; grid-template-columns: repeat(5, 1fr); gap: 0.25rem; text-align: center; margin-bottom: 0.5rem; }
+.currency div { border: 1px solid var(--line); border-radius: 0.25rem; }
+.currency span { display: block; font-size: 0.65rem; color: var(--muted); }
+.spell-levels { columns: 3 14rem; column-gap: 1.5rem; }
+.spell-level { break-inside: avoid; margin-bottom: 0.75rem; }
+.slots { font-size: 0.8rem; color: var(--muted); font-weight: normal; }
+.narrative p { margin: 0 0 0.5rem; }
+.page { break-before: page; }
+@media print {
+  @page { size: letter; margin: 0.5in; }
+  body { background: #fff; font-size: 11pt; }
+  .sheet, .party { max-width: none; margin: 0; padding: 0; box-shadow: none; }
+  .sheet + .sheet, .party + .sheet { break-before: page; }
+  a { color: inherit; text-decoration: none; }
+}
+@media (max-width: 48rem) { .columns { grid-template-columns: 1fr; } .abilities { flex-direction: row; flex-wrap: wrap; } }
+"#;
+
+/// Renders characters as a self-contained, printable HTML sheet: identity,
+/// abilities, saves and skills with proficiency dots, combat and attacks,
+/// equipment, features, a narrative page and a spellcasting page. Numbers come
+/// from `DerivedStats`, so they match the filled PDF.
+pub struct HtmlExporter;
+
+impl HtmlExporter {
+    pub fn export(character_data: &CharacterData) -> String {
+        let mut body = String::new();
+        Self::sheet(&mut body, character_data);
+        Self::document(&character_data.character.name, &body)
+    }
+
+    /// A party summary table followed by each member's sheet, one after another
+    pub fn export_party(party_name: &str, characters: &[CharacterData]) -> String {
+        let mut body = String::new();
+        let _ = writeln!(body, "<section class=\"party\">\n<h1>{}</h1>", escape(party_name));
+        body.push_str("<table class=\"party-summary\">\n<thead><tr><th>Character</th><th>Player</th><th>Class &amp; Level</th><th>Race</th><th>AC</th><th>HP</th><th>Passive Perception</th><th>Spell Save DC</th></tr></thead>\n<tbody>\n");
+        for character_data in characters {
+            let derived = DerivedStats::calculate(character_data);
+            let combat = character_data.combat.clone().unwrap_or_default();
+            let character = &character_data.character;
+            let hit_points = combat.hit_point_maximum.or(derived.hit_point_maximum);
+            let _ = writeln!(
+                body,
+                "<tr><td><a href=\"#{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                Self::anchor(&character.name),
+                escape(&character.name),
+                escape(character.player_name.as_deref().unwrap_or_default()),
+                escape(&Self::class_and_level(character_data)),
+                escape(&character.race),
+                Self::optional(combat.armor_class),
+                Self::optional(hit_points),
+                derived.passive_perception,
+                Self::optional(Self::spell_numbers(character_data, &derived).map(|(dc, _)| dc)),
+            );
+        }
+        body.push_str("</tbody>\n</table>\n</section>\n");
+        for character_data in characters {
+            Self::sheet(&mut body, character_data);
+        }
+        Self::document(party_name, &body)
+    }
+
+    fn document(title: &str, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape(title),
+            STYLESHEET,
+            body
+        )
+    }
+
+    fn sheet(out: &mut String, character_data: &CharacterData) {
+        let derived = DerivedStats::calculate(character_data);
+        let _ = writeln!(out, "<article class=\"sheet\" id=\"{}\">", Self::anchor(&character_data.character.name));
+        Self::header(out, character_data);
+        out.push_str("<div class=\"columns\">\n");
+        Self::abilities(out, &derived);
+        out.push_str("<div>\n");
+        Self::checks(out, character_data, &derived);
+        Self::proficiencies(out, character_data);
+        out.push_str("</div>\n<div>\n");
+        Self::combat(out, character_data, &derived);
+        Self::attacks(out, character_data, &derived);
+        Self::equipment(out, character_data);
+        out.push_str("</div>\n</div>\n");
+        Self::features(out, character_data, &derived);
+        Self::narrative(out, character_data);
+        Self::spellcasting(out, character_data, &derived);
+        out.push_str("</article>\n");
+    }
+
+    fn header(out: &mut String, character_data: &CharacterData) {
+        let character = &character_data.character;
+        let milestone = character.advancement_mode == Some(AdvancementMode::Milestone);
+        let _ = writeln!(out, "<header>\n<h1>{}</h1>", escape(&character.name));
+        Self::details(
+            out,
+            &[
+                ("Class & Level", Some(Self::class_and_level(character_data))),
+                ("Race", Some(character.race.clone())),
+                ("Background", character.background.clone()),
+                ("Alignment", character.alignment.clone()),
+                ("Player", character.player_name.clone()),
+                ("Experience Points", character.experience_points.filter(|_| !milestone).map(|xp| xp.to_string())),
+                ("Leveling", milestone.then(|| "Milestone".to_string())),
+            ],
+        );
+        out.push_str("</header>\n");
+    }
+
+    fn abilities(out: &mut String, derived: &DerivedStats) {
+        out.push_str("<section class=\"abilities\">\n");
+        for ability in AbilityScores::NAMES {
+            let _ = writeln!(
+                out,
+                "<div class=\"ability\"><span class=\"label\">{}</span><span class=\"modifier\">{:+}</span><span class=\"score\">{}</span></div>",
+                MarkdownExporter::title(ability),
+                derived.abilities.modifier(ability),
+                derived.abilities.get_ability_score(ability)
+            );
+        }
+        out.push_str("</section>\n");
+    }
+
+    fn checks(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let proficiencies = character_data.proficiencies.as_ref();
+        let bonus = |ability: &str, proficient: bool| {
+            derived.abilities.modifier(ability) + if proficient { derived.proficiency_bonus as i8 } else { 0 }
+        };
+        let inspiration = character_data.combat.as_ref().and_then(|c| c.inspiration).unwrap_or(false);
+
+        out.push_str("<div class=\"stats\">\n");
+        Self::stat(out, "Proficiency Bonus", &format!("{:+}", derived.proficiency_bonus));
+        Self::stat(out, "Passive Perception", &derived.passive_perception.to_string());
+        Self::stat(out, "Inspiration", Self::dot(inspiration));
+        out.push_str("</div>\n");
+
+        out.push_str("<section>\n<h2>Saving Throws</h2>\n<ul class=\"checks\">\n");
+        for ability in AbilityScores::NAMES {
+            let proficient = proficiencies.is_some_and(|p| p.saving_throws.iter().any(|s| s == ability));
+            Self::check(out, proficient, bonus(ability, proficient), &MarkdownExporter::title(ability), None);
+        }
+        out.push_str("</ul>\n</section>\n");
+
+        out.push_str("<section>\n<h2>Skills</h2>\n<ul class=\"checks\">\n");
+        for skill in SKILL_NAMES {
+            let ability = get_skill_ability(skill);
+            let proficient = proficiencies.is_some_and(|p| p.skills.iter().any(|s| s == skill));
+            let short = MarkdownExporter::title(&ability[..3]);
+            Self::check(out, proficient, bonus(ability, proficient), &MarkdownExporter::title(skill), Some(&short));
+        }
+        out.push_str("</ul>\n</section>\n");
+    }
+
+    fn check(out: &mut String, proficient: bool, bonus: i8, name: &str, ability: Option<&str>) {
+        let ability = ability.map(|a| format!(" <span class=\"ability-name\">({})</span>", a)).unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "<li><span class=\"dot\">{}</span><span class=\"bonus\">{:+}</span><span>{}{}</span></li>",
+            Self::dot(proficient),
+            bonus,
+            escape(name),
+            ability
+        );
+    }
+
+    fn proficiencies(out: &mut String, character_data: &CharacterData) {
+        let Some(proficiencies) = &character_data.proficiencies else {
+            return;
+        };
+        let merged = ProficiencyHandler::merge(proficiencies);
+        let tools: Vec<String> = merged
+            .tools
+            .iter()
+            .map(|tool| match merged.tool_expertise.iter().any(|e| e.eq_ignore_ascii_case(tool)) {
+                true => format!("{} (expertise)", tool),
+                false => tool.clone(),
+            })
+            .collect();
+        let groups = [
+            ("Armor", &merged.armor),
+            ("Weapons", &merged.weapons),
+            ("Tools", &tools),
+            ("Languages", &merged.languages),
+        ];
+        if groups.iter().all(|(_, items)| items.is_empty()) {
+            return;
+        }
+        out.push_str("<section>\n<h2>Proficiencies &amp; Languages</h2>\n");
+        let rows: Vec<(&str, Option<String>)> = groups
+            .iter()
+            .map(|(label, items)| (*label, (!items.is_empty()).then(|| items.join(", "))))
+            .collect();
+        Self::details(out, &rows);
+        out.push_str("</section>\n");
+    }
+
+    fn combat(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let combat = character_data.combat.clone().unwrap_or_default();
+        out.push_str("<section>\n<h2>Combat</h2>\n<div class=\"stats\">\n");
+        Self::stat(out, "Armor Class", &Self::optional(combat.armor_class));
+        Self::stat(out, "Initiative", &format!("{:+}", combat.initiative.unwrap_or(derived.initiative)));
+        Self::stat(out, "Speed", &combat.speed.map(|speed| format!("{} ft", speed)).unwrap_or_default());
+        Self::stat(out, "Hit Point Maximum", &Self::optional(combat.hit_point_maximum.or(derived.hit_point_maximum)));
+        Self::stat(out, "Current Hit Points", &Self::optional(combat.current_hit_points));
+        Self::stat(out, "Temporary Hit Points", &Self::optional(combat.temporary_hit_points));
+        out.push_str("</div>\n");
+
+        let saves = |count: Option<u8>| {
+            let count = count.unwrap_or(0).min(3) as usize;
+            format!("{}{}", PROFICIENT.repeat(count), NOT_PROFICIENT.repeat(3 - count))
+        };
+        let conditions = combat.conditions.as_ref().filter(|c| !c.is_empty()).map(|c| c.join(", "));
+        Self::details(
+            out,
+            &[
+                ("Hit Dice", combat.hit_dice.clone()),
+                ("Death Save Successes", Some(saves(combat.death_save_successes))),
+                ("Death Save Failures", Some(saves(combat.death_save_failures))),
+                ("Exhaustion", combat.exhaustion_level.filter(|level| *level > 0).map(|level| level.to_string())),
+                ("Conditions", conditions),
+            ],
+        );
+        out.push_str("</section>\n");
+    }
+
+    fn attacks(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let attacks = derived.attacks(character_data);
+        let spell_attack = Self::spell_numbers(character_data, derived).map(|(_, attack)| attack);
+        if attacks.is_empty() && spell_attack.is_none() {
+            return;
+        }
+        out.push_str("<section>\n<h2>Attacks &amp; Spellcasting</h2>\n<table>\n<thead><tr><th>Name</th><th>Atk Bonus</th><th>Damage/Type</th><th>Properties</th></tr></thead>\n<tbody>\n");
+        for attack in attacks {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>{:+}</td><td>{}</td><td>{}</td></tr>",
+                escape(&attack.name),
+                attack.attack_bonus,
+                escape(&attack.damage),
+                escape(&attack.properties.join(", "))
+            );
+        }
+        if let Some(bonus) = spell_attack {
+            let _ = writeln!(out, "<tr><td>Spell attack</td><td>{:+}</td><td></td><td></td></tr>", bonus);
+        }
+        out.push_str("</tbody>\n</table>\n</section>\n");
+    }
+
+    fn equipment(out: &mut String, character_data: &CharacterData) {
+        let Some(equipment) = &character_data.equipment else {
+            return;
+        };
+        out.push_str("<section>\n<h2>Equipment</h2>\n");
+        if let Some(currency) = &equipment.currency {
+            out.push_str("<div class=\"currency\">");
+            for (label, coins) in [("CP", currency.cp), ("SP", currency.sp), ("EP", currency.ep), ("GP", currency.gp), ("PP", currency.pp)] {
+                let _ = write!(out, "<div><span>{}</span>{}</div>", label, coins);
+            }
+            out.push_str("</div>\n");
+        }
+        if let Some(items) = equipment.items.as_deref().filter(|items| !items.trim().is_empty()) {
+            Self::list(out, &Equipment::split_items(items));
+        }
+        if let Some(treasure) = equipment.treasure.as_deref() {
+            Self::paragraphs(out, Some("Treasure"), treasure);
+        }
+        out.push_str("</section>\n");
+    }
+
+    fn features(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let features_traits = character_data.features_traits.as_ref();
+        let lists = [
+            ("Features", features_traits.and_then(|f| f.features.clone()).unwrap_or_default()),
+            ("Traits", features_traits.and_then(|f| f.traits.clone()).unwrap_or_default()),
+            ("Additional Features & Traits", features_traits.and_then(|f| f.additional.clone()).unwrap_or_default()),
+            ("Feats", derived.feat_notes()),
+        ];
+        if lists.iter().all(|(_, items)| items.is_empty()) {
+            return;
+        }
+        out.push_str("<section>\n<h2>Features &amp; Traits</h2>\n");
+        for (heading, items) in lists {
+            if !items.is_empty() {
+                let _ = writeln!(out, "<h3>{}</h3>", escape(heading));
+                Self::list(out, &items);
+            }
+        }
+        out.push_str("</section>\n");
+    }
+
+    /// The second page: appearance, personality, backstory, allies
+    fn narrative(out: &mut String, character_data: &CharacterData) {
+        let appearance = character_data.appearance.clone().unwrap_or_default();
+        let appearance = [
+            ("Age", appearance.age),
+            ("Height", appearance.height),
+            ("Weight", appearance.weight),
+            ("Eyes", appearance.eyes),
+            ("Skin", appearance.skin),
+            ("Hair", appearance.hair),
+        ];
+        let narrative = character_data.narrative.as_ref();
+        let texts = [
+            ("Personality Traits", narrative.and_then(|n| n.personality_traits.as_deref())),
+            ("Ideals", narrative.and_then(|n| n.ideals.as_deref())),
+            ("Bonds", narrative.and_then(|n| n.bonds.as_deref())),
+            ("Flaws", narrative.and_then(|n| n.flaws.as_deref())),
+            ("Backstory", narrative.and_then(|n| n.backstory.as_deref())),
+            ("Faction", narrative.and_then(|n| n.faction_name.as_deref())),
+            ("Allies & Organizations", narrative.and_then(|n| n.allies.as_deref())),
+        ];
+        if appearance.iter().all(|(_, value)| value.is_none()) && texts.iter().all(|(_, text)| text.is_none()) {
+            return;
+        }
+        out.push_str("<section class=\"page narrative\">\n<h2>Appearance &amp; Story</h2>\n");
+        Self::details(out, &appearance);
+        for (heading, text) in texts {
+            if let Some(text) = text {
+                Self::paragraphs(out, Some(heading), text);
+            }
+        }
+        out.push_str("</section>\n");
+    }
+
+    /// The spellcasting page: ability, save DC, attack bonus and spells by
+    /// level with slot counts; filled dots mark prepared spells
+    fn spellcasting(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let Some(spells) = &character_data.spells else {
+            return;
+        };
+        let by_level = SpellSystem::organize_spells_by_level(spells);
+        if by_level.is_empty() && spells.spellcasting_ability.is_none() {
+            return;
+        }
+        let numbers = Self::spell_numbers(character_data, derived);
+        out.push_str("<section class=\"page spellcasting\">\n<h2>Spellcasting</h2>\n");
+        Self::details(
+            out,
+            &[
+                ("Spellcasting Class", spells.spellcasting_class.clone()),
+                ("Spellcasting Ability", spells.spellcasting_ability.clone()),
+                ("Spell Save DC", numbers.map(|(dc, _)| dc.to_string())),
+                ("Spell Attack Bonus", numbers.map(|(_, attack)| format!("{:+}", attack))),
+            ],
+        );
+        let slots = SpellSystem::calculate_spell_slots(character_data);
+        out.push_str("<div class=\"spell-levels\">\n");
+        for (level, list) in by_level {
+            out.push_str("<div class=\"spell-level\">\n");
+            match (level, slots.get(&format!("spell_slots_{}", level)).copied().unwrap_or(0)) {
+                (0, _) => out.push_str("<h3>Cantrips</h3>\n"),
+                (_, 0) => {
+                    let _ = writeln!(out, "<h3>Level {}</h3>", level);
+                }
+                (_, count) => {
+                    let _ = writeln!(out, "<h3>Level {} <span class=\"slots\">{} slots</span></h3>", level, count);
+                }
+            }
+            out.push_str("<ul class=\"spells\">\n");
+            for spell in list {
+                match level {
+                    0 => {
+                        let _ = writeln!(out, "<li>{}</li>", escape(&spell.name));
+                    }
+                    _ => {
+                        let _ = writeln!(out, "<li><span class=\"dot\">{}</span>{}</li>", Self::dot(spell.prepared), escape(&spell.name));
+                    }
+                }
+            }
+            out.push_str("</ul>\n</div>\n");
+        }
+        out.push_str("</div>\n</section>\n");
+    }
+
+    /// Spell save DC and attack bonus, when the character has a spellcasting ability
+    fn spell_numbers(character_data: &CharacterData, derived: &DerivedStats) -> Option<(u8, i8)> {
+        let ability = character_data.spells.as_ref()?.spellcasting_ability.as_deref()?;
+        let modifier = SpellSystem::get_spellcasting_ability_modifier(ability, &derived.abilities);
+        Some((
+            SpellSystem::calculate_spell_save_dc(modifier, derived.proficiency_bonus),
+            SpellSystem::calculate_spell_attack_bonus(modifier, derived.proficiency_bonus),
+        ))
+    }
+
+    /// "Fighter 8 (Eldritch Knight)"
+    fn class_and_level(character_data: &CharacterData) -> String {
+        let character = &character_data.character;
+        match &character.subclass {
+            Some(subclass) => format!("{} {} ({})", character.class, character.level, subclass),
+            None => format!("{} {}", character.class, character.level),
+        }
+    }
+
+    /// Label/value pairs for the values that are set
+    fn details(out: &mut String, rows: &[(&str, Option<String>)]) {
+        if rows.iter().all(|(_, value)| value.is_none()) {
+            return;
+        }
+        out.push_str("<dl class=\"details\">\n");
+        for (label, value) in rows {
+            if let Some(value) = value {
+                let _ = writeln!(out, "<div><dt>{}</dt><dd>{}</dd></div>", escape(label), escape(value));
+            }
+        }
+        out.push_str("</dl>\n");
+    }
+
+    fn stat(out: &mut String, label: &str, value: &str) {
+        let _ = writeln!(
+            out,
+            "<div class=\"stat\"><span class=\"value\">{}</span><span class=\"label\">{}</span></div>",
+            escape(value),
+            escape(label)
+        );
+    }
+
+    fn list(out: &mut String, items: &[String]) {
+        out.push_str("<ul class=\"list\">\n");
+        for item in items {
+            let _ = writeln!(out, "<li>{}</li>", escape(item.trim()));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    /// Blank-line separated paragraphs, with single newlines kept as line breaks
+    fn paragraphs(out: &mut String, heading: Option<&str>, text: &str) {
+        if let Some(heading) = heading {
+            let _ = writeln!(out, "<h3>{}</h3>", escape(heading));
+        }
+        for paragraph in text.trim().split("\n\n").filter(|p| !p.trim().is_empty()) {
+            let _ = writeln!(out, "<p>{}</p>", escape(paragraph.trim()).replace('\n', "<br>"));
+        }
+    }
+
+    fn dot(filled: bool) -> &'static str {
+        if filled {
+            PROFICIENT
+        } else {
+            NOT_PROFICIENT
+        }
+    }
+
+    fn optional<T: ToString>(value: Option<T>) -> String {
+        value.map(|value| value.to_string()).unwrap_or_default()
+    }
+
+    /// Fragment id for a character's sheet: "Elara Moonwhisper" -> "character-elara-moonwhisper"
+    fn anchor(name: &str) -> String {
+        let slug: Vec<String> = name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        format!("character-{}", slug.join("-"))
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
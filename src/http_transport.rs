@@ -0,0 +1,306 @@
+use crate::logging::Logger;
+use crate::mcp_server::McpServer;
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, Read};
+use std::net::SocketAddr;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Endpoint for MCP streamable HTTP
+pub const MCP_PATH: &str = "/mcp";
+
+/// Header naming the client's session, handed out with the `initialize` response
+pub const SESSION_HEADER: &str = "Mcp-Session-Id";
+
+/// Character data with embedded base64 images can be large, but not this large
+const MAX_BODY_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Requests handled at once; filling a sheet takes a few seconds, so a few
+/// clients can work side by side without one thread per connection
+const WORKERS: usize = 8;
+/// Requests accepted but waiting for a worker before new ones wait in the socket backlog
+const QUEUED_REQUESTS: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Address to listen on, e.g. "127.0.0.1:8080" or "0.0.0.0:8080"
+    pub bind_address: String,
+    /// When set, every request needs `Authorization: Bearer <token>`
+    pub bearer_token: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: "127.0.0.1:8080".to_string(),
+            bearer_token: None,
+        }
+    }
+}
+
+/// MCP streamable HTTP transport: JSON-RPC messages are POSTed to `/mcp` and
+/// answered as JSON or as an SSE stream, using the same handlers as stdio
+pub struct HttpTransport {
+    server: Arc<McpServer>,
+    http: Server,
+    bearer_token: Option<String>,
+}
+
+impl HttpTransport {
+    /// Listen on `config.bind_address`. Anything other than a loopback
+    /// address is refused without a bearer token, since the server writes
+    /// files and reads the character store for whoever can reach it
+    pub fn bind(server: McpServer, config: &HttpConfig) -> io::Result<Self> {
+        let http = Server::http(&config.bind_address)
+            .map_err(|e| io::Error::new(io::ErrorKind::AddrNotAvailable, e.to_string()))?;
+        let transport = Self {
+            server: Arc::new(server),
+            http,
+            bearer_token: config.bearer_token.clone(),
+        };
+        let loopback = transport.local_addr().is_some_and(|addr| addr.ip().is_loopback());
+        if !loopback && transport.bearer_token.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "refusing to listen on {} without a bearer token; set --token or DND_MCP_AUTH_TOKEN, or bind to 127.0.0.1",
+                    config.bind_address
+                ),
+            ));
+        }
+        Ok(transport)
+    }
+
+    /// The bound address; useful when binding to port 0
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    pub fn run(self) -> io::Result<()> {
+        if let Some(addr) = self.local_addr() {
+            Logger::info("http_transport", &format!("MCP HTTP server listening on http://{}{}", addr, MCP_PATH));
+        }
+
+        let bearer_token = Arc::new(self.bearer_token);
+        let (queue, requests) = mpsc::sync_channel::<Request>(QUEUED_REQUESTS);
+        let requests = Arc::new(Mutex::new(requests));
+        for _ in 0..WORKERS {
+            let server = Arc::clone(&self.server);
+            let bearer_token = Arc::clone(&bearer_token);
+            let requests = Arc::clone(&requests);
+            thread::spawn(move || loop {
+                let request = match requests.lock() {
+                    Ok(requests) => requests.recv(),
+                    Err(_) => return,
+                };
+                let Ok(request) = request else {
+                    return;
+                };
+                if let Err(e) = Self::handle_request(&server, bearer_token.as_deref(), request) {
+                    Logger::debug("http_transport", &format!("Failed to send response: {}", e));
+                }
+            });
+        }
+
+        // Blocks while every worker is busy and the queue is full
+        for request in self.http.incoming_requests() {
+            if queue.send(request).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(
+        server: &McpServer,
+        bearer_token: Option<&str>,
+        mut request: Request,
+    ) -> io::Result<()> {
+        let path = request.url().split('?').next().unwrap_or_default().to_string();
+        if path != MCP_PATH {
+            return request.respond(Response::empty(404));
+        }
+
+        if !Self::is_authorized(&request, bearer_token) {
+            let response = Response::from_string("Unauthorized")
+                .with_status_code(401)
+                .with_header(header("WWW-Authenticate", "Bearer"));
+            return request.respond(response);
+        }
+
+        // Guard against DNS rebinding from browsers when there's no token to check
+        if bearer_token.is_none() && !Self::is_local_origin(&request) {
+            return request.respond(Response::from_string("Forbidden origin").with_status_code(403));
+        }
+
+        let session = Self::header_value(&request, SESSION_HEADER);
+        match request.method() {
+            Method::Post => {}
+            // The client is done with its session
+            Method::Delete => {
+                let status = match &session {
+                    Some(session) => {
+                        server.end_session(session);
+                        200
+                    }
+                    None => 400,
+                };
+                return request.respond(Response::empty(status));
+            }
+            // No server-initiated stream: every response goes back on its POST
+            _ => {
+                let response = Response::empty(405).with_header(header("Allow", "POST, DELETE"));
+                return request.respond(response);
+            }
+        }
+
+        let too_large = || Response::from_string("Request body too large").with_status_code(413);
+        if request.body_length().is_some_and(|length| length as u64 > MAX_BODY_BYTES) {
+            return request.respond(too_large());
+        }
+        // Read one byte past the limit, so a chunked body that is too large is refused too
+        let mut body = String::new();
+        if request
+            .as_reader()
+            .take(MAX_BODY_BYTES + 1)
+            .read_to_string(&mut body)
+            .is_err()
+        {
+            return request.respond(Response::from_string("Invalid body").with_status_code(400));
+        }
+        if body.len() as u64 > MAX_BODY_BYTES {
+            return request.respond(too_large());
+        }
+
+        // Each initialize starts a new session, for its own log level
+        let new_session = Self::is_initialize(&body).then(new_session_id);
+        let session = new_session.clone().or(session);
+        let (responses, notifications) = Self::dispatch(server, session.as_deref(), &body);
+        if responses.is_empty() {
+            // Only notifications or responses were sent
+            return request.respond(Response::empty(202));
+        }
+
+        let wants_sse = Self::header_value(&request, "Accept")
+            .map(|accept| accept.contains("text/event-stream"))
+            .unwrap_or(false);
+        let mut response = if wants_sse {
+            // Log notifications can only reach the client on an SSE stream
+            let events: String = notifications
+                .iter()
+                .chain(responses.iter())
+                .map(|message| format!("event: message\ndata: {}\n\n", message))
+                .collect();
+            Response::from_string(events)
+                .with_header(header("Content-Type", "text/event-stream"))
+                .with_header(header("Cache-Control", "no-cache"))
+        } else {
+            let body = if body.trim_start().starts_with('[') {
+                Value::Array(responses).to_string()
+            } else {
+                responses[0].to_string()
+            };
+            Response::from_string(body).with_header(header("Content-Type", "application/json"))
+        };
+        if let Some(session) = new_session {
+            response.add_header(header(SESSION_HEADER, &session));
+        }
+        request.respond(response)
+    }
+
+    fn is_initialize(body: &str) -> bool {
+        serde_json::from_str::<Value>(body)
+            .map(|message| message["method"] == "initialize")
+            .unwrap_or(false)
+    }
+
+    /// Handle a single message or a JSON-RPC batch, returning the responses
+    /// and any log notifications for the client
+    fn dispatch(server: &McpServer, session: Option<&str>, body: &str) -> (Vec<Value>, Vec<Value>) {
+        let messages = match serde_json::from_str::<Value>(body) {
+            Ok(Value::Array(messages)) => messages.iter().map(Value::to_string).collect(),
+            _ => vec![body.to_string()],
+        };
+
+        let mut responses = Vec::new();
+        let mut notifications = Vec::new();
+        for message in messages {
+            let (response, logs) = server.handle_session_message(session, &message);
+            responses.extend(response);
+            notifications.extend(logs);
+        }
+        (responses, notifications)
+    }
+
+    fn is_authorized(request: &Request, bearer_token: Option<&str>) -> bool {
+        let Some(expected) = bearer_token else {
+            return true;
+        };
+        Self::header_value(request, "Authorization")
+            .and_then(|value| value.strip_prefix("Bearer ").map(|token| token.trim().to_string()))
+            .map(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+            .unwrap_or(false)
+    }
+
+    fn is_local_origin(request: &Request) -> bool {
+        let Some(origin) = Self::header_value(request, "Origin") else {
+            return true;
+        };
+        let authority = origin
+            .split("://")
+            .nth(1)
+            .unwrap_or(&origin)
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        let host = match authority.strip_prefix('[') {
+            Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+            None => authority.split(':').next().unwrap_or_default(),
+        };
+        matches!(host, "localhost" | "127.0.0.1" | "::1")
+    }
+
+    fn header_value(request: &Request, name: &'static str) -> Option<String> {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str().to_string())
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// 128 random bits from the OS for an `Mcp-Session-Id`
+fn new_session_id() -> String {
+    let mut bytes = [0u8; 16];
+    match File::open("/dev/urandom").and_then(|mut urandom| urandom.read_exact(&mut bytes)) {
+        Ok(()) => bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        Err(_) => fallback_session_id(),
+    }
+}
+
+/// Unique but guessable: std seeds `RandomState` keys once per thread and
+/// only increments them for each new `RandomState`. The id only keys a log
+/// level, so without /dev/urandom it is good enough
+fn fallback_session_id() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    (0..2)
+        .map(|_| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(now);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
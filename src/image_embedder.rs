@@ -0,0 +1,306 @@
+use crate::character_model::ImageSource;
+use crate::config::ServerConfig;
+use crate::errors::PdfError;
+use base64::{engine::general_purpose, Engine as _};
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// A decoded image ready to be written as a PDF image XObject
+struct PdfImage {
+    width: u32,
+    height: u32,
+    stream: Stream,
+    soft_mask: Option<Stream>,
+}
+
+pub struct ImageEmbedder;
+
+impl ImageEmbedder {
+    /// Read image bytes from a file path or decode them from base64. With a
+    /// `confined_to` config, paths are held to the directories tools may read
+    pub fn load_image_bytes(source: &ImageSource, confined_to: Option<&ServerConfig>) -> Result<Vec<u8>, PdfError> {
+        match source {
+            ImageSource::Path(path) => {
+                let resolved = match confined_to {
+                    Some(config) => config
+                        .resolve_input(path)
+                        .map_err(|e| PdfError::ImageError(e.to_string()))?,
+                    None => PathBuf::from(path),
+                };
+                fs::read(&resolved).map_err(|e| match e.kind() {
+                    ErrorKind::NotFound => PdfError::ImageError(format!("Image file not found: {}", path)),
+                    ErrorKind::PermissionDenied => {
+                        PdfError::ImageError(format!("Permission denied reading image {}", path))
+                    }
+                    _ => PdfError::ImageError(format!("Cannot read image {}: {}", path, e)),
+                })
+            }
+            ImageSource::Base64(data) => {
+                // Accept data URLs such as "data:image/png;base64,..."
+                let encoded = data.split_once(',').map(|(_, rest)| rest).unwrap_or(data);
+                general_purpose::STANDARD
+                    .decode(encoded.trim())
+                    .map_err(|e| PdfError::ImageError(format!("Invalid base64 image data: {}", e)))
+            }
+        }
+    }
+
+    /// Place a PNG or JPEG image into every widget of the named button field,
+    /// scaled to fit the button rectangle while keeping its aspect ratio
+    pub fn embed_in_button(
+        doc: &mut Document,
+        field_name: &str,
+        image_bytes: &[u8],
+    ) -> Result<(), PdfError> {
+        // The field dictionary is either the widget itself or a parent whose Kids are the widgets
+        let mut widget_ids = Vec::new();
+        for (object_id, object) in &doc.objects {
+            if let Object::Dictionary(dict) = object {
+                if let Ok(Object::String(name, _)) = dict.get(b"T") {
+                    if name.as_slice() == field_name.as_bytes() {
+                        match dict.get(b"Kids") {
+                            Ok(Object::Array(kids)) => widget_ids
+                                .extend(kids.iter().filter_map(|kid| kid.as_reference().ok())),
+                            _ => widget_ids.push(*object_id),
+                        }
+                    }
+                }
+            }
+        }
+
+        let widgets: Vec<(ObjectId, Dictionary)> = widget_ids
+            .into_iter()
+            .filter_map(|widget_id| match doc.get_object(widget_id) {
+                Ok(Object::Dictionary(dict)) => Some((widget_id, dict.clone())),
+                _ => None,
+            })
+            .collect();
+
+        if widgets.is_empty() {
+            return Err(PdfError::FieldNotFound(field_name.to_string()));
+        }
+
+        let mut image = Self::decode(image_bytes)?;
+        let (image_width, image_height) = (image.width as f64, image.height as f64);
+        if let Some(soft_mask) = image.soft_mask.take() {
+            let mask_id = doc.add_object(soft_mask);
+            image.stream.dict.set("SMask", Object::Reference(mask_id));
+        }
+        let image_id = doc.add_object(image.stream);
+
+        for (widget_id, mut widget) in widgets {
+            let (width, height) = Self::widget_size(doc, &widget)?;
+
+            // Fit the image inside the button and center it
+            let scale = (width / image_width).min(height / image_height);
+            let (draw_width, draw_height) = (image_width * scale, image_height * scale);
+            let offset_x = (width - draw_width) / 2.0;
+            let offset_y = (height - draw_height) / 2.0;
+            let content = format!(
+                "q {:.3} 0 0 {:.3} {:.3} {:.3} cm /Img Do Q",
+                draw_width, draw_height, offset_x, offset_y
+            );
+
+            let mut xobjects = Dictionary::new();
+            xobjects.set("Img", Object::Reference(image_id));
+            let mut resources = Dictionary::new();
+            resources.set("XObject", Object::Dictionary(xobjects));
+
+            let mut form = Dictionary::new();
+            form.set("Type", Object::Name(b"XObject".to_vec()));
+            form.set("Subtype", Object::Name(b"Form".to_vec()));
+            form.set(
+                "BBox",
+                Object::Array(vec![0.into(), 0.into(), Object::Real(width), Object::Real(height)]),
+            );
+            form.set("Resources", Object::Dictionary(resources));
+            let form_id = doc.add_object(Stream::new(form, content.into_bytes()));
+
+            let mut appearance = Dictionary::new();
+            appearance.set("N", Object::Reference(form_id));
+            widget.set("AP", Object::Dictionary(appearance));
+
+            // Push buttons also carry their icon in the appearance characteristics
+            let mut characteristics = match widget.get(b"MK") {
+                Ok(Object::Dictionary(mk)) => mk.clone(),
+                _ => Dictionary::new(),
+            };
+            characteristics.set("I", Object::Reference(form_id));
+            widget.set("MK", Object::Dictionary(characteristics));
+
+            doc.objects.insert(widget_id, Object::Dictionary(widget));
+        }
+
+        Ok(())
+    }
+
+    fn widget_size(doc: &Document, widget: &Dictionary) -> Result<(f64, f64), PdfError> {
+        let rect = match widget.get(b"Rect") {
+            Ok(Object::Array(rect)) => rect.clone(),
+            Ok(Object::Reference(rect_ref)) => match doc.get_object(*rect_ref) {
+                Ok(Object::Array(rect)) => rect.clone(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        let coords: Vec<f64> = rect
+            .iter()
+            .filter_map(|value| match value {
+                Object::Integer(i) => Some(*i as f64),
+                Object::Real(r) => Some(*r),
+                _ => None,
+            })
+            .collect();
+
+        if coords.len() != 4 {
+            return Err(PdfError::ParseError("Image button has no valid Rect".to_string()));
+        }
+
+        Ok(((coords[2] - coords[0]).abs(), (coords[3] - coords[1]).abs()))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<PdfImage, PdfError> {
+        if bytes.starts_with(&[0xFF, 0xD8]) {
+            Self::decode_jpeg(bytes)
+        } else if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+            Self::decode_png(bytes)
+        } else {
+            Err(PdfError::ImageError(
+                "Unsupported image format (expected PNG or JPEG)".to_string(),
+            ))
+        }
+    }
+
+    /// JPEG data is embedded as-is with DCTDecode; only the frame header is parsed
+    fn decode_jpeg(bytes: &[u8]) -> Result<PdfImage, PdfError> {
+        let invalid = || PdfError::ImageError("Invalid JPEG image: truncated or malformed marker segment".to_string());
+        let mut pos = 2;
+        while pos + 1 < bytes.len() {
+            if bytes[pos] != 0xFF {
+                return Err(invalid());
+            }
+            let marker = bytes[pos + 1];
+            match marker {
+                // Fill byte before a marker
+                0xFF => {
+                    pos += 1;
+                    continue;
+                }
+                // TEM, RST0-RST7, SOI and EOI stand alone, with no length
+                0x01 | 0xD0..=0xD9 => {
+                    pos += 2;
+                    continue;
+                }
+                // Start of scan: entropy-coded data follows, and the frame
+                // header would have come before it
+                0xDA => break,
+                _ => {}
+            }
+            if pos + 4 > bytes.len() {
+                return Err(invalid());
+            }
+            // The length counts itself but not the marker
+            let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+            if length < 2 {
+                return Err(invalid());
+            }
+
+            // SOF0-SOF15 except DHT (C4), JPG (C8) and DAC (CC)
+            let is_frame_header =
+                (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_frame_header {
+                if length < 8 || pos + 10 > bytes.len() {
+                    return Err(invalid());
+                }
+                let height = u16::from_be_bytes([bytes[pos + 5], bytes[pos + 6]]) as u32;
+                let width = u16::from_be_bytes([bytes[pos + 7], bytes[pos + 8]]) as u32;
+                let color_space: &[u8] = match bytes[pos + 9] {
+                    1 => b"DeviceGray",
+                    4 => b"DeviceCMYK",
+                    _ => b"DeviceRGB",
+                };
+
+                let mut dict = Self::image_dict(width, height, color_space);
+                dict.set("Filter", Object::Name(b"DCTDecode".to_vec()));
+                let stream = Stream::new(dict, bytes.to_vec()).with_compression(false);
+                return Ok(PdfImage {
+                    width,
+                    height,
+                    stream,
+                    soft_mask: None,
+                });
+            }
+
+            pos += 2 + length;
+        }
+
+        Err(PdfError::ImageError("JPEG image has no frame header".to_string()))
+    }
+
+    /// PNG data is decoded to 8-bit samples; any alpha channel becomes a soft mask
+    fn decode_png(bytes: &[u8]) -> Result<PdfImage, PdfError> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| PdfError::ImageError(format!("Invalid PNG image: {}", e)))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader
+            .next_frame(&mut buffer)
+            .map_err(|e| PdfError::ImageError(format!("Invalid PNG image: {}", e)))?;
+        buffer.truncate(frame.buffer_size());
+
+        let (color_channels, has_alpha, color_space): (usize, bool, &[u8]) = match frame.color_type {
+            png::ColorType::Grayscale => (1, false, b"DeviceGray"),
+            png::ColorType::GrayscaleAlpha => (1, true, b"DeviceGray"),
+            png::ColorType::Rgb => (3, false, b"DeviceRGB"),
+            png::ColorType::Rgba => (3, true, b"DeviceRGB"),
+            png::ColorType::Indexed => {
+                return Err(PdfError::ImageError("Indexed PNG was not expanded".to_string()))
+            }
+        };
+
+        let stride = color_channels + usize::from(has_alpha);
+        let mut color = Vec::with_capacity(buffer.len());
+        let mut alpha = Vec::new();
+        for pixel in buffer.chunks_exact(stride) {
+            color.extend_from_slice(&pixel[..color_channels]);
+            if has_alpha {
+                alpha.push(pixel[color_channels]);
+            }
+        }
+
+        // Ignore any compression error and fall back to uncompressed samples
+        let soft_mask = if has_alpha {
+            let mut mask = Stream::new(Self::image_dict(frame.width, frame.height, b"DeviceGray"), alpha);
+            let _ = mask.compress();
+            Some(mask)
+        } else {
+            None
+        };
+
+        let mut stream = Stream::new(Self::image_dict(frame.width, frame.height, color_space), color);
+        let _ = stream.compress();
+
+        Ok(PdfImage {
+            width: frame.width,
+            height: frame.height,
+            stream,
+            soft_mask,
+        })
+    }
+
+    fn image_dict(width: u32, height: u32, color_space: &[u8]) -> Dictionary {
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::Name(b"XObject".to_vec()));
+        dict.set("Subtype", Object::Name(b"Image".to_vec()));
+        dict.set("Width", width as i64);
+        dict.set("Height", height as i64);
+        dict.set("ColorSpace", Object::Name(color_space.to_vec()));
+        dict.set("BitsPerComponent", 8);
+        dict
+    }
+}
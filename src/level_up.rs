@@ -0,0 +1,365 @@
+use crate::character_model::{
+    AbilityScores, CharacterData, Combat, Improvement, LevelAdvancement, Spell, Spells,
+};
+use crate::class_catalog::{ClassCatalog, ClassInfo};
+use crate::derived_stats::DerivedStats;
+use crate::errors::LevelUpError;
+use crate::spell_system::SpellSystem;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How the hit point increase for the new level is determined
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum HitPointChoice {
+    /// The fixed average for the class hit die (e.g. 6 for a d10)
+    Average,
+    /// A hit die rolled by the player
+    Roll { value: u8 },
+}
+
+/// Player decisions for a single level-up; anything required but missing is
+/// reported back as a pending choice. The level is always taken in the
+/// character's own class, as multiclassing isn't modelled
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LevelUpChoices {
+    pub hit_points: Option<HitPointChoice>,
+    /// Required at the class's Ability Score Improvement levels
+    pub improvement: Option<Improvement>,
+    /// Required at the class's subclass level when no subclass is set
+    pub subclass: Option<String>,
+    #[serde(default)]
+    pub new_spells: Vec<Spell>,
+}
+
+/// A decision the player still has to make before the level-up can be applied
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PendingChoice {
+    pub choice: String,
+    pub description: String,
+    pub options: Vec<String>,
+}
+
+/// One changed value, addressed by its dotted path in the character JSON
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LevelUpOutcome {
+    /// The leveled character; unchanged while choices are pending
+    pub character: CharacterData,
+    pub changes: Vec<FieldChange>,
+    pub pending_choices: Vec<PendingChoice>,
+}
+
+impl LevelUpOutcome {
+    pub fn is_complete(&self) -> bool {
+        self.pending_choices.is_empty()
+    }
+}
+
+pub struct LevelUpEngine;
+
+impl LevelUpEngine {
+    pub fn level_up(
+        character_data: &CharacterData,
+        choices: &LevelUpChoices,
+    ) -> Result<LevelUpOutcome, LevelUpError> {
+        let current = &character_data.character;
+        if current.level >= 20 {
+            return Err(LevelUpError::MaxLevel(current.level));
+        }
+
+        let class = ClassCatalog::get(&current.class)
+            .ok_or_else(|| LevelUpError::UnknownClass(current.class.clone()))?;
+
+        let new_level = current.level + 1;
+        let pending_choices = Self::pending_choices(character_data, class, new_level, choices)?;
+        if !pending_choices.is_empty() {
+            return Ok(LevelUpOutcome {
+                character: character_data.clone(),
+                changes: Vec::new(),
+                pending_choices,
+            });
+        }
+
+        let mut leveled = character_data.clone();
+        leveled.character.level = new_level;
+
+        if let Some(subclass) = &choices.subclass {
+            leveled.character.subclass = Some(subclass.clone());
+        }
+
+        if let Some(improvement) = &choices.improvement {
+            leveled
+                .level_history
+                .get_or_insert_with(Vec::new)
+                .push(LevelAdvancement {
+                    level: new_level,
+                    class: None,
+                    improvement: improvement.clone(),
+                });
+        }
+
+        Self::add_spells(&mut leveled, class, new_level, &choices.new_spells)?;
+        Self::apply_hit_points(character_data, &mut leveled, class, choices)?;
+
+        let before = serde_json::to_value(character_data).unwrap_or(Value::Null);
+        let after = serde_json::to_value(&leveled).unwrap_or(Value::Null);
+        let mut changes = Vec::new();
+        Self::diff("", &before, &after, &mut changes);
+        Self::diff_derived(character_data, &leveled, &mut changes);
+
+        Ok(LevelUpOutcome {
+            character: leveled,
+            changes,
+            pending_choices,
+        })
+    }
+
+    fn pending_choices(
+        character_data: &CharacterData,
+        class: &ClassInfo,
+        new_level: u8,
+        choices: &LevelUpChoices,
+    ) -> Result<Vec<PendingChoice>, LevelUpError> {
+        let mut pending = Vec::new();
+
+        if choices.hit_points.is_none() {
+            pending.push(PendingChoice {
+                choice: "hit_points".to_string(),
+                description: format!(
+                    "Take the average hit point increase or roll a d{}",
+                    class.hit_die
+                ),
+                options: vec![
+                    format!(
+                        "average ({})",
+                        ClassCatalog::average_hit_points_per_level(class.hit_die)
+                    ),
+                    format!("roll (1d{})", class.hit_die),
+                ],
+            });
+        }
+
+        let is_asi_level = class.asi_levels.contains(&new_level);
+        match (&choices.improvement, is_asi_level) {
+            (None, true) => pending.push(PendingChoice {
+                choice: "improvement".to_string(),
+                description: format!(
+                    "{} level {} grants an Ability Score Improvement or a feat",
+                    class.name, new_level
+                ),
+                options: vec![
+                    "ability_score_improvement".to_string(),
+                    "feat".to_string(),
+                ],
+            }),
+            (Some(_), false) => {
+                return Err(LevelUpError::UnexpectedImprovement {
+                    level: new_level,
+                    class: class.name.to_string(),
+                })
+            }
+            _ => {}
+        }
+
+        if new_level == class.subclass_level
+            && character_data.character.subclass.is_none()
+            && choices.subclass.is_none()
+        {
+            pending.push(PendingChoice {
+                choice: "subclass".to_string(),
+                description: format!("{} chooses a subclass at level {}", class.name, new_level),
+                options: Vec::new(),
+            });
+        }
+
+        Ok(pending)
+    }
+
+    fn apply_hit_points(
+        before: &CharacterData,
+        leveled: &mut CharacterData,
+        class: &ClassInfo,
+        choices: &LevelUpChoices,
+    ) -> Result<(), LevelUpError> {
+        let die_result = match choices.hit_points {
+            Some(HitPointChoice::Roll { value }) => {
+                if !(1..=class.hit_die).contains(&value) {
+                    return Err(LevelUpError::InvalidHitPointRoll {
+                        value,
+                        hit_die: class.hit_die,
+                    });
+                }
+                value
+            }
+            _ => ClassCatalog::average_hit_points_per_level(class.hit_die),
+        };
+
+        let old_stats = DerivedStats::calculate(before);
+        let new_stats = DerivedStats::calculate(leveled);
+        let old_level = before.character.level as i32;
+        let new_level = leveled.character.level as i32;
+        let old_con = old_stats.abilities.modifier("constitution") as i32;
+        let new_con = new_stats.abilities.modifier("constitution") as i32;
+
+        // A Constitution increase applies retroactively to every earlier level,
+        // as does a feat like Tough taken at this level
+        let gain = (die_result as i32 + new_con).max(1)
+            + (new_con - old_con) * old_level
+            + new_stats.hit_point_bonus_per_level as i32 * new_level
+            - old_stats.hit_point_bonus_per_level as i32 * old_level;
+
+        let old_max = before
+            .combat
+            .as_ref()
+            .and_then(|combat| combat.hit_point_maximum)
+            .or(old_stats.hit_point_maximum)
+            .unwrap_or_default() as i32;
+
+        let combat = leveled.combat.get_or_insert_with(Combat::default);
+        combat.hit_point_maximum = Some((old_max + gain).max(1) as u16);
+        if let Some(current) = combat.current_hit_points {
+            combat.current_hit_points = Some((current as i32 + gain).max(0) as u16);
+        }
+        if combat.hit_dice.is_some() {
+            combat.hit_dice = Some(format!("{}d{}", new_level, class.hit_die));
+        }
+        if combat.hit_dice_total.is_some() {
+            combat.hit_dice_total = Some(new_level as u8);
+        }
+
+        Ok(())
+    }
+
+    fn add_spells(
+        leveled: &mut CharacterData,
+        class: &ClassInfo,
+        new_level: u8,
+        new_spells: &[Spell],
+    ) -> Result<(), LevelUpError> {
+        if new_spells.is_empty() {
+            return Ok(());
+        }
+
+        let max_level = if class.name == "Warlock" {
+            Some(SpellSystem::pact_slot_level(new_level))
+        } else {
+            SpellSystem::get_spell_slots_for_single_class(new_level, class.caster_type)
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .map(|(level, _)| *level)
+                .max()
+        };
+        let Some(max_level) = max_level else {
+            return Err(LevelUpError::NoSpellcasting {
+                class: class.name.to_string(),
+                level: new_level,
+            });
+        };
+        // A Warlock learns one Mystic Arcanum spell above their pact slots at 11, 13, 15 and 17
+        let arcanum = match (class.name, new_level) {
+            ("Warlock", 11 | 13 | 15 | 17) => Some(new_level / 2 + 1),
+            _ => None,
+        };
+
+        let spells = leveled.spells.get_or_insert_with(|| Spells {
+            spellcasting_class: Some(class.name.to_string()),
+            spellcasting_ability: class.spellcasting_ability.map(str::to_string),
+            ..Spells::default()
+        });
+
+        for spell in new_spells {
+            let Some(list) = spells.level_mut(spell.level) else {
+                return Err(LevelUpError::InvalidSpellLevel {
+                    spell: spell.name.clone(),
+                    level: spell.level,
+                });
+            };
+            if spell.level > max_level && Some(spell.level) != arcanum {
+                return Err(LevelUpError::SpellLevelTooHigh {
+                    spell: spell.name.clone(),
+                    level: spell.level,
+                    max_level,
+                });
+            }
+            if !list.iter().any(|known| known.name.eq_ignore_ascii_case(&spell.name)) {
+                list.push(spell.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recursively compare two JSON values, recording changed leaves and arrays
+    fn diff(path: &str, before: &Value, after: &Value, changes: &mut Vec<FieldChange>) {
+        match (before, after) {
+            (Value::Object(old), Value::Object(new)) => {
+                let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+                keys.sort();
+                keys.dedup();
+                for key in keys {
+                    let child = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    Self::diff(
+                        &child,
+                        old.get(key).unwrap_or(&Value::Null),
+                        new.get(key).unwrap_or(&Value::Null),
+                        changes,
+                    );
+                }
+            }
+            _ if before != after => changes.push(FieldChange {
+                path: path.to_string(),
+                before: before.clone(),
+                after: after.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    /// Record values that aren't stored on the character but change on the sheet
+    fn diff_derived(before: &CharacterData, after: &CharacterData, changes: &mut Vec<FieldChange>) {
+        let old = DerivedStats::calculate(before);
+        let new = DerivedStats::calculate(after);
+
+        let mut push = |path: &str, before: Value, after: Value| {
+            if before != after {
+                changes.push(FieldChange {
+                    path: format!("derived.{}", path),
+                    before,
+                    after,
+                });
+            }
+        };
+
+        push(
+            "proficiency_bonus",
+            old.proficiency_bonus.into(),
+            new.proficiency_bonus.into(),
+        );
+        for ability in AbilityScores::NAMES {
+            push(
+                ability,
+                old.abilities.get_ability_score(ability).into(),
+                new.abilities.get_ability_score(ability).into(),
+            );
+        }
+        push("initiative", old.initiative.into(), new.initiative.into());
+        push(
+            "passive_perception",
+            old.passive_perception.into(),
+            new.passive_perception.into(),
+        );
+    }
+}
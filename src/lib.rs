@@ -1,8 +1,37 @@
+pub mod character_diff;
 pub mod character_model;
+pub mod character_store;
+pub mod class_catalog;
+pub mod config;
+pub mod derived_stats;
 pub mod dnd_validator;
+pub mod dndbeyond_importer;
 pub mod errors;
+pub mod feat_catalog;
 pub mod field_mapper;
+pub mod fight_club_exporter;
+pub mod foundry_exporter;
+pub mod html_exporter;
+pub mod http_transport;
+pub mod image_embedder;
+pub mod level_up;
+pub mod logging;
+pub mod markdown_exporter;
+pub mod markdown_importer;
 pub mod narrative_handler;
+pub mod party;
+pub mod party_sheets;
 pub mod pdf_filler;
+pub mod proficiency_handler;
+pub mod prompt_library;
+pub mod race_catalog;
+pub mod resource_provider;
+pub mod schema;
+pub mod schema_migration;
+pub mod session_log;
+pub mod sheet_flattener;
+pub mod sheet_writer;
+pub mod spell_catalog;
 pub mod spell_system;
+pub mod weapon_catalog;
 pub mod mcp_server;
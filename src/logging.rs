@@ -0,0 +1,313 @@
+use crate::errors::LoggingError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+pub const LEVEL_ENV: &str = "DND_MCP_LOG_LEVEL";
+pub const FILE_ENV: &str = "DND_MCP_LOG_FILE";
+
+/// Raw requests can carry whole characters and base64 PDFs; keep log lines readable
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// MCP log levels (RFC 5424 severities), least severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Notice => "notice",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+            LogLevel::Critical => "critical",
+            LogLevel::Alert => "alert",
+            LogLevel::Emergency => "emergency",
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = LoggingError;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.trim().to_lowercase().as_str() {
+            "debug" | "trace" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "notice" => Ok(LogLevel::Notice),
+            "warning" | "warn" => Ok(LogLevel::Warning),
+            "error" => Ok(LogLevel::Error),
+            "critical" => Ok(LogLevel::Critical),
+            "alert" => Ok(LogLevel::Alert),
+            "emergency" => Ok(LogLevel::Emergency),
+            _ => Err(LoggingError::InvalidLevel(level.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogTarget {
+    Stderr,
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub level: LogLevel,
+    pub target: LogTarget,
+    /// A log file is rotated once it would grow past this size
+    pub max_file_bytes: u64,
+    /// Rotated files kept next to the log (`server.log.1` is the newest)
+    pub max_files: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Info,
+            target: LogTarget::Stderr,
+            max_file_bytes: 5 * 1024 * 1024,
+            max_files: 3,
+        }
+    }
+}
+
+impl LogConfig {
+    /// Defaults overridden by `DND_MCP_LOG_LEVEL` and `DND_MCP_LOG_FILE`
+    pub fn from_env() -> Result<Self, LoggingError> {
+        Self::default().with_env()
+    }
+
+    /// This configuration overridden by `DND_MCP_LOG_LEVEL` and `DND_MCP_LOG_FILE`
+    pub fn with_env(mut self) -> Result<Self, LoggingError> {
+        if let Ok(level) = std::env::var(LEVEL_ENV) {
+            self.level = level.parse()?;
+        }
+        if let Ok(path) = std::env::var(FILE_ENV) {
+            if !path.trim().is_empty() {
+                self.target = LogTarget::File(PathBuf::from(path));
+            }
+        }
+        Ok(self)
+    }
+}
+
+struct Sink {
+    config: LogConfig,
+    file: Option<File>,
+    written: u64,
+}
+
+impl Sink {
+    fn open(config: LogConfig) -> Result<Self, LoggingError> {
+        let (file, written) = match &config.target {
+            LogTarget::Stderr => (None, 0),
+            LogTarget::File(path) => {
+                let file = open_append(path)?;
+                let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+                (Some(file), written)
+            }
+        };
+        Ok(Self {
+            config,
+            file,
+            written,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) {
+        let LogTarget::File(path) = &self.config.target else {
+            eprintln!("{}", line);
+            return;
+        };
+        let path = path.clone();
+
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.config.max_file_bytes {
+            self.rotate(&path);
+        }
+        let written = self
+            .file
+            .as_mut()
+            .map(|file| writeln!(file, "{}", line).is_ok())
+            .unwrap_or(false);
+        if written {
+            self.written += len;
+        } else {
+            // Never lose a message just because the log file went away
+            eprintln!("{}", line);
+        }
+    }
+
+    /// `server.log` -> `server.log.1` -> ... -> `server.log.N`, dropping the oldest
+    fn rotate(&mut self, path: &Path) {
+        self.file = None;
+        let rotated = |index: usize| PathBuf::from(format!("{}.{}", path.display(), index));
+        if self.config.max_files == 0 {
+            let _ = fs::remove_file(path);
+        } else {
+            let _ = fs::remove_file(rotated(self.config.max_files));
+            for index in (1..self.config.max_files).rev() {
+                let _ = fs::rename(rotated(index), rotated(index + 1));
+            }
+            let _ = fs::rename(path, rotated(1));
+        }
+        self.file = open_append(path).ok();
+        self.written = 0;
+    }
+}
+
+fn open_append(path: &Path) -> Result<File, LoggingError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        let _ = fs::create_dir_all(parent);
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| LoggingError::OpenFailed {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })
+}
+
+static SINK: OnceLock<Mutex<Sink>> = OnceLock::new();
+
+/// Log messages that should also reach the MCP client handling the current request
+struct ClientCapture {
+    level: LogLevel,
+    notifications: Vec<Value>,
+}
+
+thread_local! {
+    static CLIENT_CAPTURE: RefCell<Option<ClientCapture>> = const { RefCell::new(None) };
+}
+
+/// Process-wide leveled logger writing to stderr or a rotating file
+pub struct Logger;
+
+impl Logger {
+    /// Install the logger configuration; replaces any earlier one
+    pub fn init(config: LogConfig) -> Result<(), LoggingError> {
+        let sink = Sink::open(config)?;
+        let slot = SINK.get_or_init(|| Mutex::new(Self::default_sink()));
+        if let Ok(mut current) = slot.lock() {
+            *current = sink;
+        }
+        Ok(())
+    }
+
+    pub fn log(level: LogLevel, logger: &str, message: &str) {
+        let message = truncate(message);
+        Self::forward(level, logger, &message);
+
+        let Ok(mut sink) = SINK.get_or_init(|| Mutex::new(Self::default_sink())).lock() else {
+            return;
+        };
+        if level < sink.config.level {
+            return;
+        }
+        let line = format!(
+            "{} {:<9} {}: {}",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            level.as_str().to_uppercase(),
+            logger,
+            message
+        );
+        sink.write_line(&line);
+    }
+
+    pub fn debug(logger: &str, message: &str) {
+        Self::log(LogLevel::Debug, logger, message);
+    }
+
+    pub fn info(logger: &str, message: &str) {
+        Self::log(LogLevel::Info, logger, message);
+    }
+
+    pub fn warning(logger: &str, message: &str) {
+        Self::log(LogLevel::Warning, logger, message);
+    }
+
+    pub fn error(logger: &str, message: &str) {
+        Self::log(LogLevel::Error, logger, message);
+    }
+
+    /// Run `f`, collecting messages at or above `client_level` logged on this
+    /// thread as MCP `notifications/message` for the client
+    pub fn capture_for_client<T>(client_level: Option<LogLevel>, f: impl FnOnce() -> T) -> (T, Vec<Value>) {
+        let Some(level) = client_level else {
+            return (f(), Vec::new());
+        };
+
+        let previous = CLIENT_CAPTURE.with(|capture| {
+            capture.borrow_mut().replace(ClientCapture {
+                level,
+                notifications: Vec::new(),
+            })
+        });
+        let result = f();
+        let captured = CLIENT_CAPTURE.with(|capture| std::mem::replace(&mut *capture.borrow_mut(), previous));
+        (result, captured.map(|c| c.notifications).unwrap_or_default())
+    }
+
+    fn forward(level: LogLevel, logger: &str, message: &str) {
+        CLIENT_CAPTURE.with(|capture| {
+            if let Some(capture) = capture.borrow_mut().as_mut() {
+                if level >= capture.level {
+                    capture.notifications.push(json!({
+                        "jsonrpc": "2.0",
+                        "method": "notifications/message",
+                        "params": {
+                            "level": level,
+                            "logger": logger,
+                            "data": message
+                        }
+                    }));
+                }
+            }
+        });
+    }
+
+    /// Used until `init` is called: environment settings, falling back to stderr
+    fn default_sink() -> Sink {
+        LogConfig::from_env()
+            .and_then(Sink::open)
+            .or_else(|_| Sink::open(LogConfig::default()))
+            .expect("stderr logging needs no setup")
+    }
+}
+
+fn truncate(message: &str) -> String {
+    match message.char_indices().nth(MAX_MESSAGE_CHARS) {
+        Some((cut, _)) => format!(
+            "{}... ({} more bytes)",
+            &message[..cut],
+            message.len() - cut
+        ),
+        None => message.to_string(),
+    }
+}
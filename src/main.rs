@@ -1,269 +1,519 @@
-mod character_model;
-mod dnd_validator;
-mod errors;
-mod field_mapper;
-mod mcp_server;
-mod narrative_handler;
-mod pdf_filler;
-mod spell_system;
-
+use clap::{Parser, Subcommand};
+use dnd_character_sheet_filler::character_model::{AdvancementMode, CharacterData, CURRENT_SCHEMA_VERSION};
+use dnd_character_sheet_filler::character_store::CharacterStore;
+use dnd_character_sheet_filler::config::ServerConfig;
+use dnd_character_sheet_filler::dnd_validator::DndValidator;
+use dnd_character_sheet_filler::errors::PdfError;
+use dnd_character_sheet_filler::http_transport::{HttpConfig, HttpTransport};
+use dnd_character_sheet_filler::logging::{LogTarget, Logger};
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use dnd_character_sheet_filler::pdf_filler::PdfFiller;
+use dnd_character_sheet_filler::schema::SchemaGenerator;
+use dnd_character_sheet_filler::schema_migration::SchemaMigration;
+use dnd_character_sheet_filler::sheet_writer::SheetWriter;
 use lopdf::{Document, Object};
-use mcp_server::McpServer;
-use std::collections::HashMap;
-use std::env;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+type CliResult = Result<ExitCode, Box<dyn std::error::Error>>;
 
-    if args.len() > 1 && args[1] == "test" {
-        println!("Test mode - MCP server functionality verified");
-        return;
-    }
+/// Fill D&D 5e character sheet PDFs, from the command line or as an MCP server
+#[derive(Parser)]
+#[command(name = "dnd-character-sheet-filler", version)]
+struct Cli {
+    /// Config file (default: $DND_MCP_CONFIG, then ./dnd-mcp.toml if present)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Log level: debug, info, notice, warning, error, critical, alert or emergency
+    #[arg(long, global = true)]
+    log_level: Option<String>,
+
+    /// Write logs to this file (rotated) instead of stderr
+    #[arg(long, global = true)]
+    log_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-    if args.len() > 1 && args[1] == "bob" {
-        if let Err(e) = simple_bob_test() {
-            eprintln!("Bob test error: {}", e);
+#[derive(Subcommand)]
+enum Command {
+    /// Run the MCP server on stdio, or over HTTP with --http (the default command)
+    Serve {
+        /// Serve streamable HTTP instead of stdio
+        #[arg(long)]
+        http: bool,
+        /// Address for the HTTP transport; other than loopback it needs a token
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        bind: String,
+        /// Bearer token required by the HTTP transport (or $DND_MCP_AUTH_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Fill character sheet PDFs from character_data JSON files, in parallel
+    Fill {
+        /// character_data JSON files, directories of them, or - for stdin
+        /// (one character or an array of them)
+        #[arg(required = true)]
+        characters: Vec<PathBuf>,
+        /// Output PDF for a single character, or a directory (an existing
+        /// one or a path ending in /) for one or more
+        /// (default: <character>.pdf in the output directory)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// PDF template to fill instead of the configured one
+        #[arg(long)]
+        template: Option<PathBuf>,
+        /// Fill the sheet even when the character breaks D&D 5e rules
+        #[arg(long)]
+        allow_violations: bool,
+        /// Level characters by "xp" or "milestone" instead of their own
+        /// advancement_mode; milestone skips the XP-for-level check
+        #[arg(long, value_parser = parse_advancement_mode)]
+        advancement_mode: Option<AdvancementMode>,
+        /// Replace an existing output file instead of writing "name (2).pdf"
+        #[arg(long)]
+        overwrite: bool,
+        /// Draw the values into the pages and remove the form fields
+        #[arg(long)]
+        flatten: bool,
+    },
+    /// Check a character_data JSON file against the D&D 5e rules
+    Validate {
+        /// character_data JSON file
+        character: PathBuf,
+        /// Report rule violations as warnings instead of failing
+        #[arg(long)]
+        allow_violations: bool,
+        /// Level characters by "xp" or "milestone" instead of their own
+        /// advancement_mode; milestone skips the XP-for-level check
+        #[arg(long, value_parser = parse_advancement_mode)]
+        advancement_mode: Option<AdvancementMode>,
+    },
+    /// Print the filled-in form field values of a PDF
+    Read {
+        pdf: PathBuf,
+    },
+    /// List the form fields of a PDF (default: the configured template)
+    Inspect {
+        pdf: Option<PathBuf>,
+    },
+    /// Print the character_data JSON Schema, or write it to a file
+    Schema {
+        output: Option<PathBuf>,
+    },
+    /// Rewrite character_data JSON files at the current schema_version,
+    /// keeping each original as <file>.v<N>.bak
+    Migrate {
+        /// character_data JSON files or directories of them
+        #[arg(required = true)]
+        characters: Vec<PathBuf>,
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let config = match load_config(&cli) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
         }
-        return;
-    }
+    };
 
-    if args.len() > 1 && args[1] == "test-main" {
-        if let Err(e) = test_main_pdf_filler() {
-            eprintln!("Main PDF filler test error: {}", e);
+    let command = cli.command.unwrap_or(Command::Serve {
+        http: false,
+        bind: HttpConfig::default().bind_address,
+        token: None,
+    });
+    let result = match command {
+        Command::Serve { http, bind, token } => serve(config, http, bind, token),
+        Command::Fill {
+            characters,
+            output,
+            template,
+            allow_violations,
+            advancement_mode,
+            overwrite,
+            flatten,
+        } => {
+            let options = FillOptions {
+                output,
+                template,
+                allow_violations,
+                advancement_mode,
+                overwrite,
+                flatten,
+            };
+            fill(config, &characters, options)
         }
-        return;
+        Command::Validate {
+            character,
+            allow_violations,
+            advancement_mode,
+        } => validate(&config, &character, allow_violations, advancement_mode),
+        Command::Read { pdf } => read_pdf_fields(&pdf),
+        Command::Inspect { pdf } => inspect_pdf_fields(&config, pdf.as_deref()),
+        Command::Schema { output } => write_schema(output.as_deref()),
+        Command::Migrate { characters, dry_run } => migrate(&characters, dry_run),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        ExitCode::FAILURE
+    })
+}
+
+/// Config file settings, with `--log-level` and `--log-file` applied and the logger started
+fn load_config(cli: &Cli) -> Result<ServerConfig, Box<dyn std::error::Error>> {
+    let config = ServerConfig::load(cli.config.as_deref())?;
+
+    let mut log_config = config.log_config()?;
+    if let Some(level) = &cli.log_level {
+        log_config.level = level.parse()?;
+    }
+    if let Some(path) = &cli.log_file {
+        log_config.target = LogTarget::File(path.clone());
     }
+    Logger::init(log_config)?;
+    Ok(config)
+}
 
-    if args.len() > 1 && args[1] == "test-docs" {
-        if let Err(e) = test_docs_data() {
-            eprintln!("Docs test error: {}", e);
-        }
-        return;
+fn serve(config: ServerConfig, http: bool, bind: String, token: Option<String>) -> CliResult {
+    let server = McpServer::with_config(config)?;
+    if http {
+        let config = HttpConfig {
+            bind_address: bind,
+            bearer_token: token.or_else(|| std::env::var("DND_MCP_AUTH_TOKEN").ok()).filter(|token| !token.is_empty()),
+        };
+        HttpTransport::bind(server, &config)?.run()?;
+    } else {
+        server.run()?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+struct FillOptions {
+    output: Option<String>,
+    template: Option<PathBuf>,
+    allow_violations: bool,
+    advancement_mode: Option<AdvancementMode>,
+    overwrite: bool,
+    flatten: bool,
+}
+
+/// Whether `-o` names a directory: an existing one, or any path ending in a separator
+fn is_directory(config: &ServerConfig, output: &str) -> bool {
+    output.ends_with(std::path::is_separator) || config.output_dir().join(output).is_dir()
+}
+
+/// One character to fill: where it came from and the sheet name to use
+struct FillJob {
+    source: String,
+    default_name: String,
+    character: Result<CharacterData, String>,
+}
+
+enum FillOutcome {
+    Filled {
+        output_file: String,
+        warnings: Vec<String>,
+    },
+    Failed(Vec<String>),
+}
+
+fn fill(mut config: ServerConfig, inputs: &[PathBuf], options: FillOptions) -> CliResult {
+    if options.template.is_some() {
+        config.template = options.template;
+    }
+    let template = config.template_bytes()?;
+    let jobs = collect_fill_jobs(inputs)?;
+    if jobs.is_empty() {
+        return Err("No character_data JSON files found".into());
     }
 
-    if args.len() > 1 && args[1] == "read" {
-        let filename = if args.len() > 2 { &args[2] } else { "test_ap.pdf" };
-        if let Err(e) = read_pdf_fields(filename) {
-            eprintln!("Read error: {}", e);
+    // A single character may be written to any -o file; several, or a -o that is a directory,
+    // go into that directory.
+    // The person at the terminal chose the path, so unlike the MCP tools it isn't confined
+    // to the output roots; relative paths are still under the output directory
+    let output_for = |job: &FillJob| -> PathBuf {
+        let requested = match (&options.output, jobs.len()) {
+            (Some(output), 1) if !is_directory(&config, output) => PathBuf::from(output),
+            (Some(dir), _) => Path::new(dir).join(&job.default_name),
+            (None, _) => PathBuf::from(&job.default_name),
+        };
+        config.output_dir().join(requested)
+    };
+    let filler = PdfFiller::new(options.allow_violations || config.allow_rule_violations)
+        .with_overwrite(options.overwrite)
+        .with_flatten(options.flatten)
+        .with_advancement_mode(options.advancement_mode);
+
+    let fill_one = |job: &FillJob| -> FillOutcome {
+        let character = match &job.character {
+            Ok(character) => character,
+            Err(e) => return FillOutcome::Failed(vec![e.clone()]),
+        };
+        let output_path = output_for(job);
+        match filler.fill_character_sheet_from_bytes(character, &template, &output_path.display().to_string()) {
+            Ok(result) => FillOutcome::Filled {
+                output_file: result.output_file,
+                warnings: result.validation_errors.iter().map(|w| w.to_string()).collect(),
+            },
+            Err(PdfError::ValidationFailed(errors)) => {
+                FillOutcome::Failed(errors.iter().map(|e| e.to_string()).collect())
+            }
+            Err(e) => FillOutcome::Failed(vec![e.to_string()]),
+        }
+    };
+    let outcomes = run_parallel(&jobs, fill_one);
+
+    let mut failed = 0;
+    for (job, outcome) in jobs.iter().zip(&outcomes) {
+        match outcome {
+            FillOutcome::Filled { output_file, warnings } => {
+                for warning in warnings {
+                    eprintln!("warning: {}: {}", job.source, warning);
+                }
+                println!("{}", output_file);
+            }
+            FillOutcome::Failed(errors) => {
+                failed += 1;
+                eprintln!("{}:", job.source);
+                for error in errors {
+                    eprintln!("  error: {}", error);
+                }
+            }
         }
-        return;
     }
 
-    let server = McpServer::new();
-    if let Err(e) = server.run() {
-        eprintln!("Server error: {}", e);
+    if failed > 0 {
+        eprintln!(
+            "{} of {} character(s) failed; fix them or pass --allow-violations",
+            failed,
+            jobs.len()
+        );
+        return Ok(ExitCode::FAILURE);
     }
+    Ok(ExitCode::SUCCESS)
 }
 
-#[allow(dead_code)]
-fn extract_debug_fields() -> Result<(), Box<dyn std::error::Error>> {
-    let doc = Document::load("debug-sheet.pdf")?;
-    let mut field_values = HashMap::new();
-
-    // Extract all form field values
-    for (_page_id, page_obj) in doc.get_pages() {
-        if let Ok(page) = doc.get_object(page_obj) {
-            if let Object::Dictionary(page_dict) = page {
-                if let Ok(Object::Reference(annots_ref)) = page_dict.get(b"Annots") {
-                    if let Ok(Object::Array(annots)) = doc.get_object(*annots_ref) {
-                        for annot_ref in annots {
-                            if let Object::Reference(ref_id) = annot_ref {
-                                if let Ok(Object::Dictionary(field_dict)) = doc.get_object(*ref_id)
-                                {
-                                    if let Ok(Object::Name(subtype)) = field_dict.get(b"Subtype") {
-                                        if subtype == b"Widget" {
-                                            if let Ok(Object::String(field_name, _)) =
-                                                field_dict.get(b"T")
-                                            {
-                                                let name_str = String::from_utf8_lossy(field_name);
-
-                                                // Get field value
-                                                if let Ok(Object::String(field_value, _)) =
-                                                    field_dict.get(b"V")
-                                                {
-                                                    let value_str =
-                                                        String::from_utf8_lossy(field_value);
-                                                    if !value_str.is_empty() {
-                                                        field_values.insert(
-                                                            name_str.to_string(),
-                                                            value_str.to_string(),
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+/// Expand the fill inputs: files, `*.json` in directories (sorted), and `-` for stdin
+fn collect_fill_jobs(inputs: &[PathBuf]) -> Result<Vec<FillJob>, Box<dyn std::error::Error>> {
+    let mut jobs = Vec::new();
+    for input in inputs {
+        if input.as_os_str() == "-" {
+            let mut json = String::new();
+            std::io::stdin().read_to_string(&mut json)?;
+            let value: serde_json::Value = serde_json::from_str(&json)
+                .map_err(|e| format!("Invalid character data on stdin: {}", e))?;
+            let characters = match value {
+                serde_json::Value::Array(characters) => characters,
+                character => vec![character],
+            };
+            for (index, character) in characters.into_iter().enumerate() {
+                let source = format!("stdin[{}]", index);
+                let character = serde_json::from_value::<CharacterData>(character)
+                    .map_err(|e| format!("Invalid character data: {}", e));
+                let default_name = match &character {
+                    Ok(character) => format!("{}.pdf", file_name_for(&character.character.name)),
+                    Err(_) => format!("character-{}.pdf", index + 1),
+                };
+                jobs.push(FillJob {
+                    source,
+                    default_name,
+                    character,
+                });
             }
+        } else if input.is_dir() {
+            jobs.extend(json_files_in(input)?.iter().map(|file| FillJob::from_file(file)));
+        } else {
+            jobs.push(FillJob::from_file(input));
         }
     }
+    Ok(jobs)
+}
+
+/// The `*.json` files directly in `dir`, sorted
+fn json_files_in(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Cannot read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
 
-    // Print cantrip mappings
-    println!("=== CANTRIP MAPPINGS ===");
-    for (field_name, value) in &field_values {
-        if value.starts_with("Cantrip ") {
-            println!("Field '{}' = '{}'", field_name, value);
+impl FillJob {
+    fn from_file(path: &Path) -> Self {
+        Self {
+            source: path.display().to_string(),
+            default_name: format!("{}.pdf", path.file_stem().unwrap_or_default().to_string_lossy()),
+            character: load_character(path).map_err(|e| e.to_string()),
         }
     }
+}
+
+/// "Erilon Silverleaf" -> "erilon-silverleaf"
+fn file_name_for(character_name: &str) -> String {
+    CharacterStore::id_for(character_name)
+}
 
-    // Print spell level mappings
-    println!("\n=== SPELL LEVEL MAPPINGS ===");
-    for level in 1..=9 {
-        println!("\nLevel {} spells:", level);
-        for (field_name, value) in &field_values {
-            if value.starts_with(&format!("Level {}, ", level)) {
-                println!("Field '{}' = '{}'", field_name, value);
+/// Run `task` over `items` on up to one thread per core, keeping the input order
+fn run_parallel<T: Sync, R: Send>(items: &[T], task: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(items.len())
+        .max(1);
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else { break };
+                let result = task(item);
+                results.lock().unwrap().push((index, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn validate(
+    config: &ServerConfig,
+    character_path: &Path,
+    allow_violations: bool,
+    advancement_mode: Option<AdvancementMode>,
+) -> CliResult {
+    let character = load_character(character_path)?;
+    let validator = DndValidator::new(allow_violations || config.allow_rule_violations)
+        .with_advancement_mode(advancement_mode);
+    match validator.validate(&character) {
+        Ok(warnings) => {
+            for warning in &warnings {
+                println!("warning: {}", warning);
             }
+            println!("{} is valid", character.character.name);
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(errors) => {
+            for error in &errors {
+                println!("error: {}", error);
+            }
+            Ok(ExitCode::FAILURE)
         }
     }
+}
 
-    Ok(())
+/// `--advancement-mode xp` or `milestone`
+fn parse_advancement_mode(value: &str) -> Result<AdvancementMode, String> {
+    serde_json::from_value(serde_json::Value::from(value.to_lowercase()))
+        .map_err(|_| format!("'{}' is not xp or milestone", value))
 }
 
-fn simple_bob_test() -> Result<(), Box<dyn std::error::Error>> {
-    println!("=== SIMPLE BOB TEST ===");
-    
-    // Load the PDF
-    let mut doc = Document::load("test_character_sheet.pdf")?;
-    
-    // Find and update fields
-    let mut found_fields = 0;
-    for (object_id, object) in doc.objects.clone() {
-        if let Object::Dictionary(dict) = object {
-            if let Ok(Object::String(field_name_bytes, _)) = dict.get(b"T") {
-                let field_name = String::from_utf8_lossy(&field_name_bytes);
-                
-                let mut new_dict = dict.clone();
-                let mut updated = false;
-                
-                // Character name
-                if field_name == "CharacterName" || field_name == "Character Name" || field_name == "Name" {
-                    new_dict.set(b"V", Object::String(b"Bob".to_vec(), lopdf::StringFormat::Literal));
-                    println!("Set {} to 'Bob'", field_name);
-                    updated = true;
-                }
-                // Class & Level variations
-                else if field_name == "ClassLevel" || field_name == "Class & Level" || field_name == "Class" || field_name == "Level" {
-                    new_dict.set(b"V", Object::String(b"Wizard 6".to_vec(), lopdf::StringFormat::Literal));
-                    println!("Set {} to 'Wizard 6'", field_name);
-                    updated = true;
-                }
-                // Alignment
-                else if field_name == "Alignment" {
-                    new_dict.set(b"V", Object::String(b"Chaotic Evil".to_vec(), lopdf::StringFormat::Literal));
-                    println!("Set {} to 'Chaotic Evil'", field_name);
-                    updated = true;
-                }
-                
-                if updated {
-                    doc.objects.insert(object_id, Object::Dictionary(new_dict));
-                    found_fields += 1;
-                }
+fn load_character(path: &Path) -> Result<CharacterData, Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Invalid character data in {}: {}", path.display(), e).into())
+}
+
+/// Migrate each file (one character or an array of them) in place; the
+/// original is kept as `<file>.v<N>.bak`, N being its oldest version
+fn migrate(inputs: &[PathBuf], dry_run: bool) -> CliResult {
+    let mut files = Vec::new();
+    for input in inputs {
+        match input.is_dir() {
+            true => files.extend(json_files_in(input)?),
+            false => files.push(input.clone()),
+        }
+    }
+    if files.is_empty() {
+        return Err("No character_data JSON files found".into());
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        match migrate_file(file, dry_run) {
+            Ok(report) => println!("{}: {}", file.display(), report),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{}: error: {}", file.display(), e);
             }
         }
     }
-    
-    // Save the updated PDF
-    doc.save("bob_test.pdf")?;
-    println!("Saved bob_test.pdf with {} fields updated", found_fields);
-    
-    Ok(())
+    if failed > 0 {
+        eprintln!("{} of {} file(s) could not be migrated and were left unchanged", failed, files.len());
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
 }
 
-fn test_main_pdf_filler() -> Result<(), Box<dyn std::error::Error>> {
-    println!("=== TESTING MAIN PDF FILLER ===");
-    
-    // Create minimal character data
-    let character_data = character_model::CharacterData {
-        character: character_model::Character {
-            name: "Bob".to_string(),
-            class: "Wizard".to_string(),
-            level: 6,
-            background: Some("Scholar".to_string()),
-            player_name: Some("Test Player".to_string()),
-            race: "Human".to_string(),
-            alignment: Some("Chaotic Evil".to_string()),
-            experience_points: Some(14000),
-        },
-        abilities: character_model::AbilityScores {
-            strength: 10,
-            dexterity: 14,
-            constitution: 12,
-            intelligence: 16,
-            wisdom: 13,
-            charisma: 8,
-        },
-        proficiencies: None,
-        combat: None,
-        spells: None,
-        equipment: None,
-        narrative: None,
-        features_traits: None,
+fn migrate_file(path: &Path, dry_run: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let original = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let mut value: serde_json::Value = serde_json::from_str(&original)?;
+    let characters = match &mut value {
+        serde_json::Value::Array(characters) => characters.iter_mut().collect(),
+        character => vec![character],
     };
-    
-    println!("Created character: {}", character_data.character.name);
-    
-    // Create PDF filler
-    let pdf_filler = pdf_filler::PdfFiller::new(false);
-    
-    // Test with the existing PDF
-    let template_path = "test_character_sheet.pdf";
-    let output_path = "main_test.pdf";
-    
-    match pdf_filler.fill_character_sheet(&character_data, template_path, output_path) {
-        Ok(result) => {
-            println!("✅ Main PDF filler works!");
-            println!("Output file: {}", result.output_file);
-        }
-        Err(e) => {
-            println!("❌ Error with main PDF filler: {:?}", e);
-        }
+
+    let mut oldest = CURRENT_SCHEMA_VERSION as u64;
+    for (index, character) in characters.into_iter().enumerate() {
+        let from = SchemaMigration::migrate(character).map_err(|e| format!("character {}: {}", index + 1, e))?;
+        // Only rewrite files whose characters load once migrated
+        serde_json::from_value::<CharacterData>(character.clone())
+            .map_err(|e| format!("character {}: invalid character data: {}", index + 1, e))?;
+        oldest = oldest.min(from);
+    }
+    if oldest == CURRENT_SCHEMA_VERSION as u64 {
+        return Ok(format!("already at schema_version {}", CURRENT_SCHEMA_VERSION));
+    }
+    let change = format!("schema_version {} -> {}", oldest, CURRENT_SCHEMA_VERSION);
+    if dry_run {
+        return Ok(format!("{} (dry run, not written)", change));
     }
-    
-    Ok(())
+
+    let backup = PathBuf::from(format!("{}.v{}.bak", path.display(), oldest));
+    let backup = SheetWriter::write(&backup, original.as_bytes(), false)?;
+    let migrated = serde_json::to_string_pretty(&value)? + "\n";
+    SheetWriter::write(path, migrated.as_bytes(), true)?;
+    Ok(format!("{} (backup: {})", change, backup.display()))
 }
 
-fn test_docs_data() -> Result<(), Box<dyn std::error::Error>> {
-    use std::fs;
-    use serde_json;
-    
-    println!("=== TESTING DOCS DATA ===");
-    
-    // Load the docs test data
-    let character_json = fs::read_to_string("docs/test-data.json")?;
-    let character_data: character_model::CharacterData = serde_json::from_str(&character_json)?;
-    
-    println!("Loaded character: {}", character_data.character.name);
-    
-    // Create PDF filler
-    let pdf_filler = pdf_filler::PdfFiller::new(false);
-    
-    // Test with the same template as MCP server
-    let template_path = "docs/5E_CharacterSheet_Fillable.pdf";
-    let output_path = "docs_test.pdf";
-    
-    match pdf_filler.fill_character_sheet(&character_data, template_path, output_path) {
-        Ok(result) => {
-            println!("✅ Docs data PDF filler works!");
-            println!("Output file: {}", result.output_file);
-        }
-        Err(e) => {
-            println!("❌ Error with docs data PDF filler: {:?}", e);
+/// Print the character_data JSON Schema, or write it to `output_path`
+fn write_schema(output_path: Option<&Path>) -> CliResult {
+    let schema = serde_json::to_string_pretty(&SchemaGenerator::character_data_document())?;
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, schema + "\n")?;
+            eprintln!("Wrote character_data schema to {}", path.display());
         }
+        None => println!("{}", schema),
     }
-    
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
-fn read_pdf_fields(filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let doc = Document::load(filename)?;
+fn read_pdf_fields(path: &Path) -> CliResult {
+    let doc = Document::load(path)?;
 
     // Read all form field values
-    for (_object_id, object) in &doc.objects {
+    for object in doc.objects.values() {
         if let Object::Dictionary(dict) = object {
             if let Ok(Object::Name(subtype)) = dict.get(b"Subtype") {
                 if subtype == b"Widget" {
@@ -282,5 +532,35 @@ fn read_pdf_fields(filename: &str) -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// List every named form field with its type, sorted by name
+fn inspect_pdf_fields(config: &ServerConfig, path: Option<&Path>) -> CliResult {
+    let doc = match path {
+        Some(path) => Document::load(path)?,
+        None => Document::load_mem(&config.template_bytes()?)?,
+    };
+
+    let mut fields = BTreeMap::new();
+    for object in doc.objects.values() {
+        if let Object::Dictionary(dict) = object {
+            if let Ok(Object::String(field_name, _)) = dict.get(b"T") {
+                let field_type = match dict.get(b"FT") {
+                    Ok(Object::Name(name)) if name == b"Tx" => "text",
+                    Ok(Object::Name(name)) if name == b"Btn" => "button",
+                    Ok(Object::Name(name)) if name == b"Ch" => "choice",
+                    Ok(Object::Name(name)) if name == b"Sig" => "signature",
+                    _ => "other",
+                };
+                fields.insert(String::from_utf8_lossy(field_name).to_string(), field_type);
+            }
+        }
+    }
+
+    for (name, field_type) in &fields {
+        println!("{:<10} {}", field_type, name);
+    }
+    eprintln!("{} form fields", fields.len());
+    Ok(ExitCode::SUCCESS)
 }
@@ -0,0 +1,428 @@
+use crate::character_model::{
+    get_skill_ability, AbilityScores, AdvancementMode, CharacterData, Equipment, Improvement, Spell, SKILL_NAMES,
+};
+use crate::derived_stats::DerivedStats;
+use crate::proficiency_handler::ProficiencyHandler;
+use crate::spell_system::SpellSystem;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Proficiency dots, as on the printed sheet
+pub const PROFICIENT: &str = "●";
+pub const NOT_PROFICIENT: &str = "○";
+
+/// Renders a character as a Markdown sheet: key/value tables, ability, save
+/// and skill tables, spell lists by level with `- [x]` prepared markers,
+/// inventory and narrative. `MarkdownImporter` reads the same layout back.
+pub struct MarkdownExporter;
+
+impl MarkdownExporter {
+    pub fn export(character_data: &CharacterData) -> String {
+        let derived = DerivedStats::calculate(character_data);
+        let mut out = String::new();
+
+        Self::identity(&mut out, character_data);
+        Self::abilities(&mut out, character_data, &derived);
+        Self::combat(&mut out, character_data, &derived);
+        Self::proficiencies(&mut out, character_data);
+        Self::spellcasting(&mut out, character_data, &derived);
+        Self::features(&mut out, character_data, &derived);
+        Self::advancement(&mut out, character_data);
+        Self::equipment(&mut out, character_data);
+        Self::appearance(&mut out, character_data);
+        Self::narrative(&mut out, character_data);
+
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+
+    fn identity(out: &mut String, character_data: &CharacterData) {
+        let character = &character_data.character;
+        let milestone = character.advancement_mode == Some(AdvancementMode::Milestone);
+        let _ = writeln!(out, "# {}\n", character.name);
+        Self::key_values(
+            out,
+            &[
+                ("Class", Some(character.class.clone())),
+                ("Subclass", character.subclass.clone()),
+                ("Level", Some(character.level.to_string())),
+                ("Race", Some(character.race.clone())),
+                ("Background", character.background.clone()),
+                ("Alignment", character.alignment.clone()),
+                ("Player", character.player_name.clone()),
+                ("Experience Points", character.experience_points.filter(|_| !milestone).map(|xp| xp.to_string())),
+                ("Leveling", milestone.then(|| "Milestone".to_string())),
+            ],
+        );
+    }
+
+    fn abilities(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let base = &character_data.abilities;
+        let scores = &derived.abilities;
+        let proficiencies = character_data.proficiencies.as_ref();
+
+        out.push_str("## Ability Scores\n\n");
+        let rows: Vec<Vec<String>> = AbilityScores::NAMES
+            .iter()
+            .map(|ability| {
+                vec![
+                    Self::title(ability),
+                    scores.get_ability_score(ability).to_string(),
+                    Self::signed(scores.modifier(ability) as i16),
+                    base.get_ability_score(ability).to_string(),
+                ]
+            })
+            .collect();
+        Self::table(out, &["Ability", "Score", "Modifier", "Base"], &rows);
+
+        out.push_str("## Saving Throws\n\n");
+        let rows: Vec<Vec<String>> = AbilityScores::NAMES
+            .iter()
+            .map(|ability| {
+                let proficient = proficiencies.is_some_and(|p| p.saving_throws.iter().any(|s| s == ability));
+                vec![
+                    Self::title(ability),
+                    Self::dot(proficient).to_string(),
+                    Self::signed(Self::bonus(scores, ability, proficient, derived.proficiency_bonus)),
+                ]
+            })
+            .collect();
+        Self::table(out, &["Saving Throw", "Proficient", "Bonus"], &rows);
+
+        out.push_str("## Skills\n\n");
+        let rows: Vec<Vec<String>> = SKILL_NAMES
+            .iter()
+            .map(|skill| {
+                let ability = get_skill_ability(skill);
+                let proficient = proficiencies.is_some_and(|p| p.skills.iter().any(|s| s == skill));
+                vec![
+                    Self::title(skill),
+                    Self::title(&ability[..3]),
+                    Self::dot(proficient).to_string(),
+                    Self::signed(Self::bonus(scores, ability, proficient, derived.proficiency_bonus)),
+                ]
+            })
+            .collect();
+        Self::table(out, &["Skill", "Ability", "Proficient", "Bonus"], &rows);
+    }
+
+    fn combat(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let combat = character_data.combat.clone().unwrap_or_default();
+        let conditions = combat.conditions.as_ref().filter(|c| !c.is_empty()).map(|c| c.join(", "));
+
+        out.push_str("## Combat\n\n");
+        Self::key_values(
+            out,
+            &[
+                ("Armor Class", combat.armor_class.map(|ac| ac.to_string())),
+                ("Initiative", Some(Self::signed(combat.initiative.unwrap_or(derived.initiative) as i16))),
+                ("Speed", combat.speed.map(|speed| format!("{} ft", speed))),
+                (
+                    "Hit Point Maximum",
+                    combat.hit_point_maximum.or(derived.hit_point_maximum).map(|hp| hp.to_string()),
+                ),
+                ("Current Hit Points", combat.current_hit_points.map(|hp| hp.to_string())),
+                ("Temporary Hit Points", combat.temporary_hit_points.map(|hp| hp.to_string())),
+                ("Hit Dice", combat.hit_dice.clone()),
+                ("Proficiency Bonus", Some(Self::signed(derived.proficiency_bonus as i16))),
+                ("Passive Perception", Some(derived.passive_perception.to_string())),
+                ("Inspiration", combat.inspiration.map(|i| if i { "Yes" } else { "No" }.to_string())),
+                ("Death Save Successes", combat.death_save_successes.map(|n| n.to_string())),
+                ("Death Save Failures", combat.death_save_failures.map(|n| n.to_string())),
+                ("Exhaustion", combat.exhaustion_level.map(|n| n.to_string())),
+                ("Conditions", conditions),
+            ],
+        );
+    }
+
+    fn proficiencies(out: &mut String, character_data: &CharacterData) {
+        let Some(proficiencies) = &character_data.proficiencies else {
+            return;
+        };
+        let merged = ProficiencyHandler::merge(proficiencies);
+        let tools: Vec<String> = merged
+            .tools
+            .iter()
+            .map(|tool| match merged.tool_expertise.iter().any(|e| e.eq_ignore_ascii_case(tool)) {
+                true => format!("{} (expertise)", tool),
+                false => tool.clone(),
+            })
+            .collect();
+        let groups = [
+            ("Armor", &merged.armor),
+            ("Weapons", &merged.weapons),
+            ("Tools", &tools),
+            ("Languages", &merged.languages),
+        ];
+        if groups.iter().all(|(_, items)| items.is_empty()) {
+            return;
+        }
+
+        out.push_str("## Proficiencies\n\n");
+        for (label, items) in groups {
+            if !items.is_empty() {
+                let _ = writeln!(out, "- **{}:** {}", label, items.join(", "));
+            }
+        }
+        out.push('\n');
+    }
+
+    fn spellcasting(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let Some(spells) = &character_data.spells else {
+            return;
+        };
+        let (save_dc, attack_bonus) = match &spells.spellcasting_ability {
+            Some(ability) => {
+                let modifier = SpellSystem::get_spellcasting_ability_modifier(ability, &derived.abilities);
+                (
+                    Some(SpellSystem::calculate_spell_save_dc(modifier, derived.proficiency_bonus).to_string()),
+                    Some(Self::signed(
+                        SpellSystem::calculate_spell_attack_bonus(modifier, derived.proficiency_bonus) as i16,
+                    )),
+                )
+            }
+            None => (None, None),
+        };
+
+        out.push_str("## Spellcasting\n\n");
+        Self::key_values(
+            out,
+            &[
+                ("Spellcasting Class", spells.spellcasting_class.clone()),
+                ("Spellcasting Ability", spells.spellcasting_ability.clone()),
+                ("Spell Save DC", save_dc),
+                ("Spell Attack Bonus", attack_bonus),
+            ],
+        );
+
+        let slots = SpellSystem::calculate_spell_slots(character_data);
+        for (level, list) in SpellSystem::organize_spells_by_level(spells) {
+            match level {
+                0 => out.push_str("### Cantrips\n\n"),
+                _ => match slots.get(&format!("spell_slots_{}", level)).copied().unwrap_or(0) {
+                    0 => {
+                        let _ = writeln!(out, "### Level {}\n", level);
+                    }
+                    count => {
+                        let _ = writeln!(out, "### Level {} ({} slots)\n", level, count);
+                    }
+                },
+            }
+            for spell in list {
+                out.push_str(&Self::spell_line(spell));
+            }
+            out.push('\n');
+        }
+    }
+
+    fn spell_line(spell: &Spell) -> String {
+        match (spell.level, spell.prepared) {
+            (0, _) => format!("- {}\n", spell.name),
+            (_, true) => format!("- [x] {}\n", spell.name),
+            (_, false) => format!("- [ ] {}\n", spell.name),
+        }
+    }
+
+    fn features(out: &mut String, character_data: &CharacterData, derived: &DerivedStats) {
+        let features_traits = character_data.features_traits.as_ref();
+        let feats = derived.feat_notes();
+        let lists = [
+            ("Features", features_traits.and_then(|f| f.features.clone()).unwrap_or_default()),
+            ("Traits", features_traits.and_then(|f| f.traits.clone()).unwrap_or_default()),
+            (
+                "Additional Features & Traits",
+                features_traits.and_then(|f| f.additional.clone()).unwrap_or_default(),
+            ),
+            ("Feats", feats),
+        ];
+        if lists.iter().all(|(_, items)| items.is_empty()) {
+            return;
+        }
+
+        out.push_str("## Features & Traits\n\n");
+        for (heading, items) in lists {
+            if !items.is_empty() {
+                let _ = writeln!(out, "### {}\n", heading);
+                Self::bullets(out, &items);
+            }
+        }
+    }
+
+    fn advancement(out: &mut String, character_data: &CharacterData) {
+        let Some(history) = character_data.level_history.as_ref().filter(|h| !h.is_empty()) else {
+            return;
+        };
+        let mut history: Vec<_> = history.iter().collect();
+        history.sort_by_key(|advancement| advancement.level);
+
+        out.push_str("## Advancement\n\n");
+        let rows: Vec<Vec<String>> = history
+            .iter()
+            .map(|advancement| {
+                vec![
+                    advancement.level.to_string(),
+                    advancement.class.clone().unwrap_or_default(),
+                    Self::improvement(&advancement.improvement),
+                ]
+            })
+            .collect();
+        Self::table(out, &["Level", "Class", "Improvement"], &rows);
+    }
+
+    /// "Ability Score Improvement: Strength +2" or "Feat: Resilient (Constitution +1)"
+    pub fn improvement(improvement: &Improvement) -> String {
+        let increases = |increases: &BTreeMap<String, u8>| {
+            increases
+                .iter()
+                .map(|(ability, amount)| format!("{} +{}", Self::title(ability), amount))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match improvement {
+            Improvement::AbilityScoreImprovement { increases: chosen } => {
+                format!("Ability Score Improvement: {}", increases(chosen))
+            }
+            Improvement::Feat { name, ability_increases } if ability_increases.is_empty() => {
+                format!("Feat: {}", name)
+            }
+            Improvement::Feat { name, ability_increases } => {
+                format!("Feat: {} ({})", name, increases(ability_increases))
+            }
+        }
+    }
+
+    fn equipment(out: &mut String, character_data: &CharacterData) {
+        let Some(equipment) = &character_data.equipment else {
+            return;
+        };
+        out.push_str("## Equipment\n\n");
+        if let Some(currency) = &equipment.currency {
+            let row = [currency.cp, currency.sp, currency.ep, currency.gp, currency.pp].map(|coins| coins.to_string());
+            Self::table(out, &["CP", "SP", "EP", "GP", "PP"], &[row.to_vec()]);
+        }
+        if let Some(items) = equipment.items.as_deref().filter(|items| !items.trim().is_empty()) {
+            out.push_str("### Items\n\n");
+            Self::bullets(out, &Equipment::split_items(items));
+        }
+        if let Some(treasure) = equipment.treasure.as_deref().filter(|t| !t.trim().is_empty()) {
+            let _ = writeln!(out, "### Treasure\n\n{}\n", treasure.trim());
+        }
+    }
+
+    fn appearance(out: &mut String, character_data: &CharacterData) {
+        let Some(appearance) = &character_data.appearance else {
+            return;
+        };
+        let rows = [
+            ("Age", appearance.age.clone()),
+            ("Height", appearance.height.clone()),
+            ("Weight", appearance.weight.clone()),
+            ("Eyes", appearance.eyes.clone()),
+            ("Skin", appearance.skin.clone()),
+            ("Hair", appearance.hair.clone()),
+        ];
+        if rows.iter().all(|(_, value)| value.is_none()) {
+            return;
+        }
+        out.push_str("## Appearance\n\n");
+        Self::key_values(out, &rows);
+    }
+
+    fn narrative(out: &mut String, character_data: &CharacterData) {
+        let Some(narrative) = &character_data.narrative else {
+            return;
+        };
+        let personality = [
+            ("Personality Traits", &narrative.personality_traits),
+            ("Ideals", &narrative.ideals),
+            ("Bonds", &narrative.bonds),
+            ("Flaws", &narrative.flaws),
+        ];
+        if personality.iter().any(|(_, text)| text.is_some()) {
+            out.push_str("## Personality\n\n");
+            for (heading, text) in personality {
+                if let Some(text) = text {
+                    let _ = writeln!(out, "### {}\n\n{}\n", heading, text.trim());
+                }
+            }
+        }
+        if let Some(backstory) = &narrative.backstory {
+            let _ = writeln!(out, "## Backstory\n\n{}\n", backstory.trim());
+        }
+        if narrative.allies.is_some() || narrative.faction_name.is_some() {
+            out.push_str("## Allies & Organizations\n\n");
+            if let Some(faction) = &narrative.faction_name {
+                let _ = writeln!(out, "**Faction:** {}\n", faction);
+            }
+            if let Some(allies) = &narrative.allies {
+                let _ = writeln!(out, "{}\n", allies.trim());
+            }
+        }
+    }
+
+    /// Two-column `Field | Value` table of the values that are set
+    fn key_values(out: &mut String, rows: &[(&str, Option<String>)]) {
+        let rows: Vec<Vec<String>> = rows
+            .iter()
+            .filter_map(|(key, value)| value.as_ref().map(|value| vec![key.to_string(), value.clone()]))
+            .collect();
+        Self::table(out, &["Field", "Value"], &rows);
+    }
+
+    fn table(out: &mut String, headers: &[&str], rows: &[Vec<String>]) {
+        let _ = writeln!(out, "| {} |", headers.join(" | "));
+        let _ = writeln!(out, "|{}", "---|".repeat(headers.len()));
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(|cell| Self::cell(cell)).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+        out.push('\n');
+    }
+
+    fn bullets(out: &mut String, items: &[String]) {
+        for item in items {
+            let _ = writeln!(out, "- {}", item.replace('\n', " "));
+        }
+        out.push('\n');
+    }
+
+    /// Escape pipes and keep multi-line values inside their cell
+    fn cell(value: &str) -> String {
+        value.trim().replace('|', "\\|").replace('\n', "<br>")
+    }
+
+    fn bonus(abilities: &AbilityScores, ability: &str, proficient: bool, proficiency_bonus: u8) -> i16 {
+        abilities.modifier(ability) as i16 + if proficient { proficiency_bonus as i16 } else { 0 }
+    }
+
+    fn dot(proficient: bool) -> &'static str {
+        if proficient {
+            PROFICIENT
+        } else {
+            NOT_PROFICIENT
+        }
+    }
+
+    fn signed(value: i16) -> String {
+        format!("{:+}", value)
+    }
+
+    /// "sleight_of_hand" -> "Sleight of Hand"
+    pub fn title(name: &str) -> String {
+        name.split('_')
+            .enumerate()
+            .map(|(index, word)| match word {
+                "of" if index > 0 => word.to_string(),
+                _ => {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect())
+                        .unwrap_or_default()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,752 @@
+use crate::character_model::{
+    AbilityScores, AdvancementMode, Character, CharacterData, CharacterNarrative, Combat, Currency, Equipment,
+    FeaturesTraits, Improvement, LevelAdvancement, OtherProficiencies, Proficiencies, Spell,
+    Spells, Appearance, SKILL_NAMES, CURRENT_SCHEMA_VERSION,
+};
+use crate::derived_stats::DerivedStats;
+use crate::errors::ImportError;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// A character read from Markdown, plus anything that was skipped
+#[derive(Debug, Clone)]
+pub struct MarkdownImport {
+    pub character_data: CharacterData,
+    /// Sections and table rows the format doesn't define, e.g. "line 88: section 'Session Notes'"
+    pub ignored: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+}
+
+/// A `##` or `###` heading and the lines under it
+struct Section<'a> {
+    title: String,
+    line: usize,
+    lines: Vec<Line<'a>>,
+}
+
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<(usize, Vec<String>)>,
+}
+
+impl Table {
+    fn column(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|header| header.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Reads the Markdown layout written by `MarkdownExporter` (documented in
+/// docs/markdown-character-format.md) back into `CharacterData`. Every
+/// problem is reported with its line number rather than stopping at the first.
+pub struct MarkdownImporter {
+    errors: Vec<ImportError>,
+    ignored: Vec<String>,
+}
+
+impl MarkdownImporter {
+    pub fn parse(markdown: &str) -> Result<MarkdownImport, Vec<ImportError>> {
+        let mut importer = Self {
+            errors: Vec::new(),
+            ignored: Vec::new(),
+        };
+        let character_data = importer.character(markdown);
+        match (character_data, importer.errors.is_empty()) {
+            (Some(character_data), true) => Ok(MarkdownImport {
+                character_data,
+                ignored: importer.ignored,
+            }),
+            _ => Err(importer.errors),
+        }
+    }
+
+    fn character(&mut self, markdown: &str) -> Option<CharacterData> {
+        let lines = Self::lines(markdown);
+        let Some(title_index) = lines.iter().position(|line| line.text.starts_with("# ")) else {
+            let line = lines.first().map(|line| line.number).unwrap_or(1);
+            self.error(line, "expected a '# Character Name' heading");
+            return None;
+        };
+        let title = lines[title_index];
+        let name = title.text[2..].trim().to_string();
+        let (intro, sections) = Self::split(&lines[title_index + 1..], "## ");
+
+        let section = |title: &'static str| sections.iter().filter(move |s| s.title.eq_ignore_ascii_case(title));
+        for section in &sections {
+            if !Self::SECTIONS.iter().any(|known| section.title.eq_ignore_ascii_case(known)) {
+                self.ignored.push(format!("line {}: section '{}'", section.line, section.title));
+            }
+        }
+
+        let character = self.identity(name, title.number, &intro);
+        let level_history = section("Advancement").flat_map(|s| self.advancement(s)).collect::<Vec<_>>();
+        let abilities = self.abilities(section("Ability Scores").next(), title.number, &level_history);
+
+        let saving_throws: Option<Vec<String>> = section("Saving Throws")
+            .next()
+            .map(|s| self.proficient_rows(s, AbilityScores::NAMES.as_slice(), "ability"));
+        let skills: Option<Vec<String>> = section("Skills").next().map(|s| self.proficient_rows(s, SKILL_NAMES.as_slice(), "skill"));
+        let other = section("Proficiencies").next().map(|s| self.other_proficiencies(s));
+        let proficiencies = match (&saving_throws, &skills, &other) {
+            (None, None, None) => None,
+            _ => Some(Proficiencies {
+                saving_throws: saving_throws.unwrap_or_default(),
+                skills: skills.unwrap_or_default(),
+                other: other.unwrap_or_default(),
+                sources: Vec::new(),
+            }),
+        };
+
+        let mut character_data = CharacterData {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            character: character?,
+            abilities: abilities?,
+            proficiencies,
+            combat: section("Combat").next().map(|s| self.combat(s)),
+            spells: section("Spellcasting").next().map(|s| self.spells(s)),
+            equipment: section("Equipment").next().map(|s| self.equipment(s)),
+            narrative: None,
+            features_traits: section("Features & Traits").next().map(|s| self.features(s)),
+            appearance: section("Appearance").next().map(|s| self.appearance(s)),
+            images: None,
+            level_history: (!level_history.is_empty()).then_some(level_history),
+            feat_definitions: None,
+        };
+        character_data.narrative = self.narrative(&sections);
+
+        // Initiative is written even when it's only derived; keep it only if it was overridden
+        let derived_initiative = DerivedStats::calculate(&character_data).initiative;
+        if let Some(combat) = &mut character_data.combat {
+            if combat.initiative == Some(derived_initiative) {
+                combat.initiative = None;
+            }
+        }
+        Some(character_data)
+    }
+
+    const SECTIONS: [&'static str; 14] = [
+        "Ability Scores",
+        "Saving Throws",
+        "Skills",
+        "Combat",
+        "Proficiencies",
+        "Spellcasting",
+        "Features & Traits",
+        "Advancement",
+        "Equipment",
+        "Appearance",
+        "Personality",
+        "Backstory",
+        "Allies & Organizations",
+        "Notes",
+    ];
+
+    fn identity(&mut self, name: String, title_line: usize, intro: &[Line]) -> Option<Character> {
+        let mut character = Character {
+            name,
+            class: String::new(),
+            level: 0,
+            subclass: None,
+            race: String::new(),
+            background: None,
+            player_name: None,
+            alignment: None,
+            experience_points: None,
+            advancement_mode: None,
+        };
+        let mut has_level = false;
+        for (line, key, value) in self.key_values(intro) {
+            match key.to_lowercase().as_str() {
+                "class" => character.class = value,
+                "subclass" => character.subclass = Some(value),
+                "level" => {
+                    has_level = true;
+                    character.level = self.number(line, "Level", &value).unwrap_or(0);
+                }
+                "race" => character.race = value,
+                "background" => character.background = Some(value),
+                "alignment" => character.alignment = Some(value),
+                "player" | "player name" => character.player_name = Some(value),
+                "experience points" | "xp" => {
+                    character.experience_points = self.number(line, "Experience Points", &value)
+                }
+                "leveling" => match value.to_lowercase().as_str() {
+                    "milestone" => character.advancement_mode = Some(AdvancementMode::Milestone),
+                    "xp" | "experience points" => character.advancement_mode = Some(AdvancementMode::Xp),
+                    _ => self.error(line, &format!("expected Leveling 'Milestone' or 'XP', found '{}'", value)),
+                },
+                _ => self.ignore_row(line, &key),
+            }
+        }
+
+        for (field, missing) in [
+            ("Class", character.class.is_empty()),
+            ("Level", !has_level),
+            ("Race", character.race.is_empty()),
+        ] {
+            if missing {
+                self.error(title_line, &format!("missing '| {} | ... |' row in the table under the title", field));
+            }
+        }
+        Some(character)
+    }
+
+    fn abilities(
+        &mut self,
+        section: Option<&Section>,
+        title_line: usize,
+        level_history: &[LevelAdvancement],
+    ) -> Option<AbilityScores> {
+        let Some(section) = section else {
+            self.error(title_line, "missing '## Ability Scores' section");
+            return None;
+        };
+        let table = self.tables(&section.lines).into_iter().next();
+        let Some(table) = table else {
+            self.error(section.line, "expected an | Ability | Score | table");
+            return None;
+        };
+        let score_column = table.column("Score").unwrap_or(1);
+        // Without a Base column the scores are final, so take level-history increases back off
+        let base_column = table.column("Base");
+
+        let mut scores = AbilityScores {
+            strength: 0,
+            dexterity: 0,
+            constitution: 0,
+            intelligence: 0,
+            wisdom: 0,
+            charisma: 0,
+        };
+        for (line, row) in &table.rows {
+            let Some(ability) = self.ability(*line, row.first().map(String::as_str).unwrap_or_default()) else {
+                continue;
+            };
+            let cell = row.get(base_column.unwrap_or(score_column)).map(String::as_str).unwrap_or_default();
+            if let (Some(score), Some(slot)) = (self.number::<u8>(*line, "score", cell), scores.get_ability_score_mut(&ability)) {
+                *slot = score;
+            }
+        }
+        if base_column.is_none() {
+            for advancement in level_history {
+                for (ability, amount) in DerivedStats::ability_increases(&advancement.improvement, None) {
+                    if let Some(score) = scores.get_ability_score_mut(&ability) {
+                        *score = score.saturating_sub(amount);
+                    }
+                }
+            }
+        }
+
+        for ability in AbilityScores::NAMES {
+            if scores.get_ability_score(ability) == 0 {
+                self.error(section.line, &format!("missing a score for {}", capitalize(ability)));
+            }
+        }
+        Some(scores)
+    }
+
+    /// Names from the first column of rows whose Proficient cell is marked
+    fn proficient_rows(&mut self, section: &Section, names: &[&str], kind: &str) -> Vec<String> {
+        let mut proficient = Vec::new();
+        let Some(table) = self.tables(&section.lines).into_iter().next() else {
+            self.error(section.line, "expected a table with a Proficient column");
+            return proficient;
+        };
+        let Some(column) = table.column("Proficient") else {
+            self.error(section.line, "expected a Proficient column");
+            return proficient;
+        };
+        for (line, row) in &table.rows {
+            let label = row.first().map(String::as_str).unwrap_or_default();
+            let name = match kind {
+                "ability" => self.ability(*line, label),
+                _ => {
+                    let skill = label.trim().to_lowercase().replace([' ', '-'], "_");
+                    match names.contains(&skill.as_str()) {
+                        true => Some(skill),
+                        false => {
+                            self.error(*line, &format!("unknown skill '{}'", label));
+                            None
+                        }
+                    }
+                }
+            };
+            let marked = self.flag(*line, row.get(column).map(String::as_str).unwrap_or_default());
+            if let (Some(name), Some(true)) = (name, marked) {
+                proficient.push(name);
+            }
+        }
+        proficient
+    }
+
+    fn other_proficiencies(&mut self, section: &Section) -> OtherProficiencies {
+        let mut other = OtherProficiencies::default();
+        for (line, item) in Self::bullets(&section.lines) {
+            let item = item.replace("**", "");
+            let Some((label, list)) = item.split_once(':') else {
+                self.error(line, "expected '- **Armor:** item, item'");
+                continue;
+            };
+            let items: Vec<String> = list.split(',').map(|i| i.trim().to_string()).filter(|i| !i.is_empty()).collect();
+            match label.trim().to_lowercase().as_str() {
+                "armor" => other.armor = items,
+                "weapons" => other.weapons = items,
+                "languages" => other.languages = items,
+                "tools" => {
+                    for tool in items {
+                        match tool.strip_suffix("(expertise)") {
+                            Some(name) => {
+                                other.tools.push(name.trim().to_string());
+                                other.tool_expertise.push(name.trim().to_string());
+                            }
+                            None => other.tools.push(tool),
+                        }
+                    }
+                }
+                _ => self.ignore_row(line, label.trim()),
+            }
+        }
+        other
+    }
+
+    fn combat(&mut self, section: &Section) -> Combat {
+        let mut combat = Combat::default();
+        for (line, key, value) in self.key_values(&section.lines) {
+            match key.to_lowercase().as_str() {
+                "armor class" => combat.armor_class = self.number(line, &key, &value),
+                "initiative" => combat.initiative = self.number(line, &key, &value),
+                "speed" => combat.speed = self.number(line, &key, &value),
+                "hit point maximum" => combat.hit_point_maximum = self.number(line, &key, &value),
+                "current hit points" => combat.current_hit_points = self.number(line, &key, &value),
+                "temporary hit points" => combat.temporary_hit_points = self.number(line, &key, &value),
+                "hit dice" => combat.hit_dice = Some(value),
+                "inspiration" => combat.inspiration = self.flag(line, &value),
+                "death save successes" => combat.death_save_successes = self.number(line, &key, &value),
+                "death save failures" => combat.death_save_failures = self.number(line, &key, &value),
+                "exhaustion" => combat.exhaustion_level = self.number(line, &key, &value),
+                "conditions" => {
+                    combat.conditions = Some(value.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect())
+                }
+                // Always derived from the rest of the sheet
+                "proficiency bonus" | "passive perception" => {}
+                _ => self.ignore_row(line, &key),
+            }
+        }
+        combat
+    }
+
+    fn spells(&mut self, section: &Section) -> Spells {
+        let mut spells = Spells::default();
+        let (body, levels) = Self::split(&section.lines, "### ");
+        for (line, key, value) in self.key_values(&body) {
+            match key.to_lowercase().as_str() {
+                "spellcasting class" => spells.spellcasting_class = Some(value),
+                "spellcasting ability" => spells.spellcasting_ability = Some(value),
+                "spell save dc" | "spell attack bonus" => {}
+                _ => self.ignore_row(line, &key),
+            }
+        }
+
+        for heading in levels {
+            let level = match heading.title.to_lowercase().as_str() {
+                "cantrips" => Some(0),
+                title => title
+                    .strip_prefix("level ")
+                    .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                    .and_then(|digits| digits.parse::<u8>().ok())
+                    .filter(|level| (1..=9).contains(level)),
+            };
+            let Some(level) = level else {
+                self.error(heading.line, &format!("expected '### Cantrips' or '### Level 1' to '### Level 9', found '{}'", heading.title));
+                continue;
+            };
+            for (_, item) in Self::bullets(&heading.lines) {
+                let (prepared, name) = if let Some(name) = item.strip_prefix("[x] ").or_else(|| item.strip_prefix("[X] ")) {
+                    (true, name)
+                } else if let Some(name) = item.strip_prefix("[ ] ") {
+                    (false, name)
+                } else {
+                    // Cantrips are always ready; other unmarked spells are known but not prepared
+                    (level == 0, item.as_str())
+                };
+                if let Some(list) = spells.level_mut(level) {
+                    list.push(Spell {
+                        name: name.trim().to_string(),
+                        level,
+                        prepared,
+                    });
+                }
+            }
+        }
+        spells
+    }
+
+    fn features(&mut self, section: &Section) -> FeaturesTraits {
+        let mut features = FeaturesTraits {
+            features: None,
+            traits: None,
+            additional: None,
+        };
+        let (_, lists) = Self::split(&section.lines, "### ");
+        for list in lists {
+            let items: Vec<String> = Self::bullets(&list.lines).into_iter().map(|(_, item)| item).collect();
+            match list.title.to_lowercase().as_str() {
+                "features" => features.features = Some(items),
+                "traits" => features.traits = Some(items),
+                "additional features & traits" | "additional features" => features.additional = Some(items),
+                // Derived from the Advancement table
+                "feats" => {}
+                _ => self.ignored.push(format!("line {}: section '{}'", list.line, list.title)),
+            }
+        }
+        features
+    }
+
+    fn advancement(&mut self, section: &Section) -> Vec<LevelAdvancement> {
+        let mut history = Vec::new();
+        let Some(table) = self.tables(&section.lines).into_iter().next() else {
+            self.error(section.line, "expected a | Level | Class | Improvement | table");
+            return history;
+        };
+        let level_column = table.column("Level").unwrap_or(0);
+        let class_column = table.column("Class");
+        let improvement_column = table.column("Improvement").unwrap_or(table.headers.len().saturating_sub(1));
+
+        for (line, row) in &table.rows {
+            let cell = |column: usize| row.get(column).map(String::as_str).unwrap_or_default();
+            let level = self.number(*line, "Level", cell(level_column));
+            let improvement = self.improvement(*line, cell(improvement_column));
+            if let (Some(level), Some(improvement)) = (level, improvement) {
+                history.push(LevelAdvancement {
+                    level,
+                    class: class_column.map(cell).filter(|class| !class.is_empty()).map(String::from),
+                    improvement,
+                });
+            }
+        }
+        history
+    }
+
+    /// "Ability Score Improvement: Strength +2" or "Feat: Resilient (Constitution +1)"
+    fn improvement(&mut self, line: usize, text: &str) -> Option<Improvement> {
+        let (kind, detail) = text.split_once(':').map(|(k, d)| (k.trim(), d.trim())).unwrap_or((text.trim(), ""));
+        if kind.eq_ignore_ascii_case("Ability Score Improvement") {
+            let increases = self.increases(line, detail)?;
+            return Some(Improvement::AbilityScoreImprovement { increases });
+        }
+        if kind.eq_ignore_ascii_case("Feat") && !detail.is_empty() {
+            let (name, ability_increases) = match detail.split_once('(') {
+                Some((name, rest)) => (name.trim(), self.increases(line, rest.trim_end_matches(')'))?),
+                None => (detail, BTreeMap::new()),
+            };
+            return Some(Improvement::Feat {
+                name: name.to_string(),
+                ability_increases,
+            });
+        }
+        self.error(line, &format!("expected 'Ability Score Improvement: Strength +2' or 'Feat: Name', found '{}'", text));
+        None
+    }
+
+    /// "Strength +2, Dexterity +1"
+    fn increases(&mut self, line: usize, text: &str) -> Option<BTreeMap<String, u8>> {
+        let mut increases = BTreeMap::new();
+        for part in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((ability, amount)) = part.rsplit_once(' ') else {
+                self.error(line, &format!("expected 'Ability +N', found '{}'", part));
+                return None;
+            };
+            let ability = self.ability(line, ability)?;
+            increases.insert(ability, self.number(line, "increase", amount)?);
+        }
+        Some(increases)
+    }
+
+    fn equipment(&mut self, section: &Section) -> Equipment {
+        let mut equipment = Equipment {
+            currency: None,
+            items: None,
+            treasure: None,
+        };
+        let (body, subsections) = Self::split(&section.lines, "### ");
+        if let Some(table) = self.tables(&body).into_iter().next() {
+            if let Some((line, row)) = table.rows.first() {
+                let mut coins = [0u32; 5];
+                for (slot, name) in coins.iter_mut().zip(["CP", "SP", "EP", "GP", "PP"]) {
+                    if let Some(cell) = table.column(name).and_then(|column| row.get(column)) {
+                        *slot = self.number(*line, name, cell).unwrap_or(0);
+                    }
+                }
+                let [cp, sp, ep, gp, pp] = coins;
+                equipment.currency = Some(Currency { cp, sp, ep, gp, pp });
+            }
+        }
+        for subsection in subsections {
+            match subsection.title.to_lowercase().as_str() {
+                "items" => {
+                    let items: Vec<String> = Self::bullets(&subsection.lines).into_iter().map(|(_, item)| item).collect();
+                    equipment.items = Some(items.join(", "));
+                }
+                "treasure" => equipment.treasure = Self::text(&subsection.lines),
+                _ => self.ignored.push(format!("line {}: section '{}'", subsection.line, subsection.title)),
+            }
+        }
+        equipment
+    }
+
+    fn appearance(&mut self, section: &Section) -> Appearance {
+        let mut appearance = Appearance::default();
+        for (line, key, value) in self.key_values(&section.lines) {
+            let slot = match key.to_lowercase().as_str() {
+                "age" => &mut appearance.age,
+                "height" => &mut appearance.height,
+                "weight" => &mut appearance.weight,
+                "eyes" => &mut appearance.eyes,
+                "skin" => &mut appearance.skin,
+                "hair" => &mut appearance.hair,
+                _ => {
+                    self.ignore_row(line, &key);
+                    continue;
+                }
+            };
+            *slot = Some(value);
+        }
+        appearance
+    }
+
+    fn narrative(&mut self, sections: &[Section]) -> Option<CharacterNarrative> {
+        let mut narrative = CharacterNarrative {
+            personality_traits: None,
+            ideals: None,
+            bonds: None,
+            flaws: None,
+            backstory: None,
+            allies: None,
+            faction_name: None,
+        };
+        let mut found = false;
+        for section in sections {
+            match section.title.to_lowercase().as_str() {
+                "personality" => {
+                    found = true;
+                    let (_, parts) = Self::split(&section.lines, "### ");
+                    for part in parts {
+                        let slot = match part.title.to_lowercase().as_str() {
+                            "personality traits" => &mut narrative.personality_traits,
+                            "ideals" => &mut narrative.ideals,
+                            "bonds" => &mut narrative.bonds,
+                            "flaws" => &mut narrative.flaws,
+                            _ => {
+                                self.ignored.push(format!("line {}: section '{}'", part.line, part.title));
+                                continue;
+                            }
+                        };
+                        *slot = Self::text(&part.lines);
+                    }
+                }
+                "backstory" => {
+                    found = true;
+                    narrative.backstory = Self::text(&section.lines);
+                }
+                "allies & organizations" => {
+                    found = true;
+                    let mut rest = Vec::new();
+                    for line in &section.lines {
+                        match line.text.trim().strip_prefix("**Faction:**") {
+                            Some(faction) => narrative.faction_name = Some(faction.trim().to_string()),
+                            None => rest.push(*line),
+                        }
+                    }
+                    narrative.allies = Self::text(&rest);
+                }
+                _ => {}
+            }
+        }
+        found.then_some(narrative)
+    }
+
+    /// Lines with 1-based numbers, skipping YAML front matter
+    fn lines(markdown: &str) -> Vec<Line<'_>> {
+        let mut lines: Vec<Line> = markdown
+            .lines()
+            .enumerate()
+            .map(|(index, text)| Line {
+                number: index + 1,
+                text: text.trim_end(),
+            })
+            .collect();
+        if lines.first().is_some_and(|line| line.text == "---") {
+            if let Some(end) = lines.iter().skip(1).position(|line| line.text == "---") {
+                lines.drain(..end + 2);
+            }
+        }
+        lines
+    }
+
+    /// Lines before the first heading with `prefix`, and each such heading's section
+    fn split<'a>(lines: &[Line<'a>], prefix: &str) -> (Vec<Line<'a>>, Vec<Section<'a>>) {
+        let mut body = Vec::new();
+        let mut sections: Vec<Section> = Vec::new();
+        for line in lines {
+            if let Some(title) = line.text.strip_prefix(prefix) {
+                sections.push(Section {
+                    title: title.trim().trim_end_matches('#').trim().to_string(),
+                    line: line.number,
+                    lines: Vec::new(),
+                });
+            } else if let Some(section) = sections.last_mut() {
+                section.lines.push(*line);
+            } else {
+                body.push(*line);
+            }
+        }
+        (body, sections)
+    }
+
+    /// Every pipe table in `lines`; a table needs a header and a `|---|` row
+    fn tables(&mut self, lines: &[Line]) -> Vec<Table> {
+        let mut tables = Vec::new();
+        let mut index = 0;
+        while index < lines.len() {
+            if !lines[index].text.trim_start().starts_with('|') {
+                index += 1;
+                continue;
+            }
+            let header = lines[index];
+            let separator = lines.get(index + 1).filter(|line| {
+                let text = line.text.trim();
+                text.starts_with('|') && text.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+            });
+            if separator.is_none() {
+                self.error(header.number, "table header must be followed by a |---|---| row");
+                index += 1;
+                continue;
+            }
+            let mut table = Table {
+                headers: Self::cells(header.text),
+                rows: Vec::new(),
+            };
+            index += 2;
+            while index < lines.len() && lines[index].text.trim_start().starts_with('|') {
+                table.rows.push((lines[index].number, Self::cells(lines[index].text)));
+                index += 1;
+            }
+            tables.push(table);
+        }
+        tables
+    }
+
+    /// `(line, key, value)` for each row of `| Field | Value |` tables
+    fn key_values(&mut self, lines: &[Line]) -> Vec<(usize, String, String)> {
+        let mut values = Vec::new();
+        for table in self.tables(lines) {
+            for (line, row) in table.rows {
+                match row.as_slice() {
+                    [key, value, ..] if value.is_empty() => self.ignore_row(line, key),
+                    [key, value, ..] => values.push((line, key.clone(), value.clone())),
+                    _ => self.error(line, "expected '| Field | Value |'"),
+                }
+            }
+        }
+        values
+    }
+
+    /// Split a table row on unescaped pipes
+    fn cells(row: &str) -> Vec<String> {
+        let row = row.trim();
+        let row = row.strip_prefix('|').unwrap_or(row);
+        let row = row.strip_suffix('|').filter(|r| !r.ends_with('\\')).unwrap_or(row);
+        let mut cells = Vec::new();
+        let mut cell = String::new();
+        let mut chars = row.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if chars.peek() == Some(&'|') => {
+                    cell.push('|');
+                    chars.next();
+                }
+                '|' => cells.push(std::mem::take(&mut cell)),
+                _ => cell.push(c),
+            }
+        }
+        cells.push(cell);
+        cells.into_iter().map(|cell| cell.trim().replace("<br>", "\n")).collect()
+    }
+
+    fn bullets(lines: &[Line]) -> Vec<(usize, String)> {
+        lines
+            .iter()
+            .filter_map(|line| {
+                let text = line.text.trim_start();
+                text.strip_prefix("- ")
+                    .or_else(|| text.strip_prefix("* "))
+                    .map(|item| (line.number, item.trim().to_string()))
+            })
+            .collect()
+    }
+
+    /// Paragraph text with surrounding blank lines removed
+    fn text(lines: &[Line]) -> Option<String> {
+        let text = lines.iter().map(|line| line.text).collect::<Vec<_>>().join("\n");
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    /// Lowercase ability name from "Strength", "STR" or "str"
+    fn ability(&mut self, line: usize, name: &str) -> Option<String> {
+        let name = name.trim().to_lowercase();
+        let found = AbilityScores::NAMES
+            .iter()
+            .find(|ability| **ability == name || (name.len() == 3 && ability.starts_with(&name)));
+        if found.is_none() {
+            self.error(line, &format!("unknown ability '{}'", name));
+        }
+        found.map(|ability| ability.to_string())
+    }
+
+    /// Numbers like "14,000", "+2" or "30 ft"
+    fn number<T: FromStr>(&mut self, line: usize, field: &str, value: &str) -> Option<T> {
+        let cleaned = value.trim().replace(',', "");
+        let token = cleaned.split_whitespace().next().unwrap_or_default();
+        let parsed = token.parse::<T>().ok().or_else(|| token.trim_start_matches('+').parse().ok());
+        if parsed.is_none() {
+            self.error(line, &format!("expected a number for {}, found '{}'", field, value.trim()));
+        }
+        parsed
+    }
+
+    /// Proficiency marks and yes/no values
+    fn flag(&mut self, line: usize, value: &str) -> Option<bool> {
+        match value.trim().to_lowercase().as_str() {
+            "●" | "x" | "✓" | "✔" | "*" | "yes" | "true" => Some(true),
+            "○" | "" | "-" | "no" | "false" => Some(false),
+            other => {
+                self.error(line, &format!("expected ● or ○ (or yes/no), found '{}'", other));
+                None
+            }
+        }
+    }
+
+    fn ignore_row(&mut self, line: usize, key: &str) {
+        self.ignored.push(format!("line {}: row '{}'", line, key));
+    }
+
+    fn error(&mut self, line: usize, message: &str) {
+        self.errors.push(ImportError::AtLine {
+            line,
+            message: message.to_string(),
+        });
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -1,49 +1,302 @@
-use crate::character_model::CharacterData;
+use crate::character_diff::CharacterDiff;
+use crate::character_model::{AdvancementMode, CharacterData};
+use crate::character_store::{CharacterStore, StoredCharacter};
+use crate::config::ServerConfig;
+use crate::dnd_validator::DndValidator;
+use crate::dndbeyond_importer::DndBeyondImporter;
+use crate::errors::{ConfigError, PdfError, ResourceError, StoreError, ValidationError};
+use crate::level_up::{LevelUpChoices, LevelUpEngine, LevelUpOutcome};
+use crate::logging::{LogLevel, Logger};
+use crate::fight_club_exporter::FightClubExporter;
+use crate::foundry_exporter::FoundryExporter;
+use crate::html_exporter::HtmlExporter;
+use crate::markdown_exporter::MarkdownExporter;
+use crate::markdown_importer::MarkdownImporter;
+use crate::party::Party;
+use crate::party_sheets::PartySheets;
 use crate::pdf_filler::PdfFiller;
+use crate::prompt_library::PromptLibrary;
+use crate::resource_provider::ResourceProvider;
+use crate::schema::SchemaGenerator;
+use crate::session_log::{Loot, Session, SessionAward, SessionLog};
+use crate::sheet_writer::SheetWriter;
 use base64::{engine::general_purpose, Engine as _};
+use schemars::JsonSchema;
+use serde::Serialize;
 use serde_json::{json, Value};
-use std::fs::{self, OpenOptions};
+use std::fs;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-// Embed the PDF template at compile time
-const PDF_TEMPLATE: &[u8] = include_bytes!("../docs/5E_CharacterSheet_Fillable.pdf");
-
-fn log_to_file(message: &str) {
-    if let Ok(mut file) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("/Users/lindegar/learningplace/specify--test/mcp_debug.log")
-    {
-        let _ = writeln!(
-            file,
-            "[{}] {}",
-            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f"),
-            message
-        );
+/// Structured result of `fill_dnd_character_sheet`
+#[derive(Serialize, JsonSchema)]
+struct FillSheetOutput {
+    success: bool,
+    /// Path of the filled PDF; absent when validation failed
+    output_file: Option<String>,
+    message: String,
+    /// Computed modifiers, bonuses and spell values, e.g. "strength_modifier"
+    calculated_fields: BTreeMap<String, String>,
+    validation_errors: Vec<ValidationIssue>,
+    rule_violations_overridden: bool,
+    file_size: Option<u64>,
+    pdf_content_error: Option<String>,
+}
+
+/// Structured result of `level_up_character`
+#[derive(Serialize, JsonSchema)]
+struct LevelUpOutput {
+    /// False while `pending_choices` still need the player's input
+    complete: bool,
+    #[serde(flatten)]
+    outcome: LevelUpOutcome,
+    validation_errors: Vec<ValidationIssue>,
+}
+
+/// Structured result of `export_character_markdown`
+#[derive(Serialize, JsonSchema)]
+struct MarkdownExportOutput {
+    markdown: String,
+    /// Where the Markdown was saved; absent when no output_path was given
+    output_file: Option<String>,
+}
+
+/// Structured result of `export_character_html`
+#[derive(Serialize, JsonSchema)]
+struct HtmlExportOutput {
+    /// Complete HTML document with its stylesheet inline
+    html: String,
+    /// Where the HTML was saved; absent when no output_path was given
+    output_file: Option<String>,
+}
+
+/// Structured result of `export_character_foundry`
+#[derive(Serialize, JsonSchema)]
+struct FoundryExportOutput {
+    /// dnd5e actor JSON for the actor sheet's "Import Data"
+    actor: Value,
+    /// Where the actor JSON was saved; absent when no output_path was given
+    output_file: Option<String>,
+}
+
+/// Structured result of `export_character_fight_club`
+#[derive(Serialize, JsonSchema)]
+struct FightClubExportOutput {
+    /// `<pc version="5">` character XML
+    xml: String,
+    /// Where the XML was saved; absent when no output_path was given
+    output_file: Option<String>,
+}
+
+/// Structured result of the `import_character_*` tools
+#[derive(Serialize, JsonSchema)]
+struct CharacterImportOutput {
+    /// Absent when the file has errors
+    character_data: Option<CharacterData>,
+    /// Sections, rows and data (e.g. D&D Beyond modifiers) that couldn't be mapped and were skipped
+    ignored: Vec<String>,
+    /// Problems that stopped the import, e.g. "line 12: expected a number for Level, found 'five'"
+    errors: Vec<String>,
+}
+
+/// Structured result of `save_character`
+#[derive(Serialize, JsonSchema)]
+struct SaveCharacterOutput {
+    id: String,
+    /// Snapshot number of this save; the first save is version 1
+    version: u32,
+    /// What changed since the previous version; empty on the first save
+    changes: Vec<String>,
+}
+
+/// Structured result of `load_character`
+#[derive(Serialize, JsonSchema)]
+struct LoadCharacterOutput {
+    id: String,
+    version: u32,
+    /// The character as saved in that version
+    character_data: CharacterData,
+}
+
+/// Structured result of `list_characters`
+#[derive(Serialize, JsonSchema)]
+struct CharacterListOutput {
+    /// Directory the characters are stored in
+    store_dir: String,
+    characters: Vec<StoredCharacter>,
+}
+
+/// Structured result of `delete_character`
+#[derive(Serialize, JsonSchema)]
+struct DeleteCharacterOutput {
+    id: String,
+    /// How many snapshots were removed along with the character
+    deleted_snapshots: usize,
+}
+
+/// Structured result of `character_diff`
+#[derive(Serialize, JsonSchema)]
+struct CharacterDiffOutput {
+    id: String,
+    from: u32,
+    to: u32,
+    /// One line per change, e.g. "Character / Level: 4 → 5" or
+    /// "Spells / Third Level: added Fireball"
+    changes: Vec<String>,
+}
+
+/// Structured result of the party roster tools
+#[derive(Serialize, JsonSchema)]
+struct PartyOutput {
+    /// Store id of the party, derived from its name
+    id: String,
+    party: Party,
+}
+
+/// Structured result of `find_party_character`
+#[derive(Serialize, JsonSchema)]
+struct PartyCharacterOutput {
+    /// Id of the party the player belongs to
+    party: String,
+    player: String,
+    character_id: String,
+    /// The player's character as currently saved
+    character_data: CharacterData,
+}
+
+/// Structured result of `fill_party_sheets`
+#[derive(Serialize, JsonSchema)]
+struct FillPartyOutput {
+    success: bool,
+    /// Path of the combined PDF or zip; absent when a sheet failed validation
+    output_file: Option<String>,
+    message: String,
+    /// One entry per member, in order of player name
+    members: Vec<PartySheetResult>,
+    file_size: Option<u64>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct PartySheetResult {
+    player: String,
+    character_id: String,
+    validation_errors: Vec<ValidationIssue>,
+}
+
+/// Structured result of `record_session` and `apply_session`
+#[derive(Serialize, JsonSchema)]
+struct SessionOutput {
+    session: Session,
+    /// Each attendee's new XP total and level-up readiness; empty until the session is applied
+    awards: Vec<SessionAward>,
+    /// The party after its share of the loot and XP, when the session has one
+    party: Option<Party>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ValidationIssue {
+    error: String,
+    severity: Severity,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+impl ValidationIssue {
+    fn from_errors(errors: &[ValidationError], allow_violations: bool) -> Vec<Self> {
+        errors
+            .iter()
+            .map(|e| ValidationIssue {
+                error: e.to_string(),
+                severity: if allow_violations { Severity::Warning } else { Severity::Error },
+            })
+            .collect()
     }
 }
 
+/// Newest first; structured tool output needs 2025-06-18
+const SUPPORTED_PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// Embed the PDF template at compile time
+pub(crate) const PDF_TEMPLATE: &[u8] = include_bytes!("../docs/5E_CharacterSheet_Fillable.pdf");
+
+const LOGGER: &str = "mcp_server";
+
+const DEFAULT_OUTPUT_FILE: &str = "filled_character_sheet.pdf";
+
+/// The one client of the stdio transport
+const STDIO_SESSION: &str = "stdio";
+
+/// Sessions whose log level is remembered; clients that never end their
+/// session are forgotten once idle for `SESSION_IDLE_TIMEOUT`, or when the
+/// least recently used has to make room
+const MAX_CLIENT_SESSIONS: usize = 1024;
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
 pub struct McpServer {
     #[allow(dead_code)]
     pdf_filler: PdfFiller,
+    resources: ResourceProvider,
+    /// Minimum level forwarded as `notifications/message` to each session,
+    /// set by `logging/setLevel`, and when the session was last used
+    client_log_levels: Mutex<HashMap<String, (LogLevel, Instant)>>,
+    config: ServerConfig,
+    template: Cow<'static, [u8]>,
+    store: CharacterStore,
+}
+
+impl Default for McpServer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl McpServer {
     pub fn new() -> Self {
+        Self::from_parts(ServerConfig::default(), Cow::Borrowed(PDF_TEMPLATE))
+    }
+
+    /// Server whose generated sheets are written to and listed from `output_dir`
+    pub fn with_output_dir(output_dir: impl Into<PathBuf>) -> Self {
+        let config = ServerConfig {
+            output_dir: Some(output_dir.into()),
+            ..ServerConfig::default()
+        };
+        Self::from_parts(config, Cow::Borrowed(PDF_TEMPLATE))
+    }
+
+    /// Server using the output directory, template and defaults from `config`
+    pub fn with_config(config: ServerConfig) -> Result<Self, ConfigError> {
+        let template = config.template_bytes()?;
+        Ok(Self::from_parts(config, template))
+    }
+
+    fn from_parts(config: ServerConfig, template: Cow<'static, [u8]>) -> Self {
         Self {
-            pdf_filler: PdfFiller::new(false),
+            pdf_filler: PdfFiller::new(config.allow_rule_violations),
+            resources: ResourceProvider::new(config.output_dir()).with_template(template.clone()),
+            client_log_levels: Mutex::new(HashMap::new()),
+            store: CharacterStore::new(config.store_dir()),
+            config,
+            template,
         }
     }
 
     pub fn run(&self) -> io::Result<()> {
-        log_to_file("MCP Server starting up");
+        Logger::info(LOGGER, "MCP server starting on stdio");
 
         let stdin = io::stdin();
         let mut stdout = io::stdout();
         let reader = BufReader::new(stdin);
 
-        log_to_file("Entering main loop");
-
         for line in reader.lines() {
             let line = line?;
             let trimmed = line.trim();
@@ -52,41 +305,61 @@ impl McpServer {
                 continue;
             }
 
-            log_to_file(&format!("Processing line: {}", trimmed));
-
-            if let Some(response) = self.handle_message(trimmed) {
-                let response_str = serde_json::to_string(&response).unwrap();
-                log_to_file(&format!("Sending response: {}", response_str));
-
-                stdout.write_all(response_str.as_bytes())?;
+            let (response, notifications) = self.handle_message_with_logs(trimmed);
+            for message in notifications.iter().chain(response.iter()) {
+                let message_str = serde_json::to_string(message).unwrap();
+                stdout.write_all(message_str.as_bytes())?;
                 stdout.write_all(b"\n")?;
-                stdout.flush()?;
-                
-                log_to_file("Response sent successfully");
+            }
+            stdout.flush()?;
+
+            if let Some(response) = response {
+                Logger::debug(LOGGER, &format!("Sent response: {}", response));
             }
         }
 
-        log_to_file("MCP Server shutting down cleanly");
+        Logger::info(LOGGER, "MCP server shutting down cleanly");
         Ok(())
     }
 
-    fn handle_message(&self, message: &str) -> Option<Value> {
-        log_to_file(&format!(
-            "=== RAW MESSAGE ===\n{}\n=== END RAW MESSAGE ===",
-            message
-        ));
+    /// Handle one message, also returning the `notifications/message` log
+    /// messages it produced at or above the level the client asked for
+    pub fn handle_message_with_logs(&self, message: &str) -> (Option<Value>, Vec<Value>) {
+        self.handle_session_message(Some(STDIO_SESSION), message)
+    }
+
+    /// `handle_message_with_logs` for one of several clients sharing the
+    /// server, told apart by their `Mcp-Session-Id`. Without a session there
+    /// is nowhere to keep a log level, so `logging/setLevel` is ignored
+    pub fn handle_session_message(&self, session: Option<&str>, message: &str) -> (Option<Value>, Vec<Value>) {
+        let client_level = session.and_then(|session| {
+            let mut levels = self.client_log_levels.lock().ok()?;
+            let (level, last_used) = levels.get_mut(session)?;
+            *last_used = Instant::now();
+            Some(*level)
+        });
+        Logger::capture_for_client(client_level, || self.handle_message_in(session, message))
+    }
+
+    /// Forget a session's log level once its client has gone
+    pub fn end_session(&self, session: &str) {
+        if let Ok(mut levels) = self.client_log_levels.lock() {
+            levels.remove(session);
+        }
+    }
+
+    /// Handle one JSON-RPC message; notifications produce no response
+    pub fn handle_message(&self, message: &str) -> Option<Value> {
+        self.handle_message_in(Some(STDIO_SESSION), message)
+    }
+
+    fn handle_message_in(&self, session: Option<&str>, message: &str) -> Option<Value> {
+        Logger::debug(LOGGER, &format!("Received: {}", message));
 
         let request: Value = match serde_json::from_str(message) {
-            Ok(req) => {
-                log_to_file(&format!(
-                    "Parsed request: {}",
-                    serde_json::to_string_pretty(&req)
-                        .unwrap_or_else(|_| "Failed to pretty print".to_string())
-                ));
-                req
-            }
+            Ok(req) => req,
             Err(e) => {
-                log_to_file(&format!("Parse error: {}", e));
+                Logger::warning(LOGGER, &format!("Parse error: {}", e));
                 return Some(self.error_response(None, -32700, "Parse error", None));
             }
         };
@@ -94,39 +367,48 @@ impl McpServer {
         let id = request.get("id").cloned();
         let method = request.get("method").and_then(|m| m.as_str());
 
-        log_to_file(&format!("Extracted - Method: {:?}", method));
-        log_to_file(&format!("Extracted - ID: {:?}", id));
-        log_to_file(&format!(
-            "ID as JSON: {}",
-            serde_json::to_string(&id).unwrap_or_else(|_| "null".to_string())
-        ));
-
         match method {
-            Some("initialize") => Some(self.handle_initialize(id)),
+            Some("initialize") => Some(self.handle_initialize(id, &request)),
             Some("initialized") | Some("notifications/initialized") => None, // No response needed for initialized notification
             Some("tools/list") => Some(self.handle_tools_list(id)),
             Some("tools/call") => Some(self.handle_tools_call(id, &request)),
+            Some("resources/list") => Some(self.handle_resources_list(id)),
+            Some("resources/templates/list") => Some(self.handle_resource_templates_list(id)),
+            Some("resources/read") => Some(self.handle_resources_read(id, &request)),
+            Some("prompts/list") => Some(self.handle_prompts_list(id)),
+            Some("prompts/get") => Some(self.handle_prompts_get(id, &request)),
+            Some("logging/setLevel") => Some(self.handle_set_log_level(session, id, &request)),
             Some(unknown_method) => {
-                log_to_file(&format!("Unknown method: {}", unknown_method));
-                log_to_file(&format!("Returning error with ID: {:?}", id));
+                Logger::warning(LOGGER, &format!("Unknown method: {}", unknown_method));
                 Some(self.error_response(id, -32601, "Method not found", None))
             }
             None => {
-                log_to_file("No method found in request");
-                log_to_file(&format!("Returning error with ID: {:?}", id));
+                Logger::warning(LOGGER, "No method found in request");
                 Some(self.error_response(id, -32600, "Invalid Request", None))
             }
         }
     }
 
-    fn handle_initialize(&self, id: Option<Value>) -> Value {
+    fn handle_initialize(&self, id: Option<Value>, request: &Value) -> Value {
+        // Echo the client's version when supported, otherwise offer the latest
+        let requested = request
+            .get("params")
+            .and_then(|p| p.get("protocolVersion"))
+            .and_then(|v| v.as_str());
+        let protocol_version = requested
+            .filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version))
+            .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0]);
+
         json!({
             "jsonrpc": "2.0",
             "id": id,
             "result": {
-                "protocolVersion": "2024-11-05",
+                "protocolVersion": protocol_version,
                 "capabilities": {
-                    "tools": {}
+                    "tools": {},
+                    "resources": {},
+                    "prompts": {},
+                    "logging": {}
                 },
                 "serverInfo": {
                     "name": "D&D Assistant",
@@ -136,7 +418,58 @@ impl McpServer {
         })
     }
 
+    fn handle_set_log_level(&self, session: Option<&str>, id: Option<Value>, request: &Value) -> Value {
+        let requested = request
+            .get("params")
+            .and_then(|p| p.get("level"))
+            .and_then(|l| l.as_str())
+            .unwrap_or_default();
+        let level = match requested.parse::<LogLevel>() {
+            Ok(level) => level,
+            Err(e) => return self.error_response(id, -32602, &e.to_string(), None),
+        };
+
+        match session {
+            Some(session) => {
+                if let Ok(mut levels) = self.client_log_levels.lock() {
+                    let now = Instant::now();
+                    levels.retain(|_, (_, last_used)| now.duration_since(*last_used) < SESSION_IDLE_TIMEOUT);
+                    if levels.len() >= MAX_CLIENT_SESSIONS && !levels.contains_key(session) {
+                        let least_recent = levels
+                            .iter()
+                            .min_by_key(|(_, (_, last_used))| *last_used)
+                            .map(|(session, _)| session.clone());
+                        if let Some(least_recent) = least_recent {
+                            levels.remove(&least_recent);
+                        }
+                    }
+                    levels.insert(session.to_string(), (level, now));
+                }
+                Logger::debug(LOGGER, &format!("Client log level set to {}", level));
+            }
+            None => Logger::debug(LOGGER, "Ignoring logging/setLevel from a client without a session"),
+        }
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {}
+        })
+    }
+
     fn handle_tools_list(&self, id: Option<Value>) -> Value {
+        let mut character_data_schema = SchemaGenerator::character_data();
+        character_data_schema["description"] = json!("Complete D&D 5e character information");
+        let mut level_up_character_schema = SchemaGenerator::character_data();
+        level_up_character_schema["description"] =
+            json!("Complete D&D 5e character information at the current level");
+        let mut choices_schema = SchemaGenerator::level_up_choices();
+        choices_schema["description"] = json!("Level-up decisions; anything required but missing is returned as a pending choice");
+        choices_schema["default"] = json!({});
+        let mut export_character_schema = SchemaGenerator::character_data();
+        export_character_schema["description"] = json!("Complete D&D 5e character information");
+        let mut sheet_path_schema = self.output_path_schema("Path where filled PDF should be saved");
+        sheet_path_schema["default"] = json!(DEFAULT_OUTPUT_FILE);
+
         json!({
             "jsonrpc": "2.0",
             "id": id,
@@ -148,229 +481,1432 @@ impl McpServer {
                         "inputSchema": {
                             "type": "object",
                             "properties": {
-                                "character_data": {
-                                    "type": "object",
-                                    "description": "Complete D&D 5e character information"
-                                },
-                                "output_path": {
-                                    "type": "string",
-                                    "description": "Path where filled PDF should be saved",
-                                    "default": "filled_character_sheet.pdf"
-                                },
+                                "character_data": character_data_schema,
+                                "output_path": sheet_path_schema,
+                                "overwrite": Self::overwrite_schema("pdf"),
                                 "return_pdf_content": {
                                     "type": "boolean",
                                     "default": false,
-                                    "description": "Return the PDF file content as base64 for the LLM to save (WARNING: Large response)"
+                                    "description": "Also return the PDF as an embedded resource content item (WARNING: Large response)"
+                                },
+                                "advancement_mode": {
+                                    "type": "string",
+                                    "enum": ["xp", "milestone"],
+                                    "description": "Overrides the character's own advancement_mode; \"milestone\" skips the XP-for-level check and leaves XP off the sheet"
                                 },
                                 "allow_rule_violations": {
                                     "type": "boolean",
-                                    "default": false,
+                                    "default": self.config.allow_rule_violations,
                                     "description": "Allow character data that violates D&D 5e rules"
                                 }
                             },
                             "required": ["character_data"]
-                        }
-                    }
-                ]
-            }
-        })
-    }
-
-    fn handle_tools_call(&self, id: Option<Value>, request: &Value) -> Value {
-        let params = match request.get("params") {
-            Some(p) => p,
-            None => return self.error_response(id, -32602, "Invalid params", None),
-        };
-
-        let tool_name = match params.get("name").and_then(|n| n.as_str()) {
-            Some("fill_dnd_character_sheet") => "fill_dnd_character_sheet",
-            _ => return self.error_response(id, -32602, "Unknown tool", None),
-        };
-
-        let arguments = match params.get("arguments") {
-            Some(args) => args,
-            None => return self.error_response(id, -32602, "Missing arguments", None),
-        };
-
-        match tool_name {
-            "fill_dnd_character_sheet" => self.handle_fill_character_sheet(id, arguments),
-            _ => self.error_response(id, -32602, "Unknown tool", None),
-        }
-    }
-
-    fn handle_fill_character_sheet(&self, id: Option<Value>, arguments: &Value) -> Value {
-        log_to_file("Starting fill_character_sheet processing");
-
-        // Parse character data
-        let character_data = match arguments.get("character_data") {
-            Some(data) => match serde_json::from_value::<CharacterData>(data.clone()) {
-                Ok(char_data) => {
-                    log_to_file("Character data parsed successfully");
-                    char_data
-                }
-                Err(e) => {
-                    log_to_file(&format!("Character data parse error: {}", e));
-                    return self.error_response(
-                        id,
-                        -32602,
-                        "Invalid character data",
-                        Some(json!({"parse_error": e.to_string()})),
-                    );
-                }
-            },
-            None => {
-                log_to_file("Missing character_data parameter");
-                return self.error_response(id, -32602, "Missing character_data", None);
-            }
-        };
-
-        // Get output path
-        let output_path = arguments
-            .get("output_path")
-            .and_then(|p| p.as_str())
-            .unwrap_or("filled_character_sheet.pdf");
-
-        // Get return PDF content setting
-        let return_pdf_content = arguments
-            .get("return_pdf_content")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-
-        // Get rule violation setting
-        let allow_violations = arguments
-            .get("allow_rule_violations")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-
-        log_to_file(&format!(
-            "Processing PDF: output_path={}, allow_violations={}, return_content={}",
-            output_path, allow_violations, return_pdf_content
-        ));
-
-        // Create PDF filler with appropriate settings
-        let filler = PdfFiller::new(allow_violations);
-
-        // Fill the character sheet using embedded template
-        match filler.fill_character_sheet_from_bytes(
-            &character_data,
-            PDF_TEMPLATE,
-            output_path,
-        ) {
-            Ok(result) => {
-                log_to_file("PDF processing completed successfully");
-                let mut response_data = json!({
-                    "success": true,
-                    "output_file": result.output_file,
-                    "calculated_fields": result.calculated_fields,
-                    "message": format!("PDF character sheet created successfully at: {}", result.output_file)
-                });
-
-                // Include PDF content if requested (WARNING: Large response)
-                if return_pdf_content {
-                    match fs::read(&result.output_file) {
-                        Ok(pdf_bytes) => {
-                            // Check size limit (5MB for MCP response)
-                            if pdf_bytes.len() > 5 * 1024 * 1024 {
-                                log_to_file("PDF too large for content return");
-                                response_data["pdf_content_error"] = json!("PDF file too large (>5MB) for MCP response. File saved locally.");
-                                response_data["file_size"] = json!(pdf_bytes.len());
-                            } else {
-                                let base64_content = general_purpose::STANDARD.encode(&pdf_bytes);
-                                response_data["pdf_content"] = json!(base64_content);
-                                response_data["file_size"] = json!(pdf_bytes.len());
-                                log_to_file(&format!("PDF content encoded as base64, size: {} bytes", pdf_bytes.len()));
-                            }
-                        }
-                        Err(e) => {
-                            log_to_file(&format!("Failed to read PDF file: {}", e));
-                            response_data["pdf_content_error"] = json!(e.to_string());
-                        }
-                    }
-                }
-
-                if !result.validation_errors.is_empty() {
-                    response_data["validation_errors"] = json!(result
-                        .validation_errors
-                        .iter()
-                        .map(|e| json!({
-                            "error": e.to_string(),
-                            "severity": if allow_violations { "warning" } else { "error" }
-                        }))
-                        .collect::<Vec<_>>());
-
-                    if allow_violations {
-                        response_data["rule_violations_overridden"] = json!(true);
-                    }
-                }
-
-                log_to_file("Returning success response");
-                let final_response = json!({
-                    "jsonrpc": "2.0",
-                    "id": id,
-                    "result": {
-                        "content": [
-                            {
-                                "type": "text",
-                                "text": format!("Character sheet created successfully at: {}", result.output_file)
-                            }
-                        ],
-                        "isError": false
-                    }
-                });
-                
-                log_to_file(&format!("Response structure: {}", 
-                    serde_json::to_string_pretty(&final_response)
-                        .unwrap_or_else(|_| "Failed to serialize".to_string())
-                ));
-                
-                final_response
-            }
-            Err(e) => {
-                log_to_file(&format!("PDF processing failed: {}", e));
-                json!({
-                    "jsonrpc": "2.0",
-                    "id": id,
-                    "result": {
-                        "content": [
-                            {
-                                "type": "text",
-                                "text": format!("Error: PDF processing failed - {}", e)
-                            }
-                        ],
-                        "isError": true
-                    }
-                })
-            }
-        }
-    }
-
-    fn error_response(
-        &self,
-        id: Option<Value>,
-        code: i32,
-        message: &str,
-        data: Option<Value>,
-    ) -> Value {
-        let mut error = json!({
-            "code": code,
-            "message": message
-        });
-
-        if let Some(data) = data {
-            error["data"] = data;
-        }
-
-        let response = json!({
-            "jsonrpc": "2.0",
-            "id": id,
-            "error": error
-        });
-
-        log_to_file(&format!(
-            "Sending error response: {}",
-            serde_json::to_string_pretty(&response)
-                .unwrap_or_else(|_| "Failed to serialize".to_string())
-        ));
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<FillSheetOutput>()
+                    },
+                    {
+                        "name": "level_up_character",
+                        "description": "Levels a single-class D&D 5e character up by one level in their own class (multiclassing is not supported), returning the updated character, a diff of every changed value, and any choices (hit points, ASI/feat, subclass) that still need the player's input",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "character_data": level_up_character_schema,
+                                "choices": choices_schema,
+                                "advancement_mode": {
+                                    "type": "string",
+                                    "enum": ["xp", "milestone"],
+                                    "description": "Overrides the character's own advancement_mode; \"milestone\" skips the XP-for-level check"
+                                },
+                                "allow_rule_violations": {
+                                    "type": "boolean",
+                                    "default": self.config.allow_rule_violations,
+                                    "description": "Allow a leveled character that violates D&D 5e rules"
+                                }
+                            },
+                            "required": ["character_data"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<LevelUpOutput>()
+                    },
+                    {
+                        "name": "export_character_markdown",
+                        "description": "Renders a D&D 5e character as a Markdown sheet (tables for abilities, saves and skills, spells by level with prepared markers, inventory and narrative) for character-level-N.md files or an Obsidian vault",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "character_data": export_character_schema,
+                                "output_path": self.output_path_schema("Also save the Markdown to this file"),
+                                "overwrite": Self::overwrite_schema("md")
+                            },
+                            "required": ["character_data"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<MarkdownExportOutput>()
+                    },
+                    {
+                        "name": "export_character_html",
+                        "description": "Renders a D&D 5e character as a self-contained, printable HTML sheet (abilities, saves and skills with proficiency dots, attacks, features, narrative and a spellcasting page) for a campaign wiki; pass party to render several characters on one party page",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "character_data": export_character_schema,
+                                "party": {
+                                    "type": "array",
+                                    "items": export_character_schema,
+                                    "description": "The other party members; renders a party page with a summary table followed by every sheet, character_data first"
+                                },
+                                "party_name": {
+                                    "type": "string",
+                                    "description": "Title of the party page (default \"Party\")"
+                                },
+                                "output_path": self.output_path_schema("Also save the HTML to this file"),
+                                "overwrite": Self::overwrite_schema("html")
+                            },
+                            "required": ["character_data"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<HtmlExportOutput>()
+                    },
+                    {
+                        "name": "export_character_foundry",
+                        "description": "Converts a D&D 5e character into a Foundry VTT dnd5e actor (abilities, skills, HP, spells with preparation mode, inventory, currency, biography) for the actor sheet's Import Data",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "character_data": export_character_schema,
+                                "output_path": self.output_path_schema("Also save the actor JSON to this file"),
+                                "overwrite": Self::overwrite_schema("json")
+                            },
+                            "required": ["character_data"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<FoundryExportOutput>()
+                    },
+                    {
+                        "name": "import_character_markdown",
+                        "description": "Reads a Markdown character file in the export_character_markdown layout back into character_data; problems are reported with line numbers",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "path": {
+                                    "type": "string",
+                                    "description": format!("Markdown file to read; relative paths are under {}", self.config.output_dir().display())
+                                }
+                            },
+                            "required": ["path"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<CharacterImportOutput>()
+                    },
+                    {
+                        "name": "export_character_fight_club",
+                        "description": "Converts a D&D 5e character into Fight Club 5 / Game Master 5 character XML (abilities, class, spells by slot level, items, coins, proficiency ids, ASIs and feats)",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "character_data": export_character_schema,
+                                "output_path": self.output_path_schema("Also save the XML to this file"),
+                                "overwrite": Self::overwrite_schema("xml")
+                            },
+                            "required": ["character_data"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<FightClubExportOutput>()
+                    },
+                    {
+                        "name": "import_character_fight_club",
+                        "description": "Reads a Fight Club 5 / Game Master 5 character XML file into character_data; problems are reported with line numbers",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "path": {
+                                    "type": "string",
+                                    "description": format!("XML file to read; relative paths are under {}", self.config.output_dir().display())
+                                }
+                            },
+                            "required": ["path"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<CharacterImportOutput>()
+                    },
+                    {
+                        "name": "import_character_dndbeyond",
+                        "description": "Reads a saved D&D Beyond character JSON file into character_data; modifiers, expertise, feats' levels and anything else the sheet has no place for are listed in ignored",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "path": {
+                                    "type": "string",
+                                    "description": format!("JSON file to read; relative paths are under {}", self.config.output_dir().display())
+                                }
+                            },
+                            "required": ["path"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<CharacterImportOutput>()
+                    },
+                    {
+                        "name": "save_character",
+                        "description": "Saves a D&D 5e character to the local character store under an id, keeping a numbered snapshot of every save, and reports what changed since the previous save",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "character_data": export_character_schema,
+                                "id": {
+                                    "type": "string",
+                                    "description": "Store id: lowercase letters, digits and dashes; defaults to the character's name, e.g. \"erilon-silverleaf\"; needed when a different character already has that id"
+                                }
+                            },
+                            "required": ["character_data"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<SaveCharacterOutput>()
+                    },
+                    {
+                        "name": "load_character",
+                        "description": "Loads a saved character's character_data from the local character store, either the current version or an earlier snapshot",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "id": {
+                                    "type": "string",
+                                    "description": "Store id, as returned by save_character or list_characters"
+                                },
+                                "version": {
+                                    "type": "integer",
+                                    "minimum": 1,
+                                    "description": "Snapshot to load; the current version when omitted"
+                                }
+                            },
+                            "required": ["id"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<LoadCharacterOutput>()
+                    },
+                    {
+                        "name": "list_characters",
+                        "description": "Lists the characters in the local character store with their class, level, latest version and when they were last saved",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {}
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<CharacterListOutput>()
+                    },
+                    {
+                        "name": "delete_character",
+                        "description": "Deletes a character and all of its snapshots from the local character store",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "id": {
+                                    "type": "string",
+                                    "description": "Store id of the character to delete"
+                                }
+                            },
+                            "required": ["id"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<DeleteCharacterOutput>()
+                    },
+                    {
+                        "name": "character_diff",
+                        "description": "Describes what changed between two saved versions of a character in plain lines, e.g. \"Character / Level: 4 → 5\" or \"Spells / Third Level: added Fireball\"",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "id": {
+                                    "type": "string",
+                                    "description": "Store id of the character"
+                                },
+                                "from": {
+                                    "type": "integer",
+                                    "minimum": 1,
+                                    "description": "Older version; the one before to when omitted"
+                                },
+                                "to": {
+                                    "type": "integer",
+                                    "minimum": 1,
+                                    "description": "Newer version; the latest when omitted"
+                                }
+                            },
+                            "required": ["id"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<CharacterDiffOutput>()
+                    },
+                    {
+                        "name": "add_party_member",
+                        "description": "Adds a player and their saved character to a party in the local character store, creating the party on its first member; a player already in the party switches to the new character",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "party": {
+                                    "type": "string",
+                                    "description": "Party name, e.g. \"The Iron Company\", or its id"
+                                },
+                                "player": {
+                                    "type": "string",
+                                    "description": "The real person playing the character"
+                                },
+                                "character_id": {
+                                    "type": "string",
+                                    "description": "Store id of the player's saved character"
+                                }
+                            },
+                            "required": ["party", "player", "character_id"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<PartyOutput>()
+                    },
+                    {
+                        "name": "remove_party_member",
+                        "description": "Removes a player from a party; their character stays in the store",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "party": {
+                                    "type": "string",
+                                    "description": "Party name or id"
+                                },
+                                "player": {
+                                    "type": "string",
+                                    "description": "Player to remove; matched regardless of case"
+                                }
+                            },
+                            "required": ["party", "player"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<PartyOutput>()
+                    },
+                    {
+                        "name": "get_party",
+                        "description": "Shows a party's members (player to character id), shared treasure and party XP",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "party": {
+                                    "type": "string",
+                                    "description": "Party name or id"
+                                }
+                            },
+                            "required": ["party"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<PartyOutput>()
+                    },
+                    {
+                        "name": "find_party_character",
+                        "description": "Looks up the character a player plays and returns its saved character_data",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "player": {
+                                    "type": "string",
+                                    "description": "Player name; matched regardless of case"
+                                },
+                                "party": {
+                                    "type": "string",
+                                    "description": "Party name or id; every party is searched when omitted"
+                                }
+                            },
+                            "required": ["player"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<PartyCharacterOutput>()
+                    },
+                    {
+                        "name": "fill_party_sheets",
+                        "description": "Fills the character sheet of every party member in one call, into a single combined PDF (flattened, one sheet after another) or a zip with one fillable PDF per character",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "party": {
+                                    "type": "string",
+                                    "description": "Party name or id"
+                                },
+                                "format": {
+                                    "type": "string",
+                                    "enum": ["pdf", "zip"],
+                                    "default": "pdf",
+                                    "description": "\"pdf\" for one combined PDF, \"zip\" for a zip of separate PDFs"
+                                },
+                                "output_path": self.output_path_schema("Where to save the file (default \"<party id>.pdf\" or \".zip\")"),
+                                "overwrite": Self::overwrite_schema("pdf"),
+                                "advancement_mode": {
+                                    "type": "string",
+                                    "enum": ["xp", "milestone"],
+                                    "description": "Overrides every member's own advancement_mode; \"milestone\" skips the XP-for-level check and leaves XP off the sheets"
+                                },
+                                "allow_rule_violations": {
+                                    "type": "boolean",
+                                    "default": self.config.allow_rule_violations,
+                                    "description": "Fill every sheet even when characters break D&D 5e rules; violations are reported as warnings"
+                                }
+                            },
+                            "required": ["party"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<FillPartyOutput>()
+                    },
+                    {
+                        "name": "record_session",
+                        "description": "Records a game session (date, attendees, XP awarded, loot, milestones and notes) in the local character store; set apply to hand out the XP and loot right away",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "number": {
+                                    "type": "integer",
+                                    "minimum": 1,
+                                    "description": "Session number; the next one when omitted. Recording an existing number replaces it unless it was applied"
+                                },
+                                "date": {
+                                    "type": "string",
+                                    "description": "When it was played, YYYY-MM-DD; today when omitted"
+                                },
+                                "party": {
+                                    "type": "string",
+                                    "description": "Party name or id; lets attendees and loot recipients be given by player name, and receives loot without a character_id"
+                                },
+                                "attendees": {
+                                    "type": "array",
+                                    "items": {"type": "string"},
+                                    "description": "Character ids (or player names in the party) of everyone who took part"
+                                },
+                                "experience_points": {
+                                    "type": "integer",
+                                    "minimum": 0,
+                                    "default": 0,
+                                    "description": "XP awarded to each attending character"
+                                },
+                                "loot": {
+                                    "type": "array",
+                                    "items": SchemaGenerator::inline_schema_for::<Loot>(),
+                                    "description": "Coins and items found, each for one character or, without character_id, for the party"
+                                },
+                                "milestones": {
+                                    "type": "array",
+                                    "items": {"type": "string"},
+                                    "description": "Story milestones reached, e.g. \"Cleared Wave Echo Cave\""
+                                },
+                                "notes": {
+                                    "type": "string",
+                                    "description": "Session notes"
+                                },
+                                "apply": {
+                                    "type": "boolean",
+                                    "default": false,
+                                    "description": "Also give out the XP and loot, as apply_session does"
+                                }
+                            },
+                            "required": ["attendees"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<SessionOutput>()
+                    },
+                    {
+                        "name": "apply_session",
+                        "description": "Gives a recorded session's XP and loot to the attending characters (saving a snapshot of each) and the party, and flags who is ready to level up",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "number": {
+                                    "type": "integer",
+                                    "minimum": 1,
+                                    "description": "Session to apply; each session can be applied once"
+                                }
+                            },
+                            "required": ["number"]
+                        },
+                        "outputSchema": SchemaGenerator::inline_schema_for::<SessionOutput>()
+                    }
+                ]
+            }
+        })
+    }
+
+    fn handle_resources_list(&self, id: Option<Value>) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "resources": self.resources.list()
+            }
+        })
+    }
+
+    fn handle_resource_templates_list(&self, id: Option<Value>) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "resourceTemplates": self.resources.templates()
+            }
+        })
+    }
+
+    fn handle_resources_read(&self, id: Option<Value>, request: &Value) -> Value {
+        let uri = match request
+            .get("params")
+            .and_then(|p| p.get("uri"))
+            .and_then(|u| u.as_str())
+        {
+            Some(uri) => uri,
+            None => return self.error_response(id, -32602, "Missing uri", None),
+        };
+
+        match self.resources.read(uri) {
+            Ok(result) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": result
+            }),
+            Err(ResourceError::NotFound(uri)) => self.error_response(
+                id,
+                -32002,
+                "Resource not found",
+                Some(json!({"uri": uri})),
+            ),
+            Err(e) => self.error_response(id, -32603, &e.to_string(), None),
+        }
+    }
+
+    fn handle_prompts_list(&self, id: Option<Value>) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "prompts": PromptLibrary::list()
+            }
+        })
+    }
+
+    fn handle_prompts_get(&self, id: Option<Value>, request: &Value) -> Value {
+        let params = request.get("params");
+        let name = match params.and_then(|p| p.get("name")).and_then(|n| n.as_str()) {
+            Some(name) => name,
+            None => return self.error_response(id, -32602, "Missing prompt name", None),
+        };
+        let arguments = params
+            .and_then(|p| p.get("arguments"))
+            .and_then(|a| a.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        match PromptLibrary::get(name, &arguments) {
+            Ok(result) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": result
+            }),
+            Err(e) => self.error_response(id, -32602, &e.to_string(), None),
+        }
+    }
+
+    fn handle_tools_call(&self, id: Option<Value>, request: &Value) -> Value {
+        let params = match request.get("params") {
+            Some(p) => p,
+            None => return self.error_response(id, -32602, "Invalid params", None),
+        };
+
+        let tool_name = match params.get("name").and_then(|n| n.as_str()) {
+            Some("fill_dnd_character_sheet") => "fill_dnd_character_sheet",
+            Some("level_up_character") => "level_up_character",
+            Some("export_character_markdown") => "export_character_markdown",
+            Some("export_character_html") => "export_character_html",
+            Some("export_character_foundry") => "export_character_foundry",
+            Some("import_character_markdown") => "import_character_markdown",
+            Some("export_character_fight_club") => "export_character_fight_club",
+            Some("import_character_fight_club") => "import_character_fight_club",
+            Some("import_character_dndbeyond") => "import_character_dndbeyond",
+            Some("save_character") => "save_character",
+            Some("load_character") => "load_character",
+            Some("list_characters") => "list_characters",
+            Some("delete_character") => "delete_character",
+            Some("character_diff") => "character_diff",
+            Some("add_party_member") => "add_party_member",
+            Some("remove_party_member") => "remove_party_member",
+            Some("get_party") => "get_party",
+            Some("find_party_character") => "find_party_character",
+            Some("fill_party_sheets") => "fill_party_sheets",
+            Some("record_session") => "record_session",
+            Some("apply_session") => "apply_session",
+            _ => return self.error_response(id, -32602, "Unknown tool", None),
+        };
+
+        // Arguments are optional, e.g. for list_characters; each tool reports what it's missing
+        let no_arguments = json!({});
+        let arguments = params.get("arguments").unwrap_or(&no_arguments);
+
+        match tool_name {
+            "fill_dnd_character_sheet" => self.handle_fill_character_sheet(id, arguments),
+            "level_up_character" => self.handle_level_up_character(id, arguments),
+            "export_character_markdown" => self.handle_export_character_markdown(id, arguments),
+            "export_character_html" => self.handle_export_character_html(id, arguments),
+            "export_character_foundry" => self.handle_export_character_foundry(id, arguments),
+            "import_character_markdown" => self.handle_import_character_markdown(id, arguments),
+            "export_character_fight_club" => self.handle_export_character_fight_club(id, arguments),
+            "import_character_fight_club" => self.handle_import_character_fight_club(id, arguments),
+            "import_character_dndbeyond" => self.handle_import_character_dndbeyond(id, arguments),
+            "save_character" => self.handle_save_character(id, arguments),
+            "load_character" => self.handle_load_character(id, arguments),
+            "list_characters" => self.handle_list_characters(id),
+            "delete_character" => self.handle_delete_character(id, arguments),
+            "character_diff" => self.handle_character_diff(id, arguments),
+            "add_party_member" => self.handle_add_party_member(id, arguments),
+            "remove_party_member" => self.handle_remove_party_member(id, arguments),
+            "get_party" => self.handle_get_party(id, arguments),
+            "find_party_character" => self.handle_find_party_character(id, arguments),
+            "fill_party_sheets" => self.handle_fill_party_sheets(id, arguments),
+            "record_session" => self.handle_record_session(id, arguments),
+            "apply_session" => self.handle_apply_session(id, arguments),
+            _ => self.error_response(id, -32602, "Unknown tool", None),
+        }
+    }
+
+    fn handle_fill_character_sheet(&self, id: Option<Value>, arguments: &Value) -> Value {
+        Logger::debug(LOGGER, "Starting fill_character_sheet processing");
+
+        // Parse character data
+        let character_data = match self.character_argument(&id, arguments.get("character_data")) {
+            Ok(character_data) => character_data,
+            Err(response) => return response,
+        };
+
+        // Resolve the output path against the configured output directory
+        let requested_path = arguments.get("output_path").and_then(|p| p.as_str());
+        let output_path = match self.config.resolve_output(requested_path, DEFAULT_OUTPUT_FILE) {
+            Ok(path) => path.display().to_string(),
+            Err(e) => {
+                Logger::warning(LOGGER, &e.to_string());
+                return self.tool_text_response(id, format!("Error: {}", e), true);
+            }
+        };
+
+        // Get return PDF content setting
+        let return_pdf_content = arguments
+            .get("return_pdf_content")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // Get rule violation setting
+        let allow_violations = arguments
+            .get("allow_rule_violations")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(self.config.allow_rule_violations);
+
+        Logger::info(LOGGER, &format!(
+            "Processing PDF: output_path={}, allow_violations={}, return_content={}",
+            output_path, allow_violations, return_pdf_content
+        ));
+
+        let overwrite = arguments
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let Ok(advancement_mode) = Self::advancement_mode_argument(arguments) else {
+            return self.error_response(id, -32602, "Invalid advancement_mode; use \"xp\" or \"milestone\"", None);
+        };
+
+        // Create PDF filler with appropriate settings
+        let filler = PdfFiller::new(allow_violations)
+            .with_overwrite(overwrite)
+            .with_image_roots(&self.config)
+            .with_advancement_mode(advancement_mode);
+
+        // Fill the character sheet using embedded template
+        let result = match filler.fill_character_sheet_from_bytes(
+            &character_data,
+            &self.template,
+            &output_path,
+        ) {
+            Ok(result) => result,
+            Err(PdfError::ValidationFailed(errors)) => {
+                Logger::warning(LOGGER, &format!("Validation failed with {} errors", errors.len()));
+                let output = FillSheetOutput {
+                    success: false,
+                    output_file: None,
+                    message: format!(
+                        "Character data violates {} D&D 5e rule(s); fix them or set allow_rule_violations",
+                        errors.len()
+                    ),
+                    calculated_fields: BTreeMap::new(),
+                    validation_errors: ValidationIssue::from_errors(&errors, false),
+                    rule_violations_overridden: false,
+                    file_size: None,
+                    pdf_content_error: None,
+                };
+                return self.structured_tool_response(id, &output, Vec::new(), true);
+            }
+            Err(e) => {
+                Logger::error(LOGGER, &format!("PDF processing failed: {}", e));
+                return self.tool_text_response(
+                    id,
+                    format!("Error: PDF processing failed - {}", e),
+                    true,
+                );
+            }
+        };
+
+        Logger::info(LOGGER, "PDF processing completed successfully");
+        let mut output = FillSheetOutput {
+            success: true,
+            output_file: Some(result.output_file.clone()),
+            message: format!("Character sheet created successfully at: {}", result.output_file),
+            calculated_fields: result.calculated_fields.into_iter().collect(),
+            validation_errors: ValidationIssue::from_errors(&result.validation_errors, allow_violations),
+            rule_violations_overridden: allow_violations && !result.validation_errors.is_empty(),
+            file_size: fs::metadata(&result.output_file).ok().map(|m| m.len()),
+            pdf_content_error: None,
+        };
+
+        // Embed the PDF as a resource content item if requested (WARNING: Large response)
+        let mut extra_content = Vec::new();
+        if return_pdf_content {
+            match fs::read(&result.output_file) {
+                // Check size limit (5MB for MCP response)
+                Ok(pdf_bytes) if pdf_bytes.len() > 5 * 1024 * 1024 => {
+                    Logger::warning(LOGGER, "PDF too large for content return");
+                    output.pdf_content_error = Some(
+                        "PDF file too large (>5MB) for MCP response. File saved locally.".to_string(),
+                    );
+                }
+                Ok(pdf_bytes) => {
+                    extra_content.push(json!({
+                        "type": "resource",
+                        "resource": {
+                            "uri": self.resources.sheet_uri(Path::new(&result.output_file)),
+                            "mimeType": "application/pdf",
+                            "blob": general_purpose::STANDARD.encode(&pdf_bytes)
+                        }
+                    }));
+                    Logger::debug(LOGGER, &format!("PDF content embedded as base64, size: {} bytes", pdf_bytes.len()));
+                }
+                Err(e) => {
+                    Logger::error(LOGGER, &format!("Failed to read PDF file: {}", e));
+                    output.pdf_content_error = Some(e.to_string());
+                }
+            }
+        }
+
+        Logger::debug(LOGGER, "Returning success response");
+        self.structured_tool_response(id, &output, extra_content, false)
+    }
+
+    fn handle_level_up_character(&self, id: Option<Value>, arguments: &Value) -> Value {
+        Logger::debug(LOGGER, "Starting level_up_character processing");
+
+        let character_data = match self.character_argument(&id, arguments.get("character_data")) {
+            Ok(character_data) => character_data,
+            Err(response) => return response,
+        };
+
+        let choices = match arguments.get("choices") {
+            Some(choices) => match serde_json::from_value::<LevelUpChoices>(choices.clone()) {
+                Ok(choices) => choices,
+                Err(e) => {
+                    Logger::warning(LOGGER, &format!("Level-up choices parse error: {}", e));
+                    return self.error_response(
+                        id,
+                        -32602,
+                        "Invalid choices",
+                        Some(json!({"parse_error": e.to_string()})),
+                    );
+                }
+            },
+            None => LevelUpChoices::default(),
+        };
+
+        let allow_violations = arguments
+            .get("allow_rule_violations")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(self.config.allow_rule_violations);
+        let Ok(advancement_mode) = Self::advancement_mode_argument(arguments) else {
+            return self.error_response(id, -32602, "Invalid advancement_mode; use \"xp\" or \"milestone\"", None);
+        };
+
+        let outcome = match LevelUpEngine::level_up(&character_data, &choices) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                Logger::warning(LOGGER, &format!("Level up failed: {}", e));
+                return self.tool_text_response(id, format!("Error: Level up failed - {}", e), true);
+            }
+        };
+
+        let mut validation_errors = Vec::new();
+        let mut is_error = false;
+        if outcome.is_complete() {
+            let validator = DndValidator::new(allow_violations).with_advancement_mode(advancement_mode);
+            match validator.validate(&outcome.character) {
+                Ok(warnings) => {
+                    validation_errors = ValidationIssue::from_errors(&warnings, allow_violations)
+                }
+                Err(errors) => {
+                    validation_errors = ValidationIssue::from_errors(&errors, false);
+                    is_error = true;
+                }
+            }
+        }
+
+        Logger::info(LOGGER, &format!(
+            "Level up processed: complete={}, changes={}",
+            outcome.is_complete(),
+            outcome.changes.len()
+        ));
+        let output = LevelUpOutput {
+            complete: outcome.is_complete(),
+            outcome,
+            validation_errors,
+        };
+        self.structured_tool_response(id, &output, Vec::new(), is_error)
+    }
+
+    fn handle_export_character_markdown(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let character_data = match self.character_argument(&id, arguments.get("character_data")) {
+            Ok(character_data) => character_data,
+            Err(response) => return response,
+        };
+
+        let markdown = MarkdownExporter::export(&character_data);
+        let output_file = match self.save_export(arguments, markdown.as_bytes()) {
+            Ok(output_file) => output_file,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        Logger::info(LOGGER, &format!("Exported {} as Markdown", character_data.character.name));
+        let output = MarkdownExportOutput { markdown, output_file };
+        self.structured_tool_response(id, &output, Vec::new(), false)
+    }
+
+    fn handle_export_character_html(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let mut characters = Vec::new();
+        let party = arguments.get("party").and_then(|p| p.as_array());
+        let members = party.into_iter().flatten().map(Some);
+        for data in std::iter::once(arguments.get("character_data")).chain(members) {
+            match self.character_argument(&id, data) {
+                Ok(character_data) => characters.push(character_data),
+                Err(response) => return response,
+            }
+        }
+
+        let html = match party {
+            Some(_) => {
+                let party_name = arguments.get("party_name").and_then(|n| n.as_str()).unwrap_or("Party");
+                HtmlExporter::export_party(party_name, &characters)
+            }
+            None => HtmlExporter::export(&characters[0]),
+        };
+        let output_file = match self.save_export(arguments, html.as_bytes()) {
+            Ok(output_file) => output_file,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        Logger::info(LOGGER, &format!("Exported {} character(s) as HTML", characters.len()));
+        let output = HtmlExportOutput { html, output_file };
+        self.structured_tool_response(id, &output, Vec::new(), false)
+    }
+
+    fn handle_export_character_foundry(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let character_data = match self.character_argument(&id, arguments.get("character_data")) {
+            Ok(character_data) => character_data,
+            Err(response) => return response,
+        };
+
+        let actor = FoundryExporter::export(&character_data);
+        let json = serde_json::to_string_pretty(&actor).unwrap_or_default();
+        let output_file = match self.save_export(arguments, json.as_bytes()) {
+            Ok(output_file) => output_file,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        Logger::info(LOGGER, &format!("Exported {} as a Foundry VTT actor", character_data.character.name));
+        let output = FoundryExportOutput { actor, output_file };
+        self.structured_tool_response(id, &output, Vec::new(), false)
+    }
+
+    /// Write an export to the tool's `output_path`, if one was given
+    fn save_export(&self, arguments: &Value, bytes: &[u8]) -> Result<Option<String>, String> {
+        let Some(requested) = arguments.get("output_path").and_then(|p| p.as_str()) else {
+            return Ok(None);
+        };
+        let overwrite = arguments
+            .get("overwrite")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        self.config
+            .resolve_output(Some(requested), requested)
+            .map_err(|e| e.to_string())
+            .and_then(|path| SheetWriter::write(&path, bytes, overwrite).map_err(|e| e.to_string()))
+            .map(|path| Some(path.display().to_string()))
+            .inspect_err(|e| Logger::warning(LOGGER, e))
+    }
+
+    fn handle_import_character_markdown(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let requested = match arguments.get("path").and_then(|p| p.as_str()) {
+            Some(path) => path,
+            None => return self.error_response(id, -32602, "Missing path", None),
+        };
+        let markdown = match self.read_input(requested) {
+            Ok(markdown) => markdown,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        let output = match MarkdownImporter::parse(&markdown) {
+            Ok(import) => {
+                Logger::info(LOGGER, &format!("Imported {} from {}", import.character_data.character.name, requested));
+                CharacterImportOutput {
+                    character_data: Some(import.character_data),
+                    ignored: import.ignored,
+                    errors: Vec::new(),
+                }
+            }
+            Err(errors) => {
+                Logger::warning(LOGGER, &format!("{} has {} Markdown error(s)", requested, errors.len()));
+                CharacterImportOutput {
+                    character_data: None,
+                    ignored: Vec::new(),
+                    errors: errors.iter().map(|e| e.to_string()).collect(),
+                }
+            }
+        };
+        let is_error = !output.errors.is_empty();
+        self.structured_tool_response(id, &output, Vec::new(), is_error)
+    }
+
+    fn handle_export_character_fight_club(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let character_data = match self.character_argument(&id, arguments.get("character_data")) {
+            Ok(character_data) => character_data,
+            Err(response) => return response,
+        };
+
+        let xml = FightClubExporter::export(&character_data);
+        let output_file = match self.save_export(arguments, xml.as_bytes()) {
+            Ok(output_file) => output_file,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        Logger::info(LOGGER, &format!("Exported {} as Fight Club 5 XML", character_data.character.name));
+        let output = FightClubExportOutput { xml, output_file };
+        self.structured_tool_response(id, &output, Vec::new(), false)
+    }
+
+    fn handle_import_character_fight_club(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let requested = match arguments.get("path").and_then(|p| p.as_str()) {
+            Some(path) => path,
+            None => return self.error_response(id, -32602, "Missing path", None),
+        };
+        let xml = match self.read_input(requested) {
+            Ok(xml) => xml,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        let output = match FightClubExporter::import(&xml) {
+            Ok(character_data) => {
+                Logger::info(LOGGER, &format!("Imported {} from {}", character_data.character.name, requested));
+                CharacterImportOutput {
+                    character_data: Some(character_data),
+                    ignored: Vec::new(),
+                    errors: Vec::new(),
+                }
+            }
+            Err(errors) => {
+                Logger::warning(LOGGER, &format!("{} has {} Fight Club 5 XML error(s)", requested, errors.len()));
+                CharacterImportOutput {
+                    character_data: None,
+                    ignored: Vec::new(),
+                    errors: errors.iter().map(|e| e.to_string()).collect(),
+                }
+            }
+        };
+        let is_error = !output.errors.is_empty();
+        self.structured_tool_response(id, &output, Vec::new(), is_error)
+    }
+
+    fn handle_import_character_dndbeyond(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let requested = match arguments.get("path").and_then(|p| p.as_str()) {
+            Some(path) => path,
+            None => return self.error_response(id, -32602, "Missing path", None),
+        };
+        let text = match self.read_input(requested) {
+            Ok(text) => text,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+        let json: Value = match serde_json::from_str(&text) {
+            Ok(json) => json,
+            Err(e) => return self.tool_text_response(id, format!("Error: {} is not JSON: {}", requested, e), true),
+        };
+
+        let output = match DndBeyondImporter::import(&json) {
+            Ok(import) => {
+                Logger::info(
+                    LOGGER,
+                    &format!("Imported {} from {} ({} unmapped)", import.character_data.character.name, requested, import.unmapped.len()),
+                );
+                CharacterImportOutput {
+                    character_data: Some(import.character_data),
+                    ignored: import.unmapped,
+                    errors: Vec::new(),
+                }
+            }
+            Err(errors) => {
+                Logger::warning(LOGGER, &format!("{} is not a usable D&D Beyond character", requested));
+                CharacterImportOutput {
+                    character_data: None,
+                    ignored: Vec::new(),
+                    errors: errors.iter().map(|e| e.to_string()).collect(),
+                }
+            }
+        };
+        let is_error = !output.errors.is_empty();
+        self.structured_tool_response(id, &output, Vec::new(), is_error)
+    }
+
+    fn handle_save_character(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let character_data = match self.character_argument(&id, arguments.get("character_data")) {
+            Ok(character_data) => character_data,
+            Err(response) => return response,
+        };
+
+        let requested_id = arguments.get("id").and_then(|i| i.as_str());
+        let saved = match self.store.save(requested_id, &character_data) {
+            Ok(saved) => saved,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        let changes = saved
+            .previous
+            .map(|previous| CharacterDiff::between(&previous, &character_data))
+            .unwrap_or_default();
+        let output = SaveCharacterOutput { id: saved.id, version: saved.version, changes };
+        self.structured_tool_response(id, &output, Vec::new(), false)
+    }
+
+    fn handle_load_character(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Some(character_id) = arguments.get("id").and_then(|i| i.as_str()) else {
+            return self.error_response(id, -32602, "Missing id", None);
+        };
+        let Ok(version) = Self::number_argument(arguments, "version") else {
+            return self.error_response(id, -32602, "Invalid version", None);
+        };
+
+        match self.store.load(character_id, version) {
+            Ok((character_data, version)) => {
+                let output = LoadCharacterOutput { id: character_id.to_string(), version, character_data };
+                self.structured_tool_response(id, &output, Vec::new(), false)
+            }
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_list_characters(&self, id: Option<Value>) -> Value {
+        match self.store.list() {
+            Ok(characters) => {
+                let output = CharacterListOutput {
+                    store_dir: self.store.root().display().to_string(),
+                    characters,
+                };
+                self.structured_tool_response(id, &output, Vec::new(), false)
+            }
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_delete_character(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Some(character_id) = arguments.get("id").and_then(|i| i.as_str()) else {
+            return self.error_response(id, -32602, "Missing id", None);
+        };
+
+        match self.store.delete(character_id) {
+            Ok(deleted_snapshots) => {
+                let output = DeleteCharacterOutput { id: character_id.to_string(), deleted_snapshots };
+                self.structured_tool_response(id, &output, Vec::new(), false)
+            }
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_character_diff(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Some(character_id) = arguments.get("id").and_then(|i| i.as_str()) else {
+            return self.error_response(id, -32602, "Missing id", None);
+        };
+        let (Ok(from), Ok(to)) = (Self::number_argument(arguments, "from"), Self::number_argument(arguments, "to")) else {
+            return self.error_response(id, -32602, "Invalid version", None);
+        };
+
+        let diff = self.store.load(character_id, to).and_then(|(after, to)| {
+            let from = from.unwrap_or(to.saturating_sub(1));
+            let (before, from) = match from {
+                0 => (after.clone(), to),
+                from => self.store.load(character_id, Some(from))?,
+            };
+            Ok(CharacterDiffOutput {
+                id: character_id.to_string(),
+                changes: CharacterDiff::between(&before, &after),
+                from,
+                to,
+            })
+        });
+        match diff {
+            Ok(output) => self.structured_tool_response(id, &output, Vec::new(), false),
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_add_party_member(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let text = |key: &str| arguments.get(key).and_then(|v| v.as_str()).filter(|v| !v.trim().is_empty());
+        let (Some(party_name), Some(player), Some(character_id)) = (text("party"), text("player"), text("character_id")) else {
+            return self.error_response(id, -32602, "Missing party, player or character_id", None);
+        };
+
+        // Locked, so a session applied meanwhile can't lose its XP and treasure to this save
+        let _lock = self.store.lock();
+        let added = self.store.load(character_id, None).and_then(|_| {
+            let mut party = match self.store.load_party(party_name) {
+                Ok(party) => party,
+                Err(StoreError::PartyNotFound(_)) => Party::new(party_name),
+                Err(e) => return Err(e),
+            };
+            party.add_member(player, character_id);
+            Ok(PartyOutput { id: self.store.save_party(&party)?, party })
+        });
+        match added {
+            Ok(output) => self.structured_tool_response(id, &output, Vec::new(), false),
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_remove_party_member(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let text = |key: &str| arguments.get(key).and_then(|v| v.as_str());
+        let (Some(party_name), Some(player)) = (text("party"), text("player")) else {
+            return self.error_response(id, -32602, "Missing party or player", None);
+        };
+
+        let _lock = self.store.lock();
+        let mut party = match self.store.load_party(party_name) {
+            Ok(party) => party,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+        if party.remove_member(player).is_none() {
+            return self.tool_text_response(id, format!("Error: {} is not in {}", player, party.name), true);
+        }
+        match self.store.save_party(&party) {
+            Ok(party_id) => self.structured_tool_response(id, &PartyOutput { id: party_id, party }, Vec::new(), false),
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_get_party(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Some(party_name) = arguments.get("party").and_then(|v| v.as_str()) else {
+            return self.error_response(id, -32602, "Missing party", None);
+        };
+
+        match self.store.load_party(party_name) {
+            Ok(party) => {
+                let output = PartyOutput { id: CharacterStore::id_for(party_name), party };
+                self.structured_tool_response(id, &output, Vec::new(), false)
+            }
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_find_party_character(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Some(player) = arguments.get("player").and_then(|v| v.as_str()) else {
+            return self.error_response(id, -32602, "Missing player", None);
+        };
+
+        let parties = match arguments.get("party").and_then(|v| v.as_str()) {
+            Some(party_name) => self
+                .store
+                .load_party(party_name)
+                .map(|party| vec![(CharacterStore::id_for(party_name), party)]),
+            None => self.store.parties(),
+        };
+        let found = parties.map(|parties| {
+            parties.into_iter().find_map(|(party_id, party)| {
+                let (player, character_id) = party.member(player)?;
+                Some((party_id, player.to_string(), character_id.to_string()))
+            })
+        });
+
+        let output = match found {
+            Ok(Some((party, player, character_id))) => self
+                .store
+                .load(&character_id, None)
+                .map(|(character_data, _)| PartyCharacterOutput { party, player, character_id, character_data }),
+            Ok(None) => return self.tool_text_response(id, format!("Error: {} is not in any party", player), true),
+            Err(e) => Err(e),
+        };
+        match output {
+            Ok(output) => self.structured_tool_response(id, &output, Vec::new(), false),
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    fn handle_fill_party_sheets(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Some(party_name) = arguments.get("party").and_then(|v| v.as_str()) else {
+            return self.error_response(id, -32602, "Missing party", None);
+        };
+        let zip = match arguments.get("format").and_then(|v| v.as_str()).unwrap_or("pdf") {
+            "pdf" => false,
+            "zip" => true,
+            _ => return self.error_response(id, -32602, "Invalid format; use \"pdf\" or \"zip\"", None),
+        };
+        let allow_violations = arguments
+            .get("allow_rule_violations")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(self.config.allow_rule_violations);
+        let Ok(advancement_mode) = Self::advancement_mode_argument(arguments) else {
+            return self.error_response(id, -32602, "Invalid advancement_mode; use \"xp\" or \"milestone\"", None);
+        };
+
+        let party = match self.store.load_party(party_name) {
+            Ok(party) if party.members.is_empty() => {
+                return self.tool_text_response(id, format!("Error: {} has no members", party.name), true)
+            }
+            Ok(party) => party,
+            Err(e) => return self.tool_text_response(id, format!("Error: {}", e), true),
+        };
+
+        // The combined PDF is flattened: every sheet has the same field names
+        let filler = PdfFiller::new(allow_violations)
+            .with_flatten(!zip)
+            .with_image_roots(&self.config)
+            .with_advancement_mode(advancement_mode);
+        let mut members = Vec::new();
+        let mut sheets = Vec::new();
+        for (player, character_id) in &party.members {
+            let character_data = match self.store.load(character_id, None) {
+                Ok((character_data, _)) => character_data,
+                Err(e) => return self.tool_text_response(id, format!("Error: {} ({}): {}", player, character_id, e), true),
+            };
+            let validation_errors = match filler.render_character_sheet(&character_data, &self.template) {
+                Ok((bytes, warnings)) => {
+                    sheets.push((format!("{}.pdf", character_id), bytes));
+                    ValidationIssue::from_errors(&warnings, allow_violations)
+                }
+                Err(PdfError::ValidationFailed(errors)) => ValidationIssue::from_errors(&errors, false),
+                Err(e) => {
+                    Logger::error(LOGGER, &format!("PDF processing failed for {}: {}", character_id, e));
+                    return self.tool_text_response(id, format!("Error: PDF processing failed for {} - {}", player, e), true);
+                }
+            };
+            members.push(PartySheetResult {
+                player: player.clone(),
+                character_id: character_id.clone(),
+                validation_errors,
+            });
+        }
+
+        if sheets.len() < members.len() {
+            let failed = members.len() - sheets.len();
+            Logger::warning(LOGGER, &format!("{} party sheet(s) failed validation", failed));
+            let output = FillPartyOutput {
+                success: false,
+                output_file: None,
+                message: format!(
+                    "{} character(s) violate D&D 5e rules; fix them or set allow_rule_violations",
+                    failed
+                ),
+                members,
+                file_size: None,
+            };
+            return self.structured_tool_response(id, &output, Vec::new(), true);
+        }
+
+        let bundled = match zip {
+            true => PartySheets::zip(&sheets),
+            false => PartySheets::combine(&sheets.into_iter().map(|(_, bytes)| bytes).collect::<Vec<_>>()),
+        };
+        let default_name = format!("{}.{}", CharacterStore::id_for(&party.name), if zip { "zip" } else { "pdf" });
+        let overwrite = arguments.get("overwrite").and_then(|v| v.as_bool()).unwrap_or(false);
+        let written = bundled.map_err(|e| e.to_string()).and_then(|bytes| {
+            let requested = arguments.get("output_path").and_then(|p| p.as_str());
+            let path = self.config.resolve_output(requested, &default_name).map_err(|e| e.to_string())?;
+            SheetWriter::write(&path, &bytes, overwrite).map_err(|e| e.to_string())
+        });
+        let output_file = match written {
+            Ok(path) => path,
+            Err(e) => {
+                Logger::warning(LOGGER, &e);
+                return self.tool_text_response(id, format!("Error: {}", e), true);
+            }
+        };
+
+        Logger::info(LOGGER, &format!("Filled {} sheet(s) for {} into {}", members.len(), party.name, output_file.display()));
+        let output = FillPartyOutput {
+            success: true,
+            message: format!("{} sheet(s) for {} created at: {}", members.len(), party.name, output_file.display()),
+            file_size: fs::metadata(&output_file).ok().map(|m| m.len()),
+            output_file: Some(output_file.display().to_string()),
+            members,
+        };
+        self.structured_tool_response(id, &output, Vec::new(), false)
+    }
+
+    fn handle_record_session(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Ok(number) = Self::number_argument(arguments, "number") else {
+            return self.error_response(id, -32602, "Invalid number", None);
+        };
+        let mut record = arguments.clone();
+        // The real number is chosen once the store is locked
+        record["number"] = json!(number.unwrap_or(0));
+        if record.get("date").is_none_or(Value::is_null) {
+            record["date"] = json!(chrono::Local::now().format("%Y-%m-%d").to_string());
+        }
+        record["applied"] = json!(false);
+        let mut session = match serde_json::from_value::<Session>(record) {
+            Ok(session) => session,
+            Err(e) => {
+                return self.error_response(id, -32602, "Invalid session", Some(json!({"parse_error": e.to_string()})))
+            }
+        };
+
+        if let Err(e) = SessionLog::record(&self.store, &mut session, number) {
+            return self.tool_text_response(id, format!("Error: {}", e), true);
+        }
+
+        let apply = arguments.get("apply").and_then(|v| v.as_bool()).unwrap_or(false);
+        if apply {
+            return self.handle_apply_session(id, &json!({"number": session.number}));
+        }
+        let output = SessionOutput { session, awards: Vec::new(), party: None };
+        self.structured_tool_response(id, &output, Vec::new(), false)
+    }
+
+    fn handle_apply_session(&self, id: Option<Value>, arguments: &Value) -> Value {
+        let Ok(Some(number)) = Self::number_argument(arguments, "number") else {
+            return self.error_response(id, -32602, "Missing or invalid number", None);
+        };
+
+        let applied = SessionLog::apply(&self.store, number)
+            .and_then(|(awards, party)| Ok(SessionOutput { session: self.store.load_session(number)?, awards, party }));
+        match applied {
+            Ok(output) => {
+                let ready: Vec<&str> = output
+                    .awards
+                    .iter()
+                    .filter(|award| award.ready_to_level_up)
+                    .map(|award| award.name.as_str())
+                    .collect();
+                Logger::info(LOGGER, &format!("Applied session {}; ready to level up: {:?}", number, ready));
+                self.structured_tool_response(id, &output, Vec::new(), false)
+            }
+            Err(e) => self.tool_text_response(id, format!("Error: {}", e), true),
+        }
+    }
+
+    /// A snapshot or session number argument; `Err` when present but not a positive integer
+    fn number_argument(arguments: &Value, key: &str) -> Result<Option<u32>, ()> {
+        match arguments.get(key) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .filter(|version| *version > 0)
+                .map(Some)
+                .ok_or(()),
+        }
+    }
+
+    /// Input schema of an `output_path` argument, described as `description`
+    fn output_path_schema(&self, description: &str) -> Value {
+        json!({
+            "type": "string",
+            "description": format!("{}; relative paths are under {}", description, self.config.output_dir().display())
+        })
+    }
+
+    /// Input schema of the `overwrite` argument that goes with `output_path`
+    fn overwrite_schema(extension: &str) -> Value {
+        json!({
+            "type": "boolean",
+            "default": false,
+            "description": format!(
+                "Replace an existing file at output_path; otherwise it is saved as \"name (2).{}\" and so on",
+                extension
+            )
+        })
+    }
+
+    /// A `character_data` argument; `Err` is the error response when it is
+    /// missing or doesn't match the schema
+    fn character_argument(&self, id: &Option<Value>, data: Option<&Value>) -> Result<CharacterData, Value> {
+        let Some(data) = data else {
+            Logger::warning(LOGGER, "Missing character_data parameter");
+            return Err(self.error_response(id.clone(), -32602, "Missing character_data", None));
+        };
+        serde_json::from_value(data.clone()).map_err(|e| {
+            Logger::warning(LOGGER, &format!("Character data parse error: {}", e));
+            self.error_response(id.clone(), -32602, "Invalid character data", Some(json!({"parse_error": e.to_string()})))
+        })
+    }
+
+    /// The `advancement_mode` argument; `Err` when present but not "xp" or "milestone"
+    fn advancement_mode_argument(arguments: &Value) -> Result<Option<AdvancementMode>, ()> {
+        match arguments.get("advancement_mode") {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => serde_json::from_value(value.clone()).map(Some).map_err(|_| ()),
+        }
+    }
+
+    /// Read a file the tools may import from, under the same roots sheets are written to
+    fn read_input(&self, requested: &str) -> Result<String, String> {
+        self.config
+            .resolve_input(requested)
+            .map_err(|e| e.to_string())
+            .and_then(|path| {
+                std::fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))
+            })
+            .inspect_err(|e| Logger::warning(LOGGER, e))
+    }
+
+    /// Tool result carrying `structuredContent`, with the same JSON serialized
+    /// as text for clients that don't read structured results
+    fn structured_tool_response<T: Serialize>(
+        &self,
+        id: Option<Value>,
+        output: &T,
+        extra_content: Vec<Value>,
+        is_error: bool,
+    ) -> Value {
+        let structured = serde_json::to_value(output).unwrap_or(Value::Null);
+        let mut content = vec![json!({
+            "type": "text",
+            "text": serde_json::to_string_pretty(&structured).unwrap_or_default()
+        })];
+        content.extend(extra_content);
+
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "content": content,
+                "structuredContent": structured,
+                "isError": is_error
+            }
+        })
+    }
+
+    fn tool_text_response(&self, id: Option<Value>, text: String, is_error: bool) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "content": [
+                    {
+                        "type": "text",
+                        "text": text
+                    }
+                ],
+                "isError": is_error
+            }
+        })
+    }
+
+    fn error_response(
+        &self,
+        id: Option<Value>,
+        code: i32,
+        message: &str,
+        data: Option<Value>,
+    ) -> Value {
+        let mut error = json!({
+            "code": code,
+            "message": message
+        });
+
+        if let Some(data) = data {
+            error["data"] = data;
+        }
+
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": error
+        });
+
+        Logger::debug(LOGGER, &format!("Sending error response: {}", response));
         response
     }
 }
@@ -22,14 +22,39 @@ impl NarrativeHandler {
             fields.push(("flaws".to_string(), flaws.clone()));
         }
 
+        if let Some(backstory) = &narrative.backstory {
+            fields.push(("backstory".to_string(), backstory.clone()));
+        }
+
+        if let Some(allies) = &narrative.allies {
+            fields.push(("allies".to_string(), allies.clone()));
+        }
+
+        if let Some(faction_name) = &narrative.faction_name {
+            fields.push(("faction_name".to_string(), faction_name.clone()));
+        }
+
         fields
     }
 
+    /// Maximum characters that fit in a narrative field; the page 2 backstory
+    /// box is much larger than the page 1 personality boxes
+    pub fn max_length_for(field_key: &str) -> usize {
+        match field_key {
+            "backstory" => 2500,
+            "allies" => 1200,
+            "faction_name" => 60,
+            _ => 500,
+        }
+    }
+
     pub fn truncate_if_needed(text: &str, max_length: usize) -> String {
-        if text.len() <= max_length {
+        if text.chars().count() <= max_length {
             text.to_string()
         } else {
-            format!("{}...", &text[..max_length.saturating_sub(3)])
+            // Count characters rather than bytes so multi-byte text can't split mid-character
+            let kept: String = text.chars().take(max_length.saturating_sub(3)).collect();
+            format!("{}...", kept)
         }
     }
 }
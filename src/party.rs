@@ -0,0 +1,57 @@
+use crate::character_model::Currency;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A group of players adventuring together, saved in the character store
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Party {
+    pub name: String,
+    /// Player name -> id of their character in the store
+    pub members: BTreeMap<String, String>,
+    /// Coins held by the party rather than by one character
+    pub currency: Currency,
+    /// Shared items and valuables, e.g. "Deed to Tresendar Manor"
+    pub treasure: Vec<String>,
+    /// Experience the party has earned together
+    pub experience_points: u32,
+}
+
+impl Party {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Give `player` the character `character_id`, replacing their previous
+    /// one (returned); player names match regardless of case
+    pub fn add_member(&mut self, player: &str, character_id: &str) -> Option<String> {
+        let previous = self.remove_member(player);
+        self.members.insert(player.trim().to_string(), character_id.to_string());
+        previous
+    }
+
+    /// Remove `player`, returning the id of the character they played
+    pub fn remove_member(&mut self, player: &str) -> Option<String> {
+        let key = self.member(player)?.0.to_string();
+        self.members.remove(&key)
+    }
+
+    /// Id of the character `player` plays in this party
+    pub fn character_for(&self, player: &str) -> Option<&str> {
+        self.member(player).map(|(_, character_id)| character_id)
+    }
+
+    /// The player's name as stored and their character id, matching the
+    /// name regardless of case
+    pub fn member(&self, player: &str) -> Option<(&str, &str)> {
+        let player = player.trim().to_lowercase();
+        self.members
+            .iter()
+            .find(|(name, _)| name.to_lowercase() == player)
+            .map(|(name, character_id)| (name.as_str(), character_id.as_str()))
+    }
+}
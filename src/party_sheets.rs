@@ -0,0 +1,99 @@
+use crate::errors::PdfError;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Page attributes a page may inherit from its page tree
+const INHERITED: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Bundles the filled sheets of a whole party into one file
+pub struct PartySheets;
+
+impl PartySheets {
+    /// One PDF holding every sheet's pages in order. The sheets must be
+    /// flattened: they share field names, so their forms can't be merged
+    pub fn combine(sheets: &[Vec<u8>]) -> Result<Vec<u8>, PdfError> {
+        let mut combined = Document::with_version("1.5");
+        let pages_id = combined.new_object_id();
+        let mut kids = Vec::new();
+
+        for bytes in sheets {
+            let mut doc = Document::load_mem(bytes)
+                .map_err(|e| PdfError::ParseError(format!("Failed to load filled sheet: {}", e)))?;
+            doc.renumber_objects_with(combined.max_id + 1);
+            combined.max_id = doc.max_id;
+
+            for (_, page_id) in doc.get_pages() {
+                let mut page = Self::page_with_inherited(&doc, page_id)?;
+                page.set("Parent", pages_id);
+                // The structure tree stays behind with the sheet's catalog
+                page.remove(b"StructParents");
+                doc.objects.insert(page_id, Object::Dictionary(page));
+                kids.push(Object::Reference(page_id));
+            }
+            combined.objects.extend(doc.objects);
+        }
+
+        let mut pages = Dictionary::new();
+        pages.set("Type", "Pages");
+        pages.set("Count", kids.len() as i64);
+        pages.set("Kids", kids);
+        combined.objects.insert(pages_id, Object::Dictionary(pages));
+        let mut catalog = Dictionary::new();
+        catalog.set("Type", "Catalog");
+        catalog.set("Pages", pages_id);
+        let catalog_id = combined.add_object(catalog);
+        combined.trailer.set("Root", catalog_id);
+        // Drops each sheet's old catalog, page tree and structure tree
+        combined.prune_objects();
+
+        let mut bytes = Vec::new();
+        combined
+            .save_to(&mut bytes)
+            .map_err(|e| PdfError::WriteError(format!("Failed to save combined PDF: {}", e)))?;
+        Ok(bytes)
+    }
+
+    /// A zip archive with one `(file name, PDF)` entry per sheet
+    pub fn zip(sheets: &[(String, Vec<u8>)]) -> Result<Vec<u8>, PdfError> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        for (name, bytes) in sheets {
+            zip.start_file(name.as_str(), options)
+                .and_then(|_| zip.write_all(bytes).map_err(Into::into))
+                .map_err(|e| PdfError::WriteError(format!("Failed to add {} to the zip: {}", name, e)))?;
+        }
+        let cursor = zip
+            .finish()
+            .map_err(|e| PdfError::WriteError(format!("Failed to finish the zip: {}", e)))?;
+        Ok(cursor.into_inner())
+    }
+
+    /// The page dictionary with anything it inherits from its parents copied in,
+    /// since it's about to move to a new page tree
+    fn page_with_inherited(doc: &Document, page_id: ObjectId) -> Result<Dictionary, PdfError> {
+        let mut page = doc
+            .get_dictionary(page_id)
+            .map_err(|e| PdfError::ParseError(format!("Invalid page {:?}: {}", page_id, e)))?
+            .clone();
+        let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+        // Bounded, in case a broken page tree loops back on itself
+        for _ in 0..MAX_TREE_DEPTH {
+            let Some(node) = parent.and_then(|id| doc.get_dictionary(id).ok()) else {
+                break;
+            };
+            for key in INHERITED {
+                if page.get(key).is_err() {
+                    if let Ok(value) = node.get(key) {
+                        page.set(key, value.clone());
+                    }
+                }
+            }
+            parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+        }
+        Ok(page)
+    }
+}
@@ -1,15 +1,28 @@
-use crate::character_model::CharacterData;
+use crate::character_model::{AdvancementMode, CharacterData};
+use crate::class_catalog::ClassCatalog;
+use crate::config::ServerConfig;
+use crate::derived_stats::DerivedStats;
 use crate::dnd_validator::DndValidator;
 use crate::errors::{PdfError, ValidationError};
 use crate::field_mapper::FieldMapper;
+use crate::image_embedder::ImageEmbedder;
 use crate::narrative_handler::NarrativeHandler;
+use crate::proficiency_handler::ProficiencyHandler;
+use crate::sheet_flattener::SheetFlattener;
+use crate::sheet_writer::SheetWriter;
 use crate::spell_system::SpellSystem;
 use lopdf::{Document, Object};
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
 pub struct PdfFiller {
     field_mapper: FieldMapper,
     validator: DndValidator,
+    overwrite: bool,
+    flatten: bool,
+    /// Where image paths may be read from; anywhere when not set
+    image_roots: Option<ServerConfig>,
 }
 
 impl PdfFiller {
@@ -17,49 +30,51 @@ impl PdfFiller {
         Self {
             field_mapper: FieldMapper::new(),
             validator: DndValidator::new(allow_rule_violations),
+            overwrite: false,
+            flatten: false,
+            image_roots: None,
         }
     }
 
+    /// Replace existing output files instead of writing `name (2).pdf`
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Draw the filled fields into the pages and remove the form
+    pub fn with_flatten(mut self, flatten: bool) -> Self {
+        self.flatten = flatten;
+        self
+    }
+
+    /// Only read image paths that `config` lets the tools read
+    pub fn with_image_roots(mut self, config: &ServerConfig) -> Self {
+        self.image_roots = Some(config.clone());
+        self
+    }
+
+    /// Level every character by `mode` instead of their own `advancement_mode`
+    pub fn with_advancement_mode(mut self, mode: Option<AdvancementMode>) -> Self {
+        self.validator = self.validator.with_advancement_mode(mode);
+        self
+    }
+
     pub fn fill_character_sheet_from_bytes(
         &self,
         character_data: &CharacterData,
         template_bytes: &[u8],
         output_path: &str,
     ) -> Result<FillResult, PdfError> {
-        // Validate character data
-        let validation_result = self.validator.validate(character_data);
-        let validation_errors = match validation_result {
-            Ok(warnings) => warnings,
-            Err(errors) => {
-                return Err(PdfError::WriteError(format!(
-                    "Validation failed: {:?}",
-                    errors
-                )))
-            }
-        };
+        let (bytes, validation_errors) = self.render_character_sheet(character_data, template_bytes)?;
 
-        // Load the PDF template from bytes
-        let mut doc = Document::load_mem(template_bytes)
-            .map_err(|e| PdfError::WriteError(format!("Failed to load PDF from bytes: {}", e)))?;
-
-        // Fill the PDF form fields
-        self.fill_pdf_fields(&mut doc, character_data)?;
-
-        // Save the filled PDF with explicit sync
-        doc.save(output_path)
-            .map_err(|e| PdfError::WriteError(format!("Failed to save PDF: {}", e)))?;
-
-        // Force filesystem sync to ensure file is written
-        use std::fs::File;
-        use std::io::Write;
-        if let Ok(mut file) = File::options().write(true).open(output_path) {
-            let _ = file.flush();
-            let _ = file.sync_all();
-        }
+        // Write atomically, possibly under a suffixed name
+        let output_file = SheetWriter::write(Path::new(output_path), &bytes, self.overwrite)
+            .map_err(PdfError::Output)?;
 
         Ok(FillResult {
             success: true,
-            output_file: output_path.to_string(),
+            output_file: output_file.display().to_string(),
             validation_errors,
             calculated_fields: self.calculate_derived_values(character_data),
         })
@@ -71,43 +86,38 @@ impl PdfFiller {
         template_path: &str,
         output_path: &str,
     ) -> Result<FillResult, PdfError> {
-        // Validate character data
-        let validation_result = self.validator.validate(character_data);
-        let validation_errors = match validation_result {
-            Ok(warnings) => warnings,
-            Err(errors) => {
-                return Err(PdfError::WriteError(format!(
-                    "Validation failed: {:?}",
-                    errors
-                )))
-            }
-        };
-
-        // Load the PDF template
-        let mut doc = Document::load(template_path)
+        let template_bytes = fs::read(template_path)
             .map_err(|e| PdfError::WriteError(format!("Failed to load PDF: {}", e)))?;
+        self.fill_character_sheet_from_bytes(character_data, &template_bytes, output_path)
+    }
 
-        // Fill the PDF form fields
+    /// Validate and fill a sheet in memory, without writing it anywhere;
+    /// returns the PDF and any rule warnings
+    pub fn render_character_sheet(
+        &self,
+        character_data: &CharacterData,
+        template_bytes: &[u8],
+    ) -> Result<(Vec<u8>, Vec<ValidationError>), PdfError> {
+        let validation_errors = self
+            .validator
+            .validate(character_data)
+            .map_err(PdfError::ValidationFailed)?;
+
+        let mut doc = Document::load_mem(template_bytes)
+            .map_err(|e| PdfError::WriteError(format!("Failed to load PDF from bytes: {}", e)))?;
         self.fill_pdf_fields(&mut doc, character_data)?;
 
-        // Save the filled PDF with explicit sync
-        doc.save(output_path)
-            .map_err(|e| PdfError::WriteError(format!("Failed to save PDF: {}", e)))?;
+        Ok((self.to_bytes(&mut doc)?, validation_errors))
+    }
 
-        // Force filesystem sync to ensure file is written
-        use std::fs::File;
-        use std::io::Write;
-        if let Ok(mut file) = File::options().write(true).open(output_path) {
-            let _ = file.flush();
-            let _ = file.sync_all();
+    fn to_bytes(&self, doc: &mut Document) -> Result<Vec<u8>, PdfError> {
+        if self.flatten {
+            SheetFlattener::flatten(doc)?;
         }
-
-        Ok(FillResult {
-            success: true,
-            output_file: output_path.to_string(),
-            validation_errors,
-            calculated_fields: self.calculate_derived_values(character_data),
-        })
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes)
+            .map_err(|e| PdfError::WriteError(format!("Failed to save PDF: {}", e)))?;
+        Ok(bytes)
     }
 
     fn fill_pdf_fields(
@@ -122,7 +132,7 @@ impl PdfFiller {
         for (object_id, object) in doc.objects.clone() {
             if let Object::Dictionary(dict) = object {
                 if let Ok(Object::String(field_name_bytes, _)) = dict.get(b"T") {
-                    let field_name = String::from_utf8_lossy(&field_name_bytes);
+                    let field_name = String::from_utf8_lossy(field_name_bytes);
                     
                     if let Some(value) = field_values.get(field_name.as_ref()) {
                         let mut new_dict = dict.clone();
@@ -135,6 +145,22 @@ impl PdfFiller {
         
         // Mark checkboxes
         self.mark_checkboxes(doc, &checkbox_fields)?;
+
+        // Embed portrait and faction symbol into the page 2 image buttons
+        if let Some(images) = &character_data.images {
+            let buttons = [
+                ("portrait_image", &images.portrait),
+                ("faction_symbol_image", &images.faction_symbol),
+            ];
+            for (field_key, source) in buttons {
+                if let (Some(source), Some(field_name)) =
+                    (source, self.field_mapper.get_pdf_field_name(field_key))
+                {
+                    let image_bytes = ImageEmbedder::load_image_bytes(source, self.image_roots.as_ref())?;
+                    ImageEmbedder::embed_in_button(doc, field_name, &image_bytes)?;
+                }
+            }
+        }
         
         Ok(())
     }
@@ -150,7 +176,7 @@ impl PdfFiller {
             if let Object::Reference(field_ref) = field_obj {
                 if let Ok(Object::Dictionary(field_dict)) = doc.get_object(*field_ref) {
                     if let Ok(Object::String(field_name_bytes, _)) = field_dict.get(b"T") {
-                        let field_name = String::from_utf8_lossy(&field_name_bytes);
+                        let field_name = String::from_utf8_lossy(field_name_bytes);
                         
                         if let Some(value) = field_values.get(field_name.as_ref()) {
                             let mut new_dict = field_dict.clone();
@@ -174,7 +200,7 @@ impl PdfFiller {
         for (object_id, object) in doc.objects.clone() {
             if let Object::Dictionary(dict) = object {
                 if let Ok(Object::String(field_name_bytes, _)) = dict.get(b"T") {
-                    let field_name = String::from_utf8_lossy(&field_name_bytes);
+                    let field_name = String::from_utf8_lossy(field_name_bytes);
                     
                     if let Some(&should_mark) = checkbox_fields.get(field_name.as_ref()) {
                         if should_mark {
@@ -194,26 +220,24 @@ impl PdfFiller {
         println!("=== SPELL FIELD NAMES IN PDF ===");
 
         for (_page_id, page_obj) in doc.get_pages() {
-            if let Ok(page) = doc.get_object(page_obj) {
-                if let Object::Dictionary(page_dict) = page {
-                    if let Ok(Object::Reference(annots_ref)) = page_dict.get(b"Annots") {
-                        if let Ok(Object::Array(annots)) = doc.get_object(*annots_ref) {
-                            for annot_ref in annots {
-                                if let Object::Reference(ref_id) = annot_ref {
-                                    if let Ok(Object::Dictionary(field_dict)) =
-                                        doc.get_object(*ref_id)
+            if let Ok(Object::Dictionary(page_dict)) = doc.get_object(page_obj) {
+                if let Ok(Object::Reference(annots_ref)) = page_dict.get(b"Annots") {
+                    if let Ok(Object::Array(annots)) = doc.get_object(*annots_ref) {
+                        for annot_ref in annots {
+                            if let Object::Reference(ref_id) = annot_ref {
+                                if let Ok(Object::Dictionary(field_dict)) =
+                                    doc.get_object(*ref_id)
+                                {
+                                    if let Ok(Object::Name(subtype)) =
+                                        field_dict.get(b"Subtype")
                                     {
-                                        if let Ok(Object::Name(subtype)) =
-                                            field_dict.get(b"Subtype")
-                                        {
-                                            if subtype == b"Widget" {
-                                                if let Ok(Object::String(field_name, _)) =
-                                                    field_dict.get(b"T")
-                                                {
-                                                    let name_str =
-                                                        String::from_utf8_lossy(field_name);
-                                                    println!("Field: '{}'", name_str);
-                                                }
+                                        if subtype == b"Widget" {
+                                            if let Ok(Object::String(field_name, _)) =
+                                                field_dict.get(b"T")
+                                            {
+                                                let name_str =
+                                                    String::from_utf8_lossy(field_name);
+                                                println!("Field: '{}'", name_str);
                                             }
                                         }
                                     }
@@ -236,11 +260,15 @@ impl PdfFiller {
         if let Some(field_name) = self.field_mapper.get_pdf_field_name("character_name") {
             fields.insert(field_name.clone(), character.name.clone());
         }
+        if let Some(field_name) = self.field_mapper.get_pdf_field_name("character_name_2") {
+            fields.insert(field_name.clone(), character.name.clone());
+        }
         if let Some(field_name) = self.field_mapper.get_pdf_field_name("class_level") {
-            fields.insert(
-                field_name.clone(),
-                format!("{} {}", character.class, character.level),
-            );
+            let class_level = match &character.subclass {
+                Some(subclass) => format!("{} ({}) {}", character.class, subclass, character.level),
+                None => format!("{} {}", character.class, character.level),
+            };
+            fields.insert(field_name.clone(), class_level);
         }
         if let Some(field_name) = self.field_mapper.get_pdf_field_name("race") {
             fields.insert(field_name.clone(), character.race.clone());
@@ -260,14 +288,36 @@ impl PdfFiller {
                 fields.insert(field_name.clone(), alignment.clone());
             }
         }
-        if let Some(xp) = character.experience_points {
+        // Milestone characters have no XP to track, so the box stays empty
+        let milestone = self.validator.advancement_mode(character_data) == AdvancementMode::Milestone;
+        if let Some(xp) = character.experience_points.filter(|_| !milestone) {
             if let Some(field_name) = self.field_mapper.get_pdf_field_name("experience_points") {
                 fields.insert(field_name.clone(), xp.to_string());
             }
         }
 
-        // Ability scores and modifiers using field mapper
-        let abilities = &character_data.abilities;
+        // Page 2 appearance
+        if let Some(appearance) = &character_data.appearance {
+            let details = [
+                ("age", &appearance.age),
+                ("height", &appearance.height),
+                ("weight", &appearance.weight),
+                ("eyes", &appearance.eyes),
+                ("skin", &appearance.skin),
+                ("hair", &appearance.hair),
+            ];
+            for (field_key, value) in details {
+                if let (Some(value), Some(field_name)) =
+                    (value, self.field_mapper.get_pdf_field_name(field_key))
+                {
+                    fields.insert(field_name.clone(), value.clone());
+                }
+            }
+        }
+
+        // Ability scores and modifiers using field mapper, including level-history improvements
+        let derived = DerivedStats::calculate(character_data);
+        let abilities = &derived.abilities;
 
         if let Some(field_name) = self.field_mapper.get_pdf_field_name("strength") {
             fields.insert(field_name.clone(), abilities.strength.to_string());
@@ -359,9 +409,23 @@ impl PdfFiller {
                     fields.insert(field_name.clone(), speed.to_string());
                 }
             }
-            if let Some(initiative) = combat.initiative {
-                if let Some(field_name) = self.field_mapper.get_pdf_field_name("initiative") {
-                    fields.insert(field_name.clone(), initiative.to_string());
+            if combat.inspiration == Some(true) {
+                if let Some(field_name) = self.field_mapper.get_pdf_field_name("inspiration") {
+                    fields.insert(field_name.clone(), "X".to_string());
+                }
+            }
+        }
+
+        // Initiative and hit point maximum fall back to derived values (Alert, Tough, etc.)
+        let combat = character_data.combat.as_ref();
+        let initiative = combat.and_then(|c| c.initiative).unwrap_or(derived.initiative);
+        if let Some(field_name) = self.field_mapper.get_pdf_field_name("initiative") {
+            fields.insert(field_name.clone(), initiative.to_string());
+        }
+        if combat.and_then(|c| c.hit_point_maximum).is_none() {
+            if let Some(hp) = derived.hit_point_maximum {
+                if let Some(field_name) = self.field_mapper.get_pdf_field_name("hit_point_maximum") {
+                    fields.insert(field_name.clone(), hp.to_string());
                 }
             }
         }
@@ -373,32 +437,36 @@ impl PdfFiller {
         }
 
         // Passive Perception calculation
-        if let Some(proficiencies) = &character_data.proficiencies {
+        if character_data.proficiencies.is_some() {
             // Validate Wisdom score is within D&D 5e range (1-30)
-            if abilities.wisdom >= 1 && abilities.wisdom <= 30 {
-                let is_perception_proficient = proficiencies.skills.contains(&"perception".to_string());
-                let passive_perception = 10 + self.calculate_modifier(abilities.wisdom) + 
-                    if is_perception_proficient { prof_bonus } else { 0 };
+            // Observant and similar feats are already included in the derived value
+            if (1..=30).contains(&abilities.wisdom) {
                 if let Some(field_name) = self.field_mapper.get_pdf_field_name("passive_perception") {
-                    fields.insert(field_name.clone(), passive_perception.to_string());
+                    fields.insert(field_name.clone(), derived.passive_perception.to_string());
                 }
             }
             // If Wisdom is invalid, passive perception field remains empty (graceful handling)
         }
 
+        // Other Proficiencies & Languages
+        if let Some(proficiencies) = &character_data.proficiencies {
+            let merged = ProficiencyHandler::merge(proficiencies);
+            let text = ProficiencyHandler::format_other_proficiencies(&merged);
+            if !text.is_empty() {
+                if let Some(field_name) = self.field_mapper.get_pdf_field_name("other_proficiencies") {
+                    fields.insert(field_name.clone(), text);
+                }
+            }
+        }
+
         // Hit Dice calculation
-        let hit_die_type = match character.class.as_str() {
-            "Barbarian" => "d12",
-            "Fighter" | "Paladin" | "Ranger" => "d10",
-            "Bard" | "Cleric" | "Druid" | "Monk" | "Rogue" | "Warlock" => "d8",
-            "Sorcerer" | "Wizard" => "d6",
-            _ => "d8", // Default fallback
-        };
+        let hit_die = ClassCatalog::get(&character.class).map(|class| class.hit_die).unwrap_or(8);
+        let hit_die_type = format!("d{}", hit_die);
         if let Some(field_name) = self.field_mapper.get_pdf_field_name("hit_dice_total") {
             fields.insert(field_name.clone(), character.level.to_string());
         }
         if let Some(field_name) = self.field_mapper.get_pdf_field_name("hit_dice_type") {
-            fields.insert(field_name.clone(), hit_die_type.to_string());
+            fields.insert(field_name.clone(), hit_die_type);
         }
 
         // Currency fields
@@ -420,14 +488,31 @@ impl PdfFiller {
                     fields.insert(field_name.clone(), currency.pp.to_string());
                 }
             }
+            if let Some(treasure) = &equipment.treasure {
+                if let Some(field_name) = self.field_mapper.get_pdf_field_name("treasure") {
+                    fields.insert(field_name.clone(), treasure.clone());
+                }
+            }
+        }
+
+        // Features & Traits field, led by any active conditions
+        let mut lines = Vec::new();
+        if let Some(combat) = &character_data.combat {
+            let mut conditions: Vec<String> = combat.conditions.clone().unwrap_or_default();
+            if let Some(exhaustion) = combat.exhaustion_level.filter(|level| *level > 0) {
+                conditions.push(format!("Exhaustion (level {})", exhaustion));
+            }
+            if !conditions.is_empty() {
+                lines.push(format!("Conditions: {}", conditions.join(", ")));
+            }
         }
 
-        // Features & Traits field
         if let Some(features_traits) = &character_data.features_traits {
-            let mut lines = Vec::new();
-            
             if let Some(features) = &features_traits.features {
                 if !features.is_empty() {
+                    if !lines.is_empty() {
+                        lines.push("".to_string()); // Empty line separator
+                    }
                     lines.push("Features:".to_string());
                     for feature in features {
                         lines.push(format!("- {}", feature));
@@ -446,15 +531,40 @@ impl PdfFiller {
                     }
                 }
             }
-            
-            if !lines.is_empty() {
-                let features_text = lines.join("\n");
-                if let Some(field_name) = self.field_mapper.get_pdf_field_name("features_traits") {
-                    fields.insert(field_name.clone(), features_text);
+
+            // Page 2 Additional Features & Traits
+            if let Some(additional) = &features_traits.additional {
+                if !additional.is_empty() {
+                    let additional_text = additional
+                        .iter()
+                        .map(|item| format!("- {}", item))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    if let Some(field_name) = self.field_mapper.get_pdf_field_name("additional_features_traits") {
+                        fields.insert(field_name.clone(), additional_text);
+                    }
                 }
             }
         }
 
+        let feat_notes = derived.feat_notes();
+        if !feat_notes.is_empty() {
+            if !lines.is_empty() {
+                lines.push("".to_string()); // Empty line separator
+            }
+            lines.push("Feats:".to_string());
+            for note in feat_notes {
+                lines.push(format!("- {}", note));
+            }
+        }
+
+        if !lines.is_empty() {
+            let features_text = lines.join("\n");
+            if let Some(field_name) = self.field_mapper.get_pdf_field_name("features_traits") {
+                fields.insert(field_name.clone(), features_text);
+            }
+        }
+
         // Spells using field mapper
         if let Some(spells) = &character_data.spells {
             // Spell attack bonus and save DC
@@ -537,8 +647,9 @@ impl PdfFiller {
             let narrative_fields = NarrativeHandler::format_narrative_fields(narrative);
             for (field_key, field_value) in narrative_fields {
                 if let Some(pdf_field_name) = self.field_mapper.get_pdf_field_name(&field_key) {
-                    // Truncate long text to fit PDF fields (max 500 chars)
-                    let truncated_value = NarrativeHandler::truncate_if_needed(&field_value, 500);
+                    // Truncate long text to fit PDF fields
+                    let max_length = NarrativeHandler::max_length_for(&field_key);
+                    let truncated_value = NarrativeHandler::truncate_if_needed(&field_value, max_length);
                     fields.insert(pdf_field_name.clone(), truncated_value);
                 }
             }
@@ -612,6 +723,22 @@ impl PdfFiller {
     pub fn get_checkbox_fields(&self, character_data: &CharacterData) -> HashMap<String, bool> {
         let mut checkboxes = HashMap::new();
 
+        // Death save checkboxes, filled left to right
+        if let Some(combat) = &character_data.combat {
+            let tallies = [
+                ("death_save_success", combat.death_save_successes.unwrap_or(0)),
+                ("death_save_failure", combat.death_save_failures.unwrap_or(0)),
+            ];
+            for (prefix, count) in tallies {
+                for index in 1..=3u8 {
+                    let checkbox_key = format!("{}_{}", prefix, index);
+                    if let Some(checkbox_field) = self.field_mapper.get_pdf_field_name(&checkbox_key) {
+                        checkboxes.insert(checkbox_field.clone(), index <= count);
+                    }
+                }
+            }
+        }
+
         // Saving throw proficiency checkboxes
         if let Some(proficiencies) = &character_data.proficiencies {
             let saves = [
@@ -701,23 +828,9 @@ impl PdfFiller {
         ((ability_score as i16 - 10) / 2) as i8
     }
 
-    /// Calculate ability modifier with validation, returns "ERROR" string for invalid scores
-    fn calculate_modifier_safe(&self, ability_score: u8, ability_name: &str) -> String {
-        if let Err(_) = crate::dnd_validator::DndValidator::validate_ability_score_range(ability_score, ability_name) {
-            "ERROR".to_string()
-        } else {
-            let modifier = self.calculate_modifier(ability_score);
-            if modifier >= 0 {
-                format!("+{}", modifier)
-            } else {
-                modifier.to_string()
-            }
-        }
-    }
-
     /// Calculate saving throw bonus with error handling
     fn calculate_saving_throw_safe(&self, ability_score: u8, level: u8, is_proficient: bool, ability_name: &str) -> String {
-        if let Err(_) = crate::dnd_validator::DndValidator::validate_ability_score_range(ability_score, ability_name) {
+        if crate::dnd_validator::DndValidator::validate_ability_score_range(ability_score, ability_name).is_err() {
             "ERROR".to_string()
         } else {
             let bonus = crate::character_model::calculate_saving_throw_bonus(ability_score, level, is_proficient);
@@ -731,7 +844,7 @@ impl PdfFiller {
 
     /// Calculate skill bonus with error handling
     fn calculate_skill_safe(&self, ability_score: u8, level: u8, is_proficient: bool, ability_name: &str) -> String {
-        if let Err(_) = crate::dnd_validator::DndValidator::validate_ability_score_range(ability_score, ability_name) {
+        if crate::dnd_validator::DndValidator::validate_ability_score_range(ability_score, ability_name).is_err() {
             "ERROR".to_string()
         } else {
             let bonus = crate::character_model::calculate_skill_bonus(ability_score, level, is_proficient);
@@ -743,152 +856,10 @@ impl PdfFiller {
         }
     }
 
-    #[allow(dead_code)]
-    fn generate_character_sheet_text(&self, character_data: &CharacterData) -> String {
-        let mut content = String::new();
-
-        content.push_str("=== D&D 5e CHARACTER SHEET ===\n\n");
-
-        // Basic character info
-        let character = &character_data.character;
-        content.push_str(&format!("Character Name: {}\n", character.name));
-        content.push_str(&format!(
-            "Class & Level: {} {}\n",
-            character.class, character.level
-        ));
-        content.push_str(&format!("Race: {}\n", character.race));
-
-        if let Some(background) = &character.background {
-            content.push_str(&format!("Background: {}\n", background));
-        }
-        if let Some(alignment) = &character.alignment {
-            content.push_str(&format!("Alignment: {}\n", alignment));
-        }
-        if let Some(xp) = character.experience_points {
-            content.push_str(&format!("Experience Points: {}\n", xp));
-        }
-
-        // Ability scores
-        content.push_str("\n=== ABILITY SCORES ===\n");
-        let abilities = &character_data.abilities;
-        content.push_str(&format!(
-            "Strength: {} ({})\n",
-            abilities.strength,
-            abilities.modifier("strength")
-        ));
-        content.push_str(&format!(
-            "Dexterity: {} ({})\n",
-            abilities.dexterity,
-            abilities.modifier("dexterity")
-        ));
-        content.push_str(&format!(
-            "Constitution: {} ({})\n",
-            abilities.constitution,
-            abilities.modifier("constitution")
-        ));
-        content.push_str(&format!(
-            "Intelligence: {} ({})\n",
-            abilities.intelligence,
-            abilities.modifier("intelligence")
-        ));
-        content.push_str(&format!(
-            "Wisdom: {} ({})\n",
-            abilities.wisdom,
-            abilities.modifier("wisdom")
-        ));
-        content.push_str(&format!(
-            "Charisma: {} ({})\n",
-            abilities.charisma,
-            abilities.modifier("charisma")
-        ));
-
-        // Combat stats
-        if let Some(combat) = &character_data.combat {
-            content.push_str("\n=== COMBAT STATS ===\n");
-            if let Some(ac) = combat.armor_class {
-                content.push_str(&format!("Armor Class: {}\n", ac));
-            }
-            if let Some(hp_max) = combat.hit_point_maximum {
-                content.push_str(&format!("Hit Points: {}\n", hp_max));
-            }
-            if let Some(speed) = combat.speed {
-                content.push_str(&format!("Speed: {} ft\n", speed));
-            }
-        }
-
-        // Spells
-        if let Some(spells) = &character_data.spells {
-            content.push_str("\n=== SPELLS ===\n");
-            if let Some(class) = &spells.spellcasting_class {
-                content.push_str(&format!("Spellcasting Class: {}\n", class));
-            }
-            if let Some(ability) = &spells.spellcasting_ability {
-                content.push_str(&format!("Spellcasting Ability: {}\n", ability));
-            }
-
-            let organized_spells = SpellSystem::organize_spells_by_level(spells);
-            for (level, spell_list) in organized_spells {
-                let level_name = if level == 0 {
-                    "Cantrips".to_string()
-                } else {
-                    format!("Level {} Spells", level)
-                };
-                content.push_str(&format!("\n{}:\n", level_name));
-                for spell in spell_list {
-                    let prepared = if spell.prepared { " (Prepared)" } else { "" };
-                    content.push_str(&format!("  - {}{}\n", spell.name, prepared));
-                }
-            }
-        }
-
-        // Narrative
-        if let Some(narrative) = &character_data.narrative {
-            content.push_str("\n=== CHARACTER DETAILS ===\n");
-            if let Some(traits) = &narrative.personality_traits {
-                content.push_str(&format!("Personality Traits: {}\n", traits));
-            }
-            if let Some(ideals) = &narrative.ideals {
-                content.push_str(&format!("Ideals: {}\n", ideals));
-            }
-            if let Some(bonds) = &narrative.bonds {
-                content.push_str(&format!("Bonds: {}\n", bonds));
-            }
-            if let Some(flaws) = &narrative.flaws {
-                content.push_str(&format!("Flaws: {}\n", flaws));
-            }
-        }
-
-        // Equipment
-        if let Some(equipment) = &character_data.equipment {
-            content.push_str("\n=== EQUIPMENT ===\n");
-            if let Some(currency) = &equipment.currency {
-                content.push_str(&format!(
-                    "Currency: {} cp, {} sp, {} ep, {} gp, {} pp\n",
-                    currency.cp, currency.sp, currency.ep, currency.gp, currency.pp
-                ));
-            }
-            if let Some(items) = &equipment.items {
-                content.push_str(&format!("Items: {}\n", items));
-            }
-        }
-
-        // Calculated values
-        content.push_str("\n=== CALCULATED VALUES ===\n");
-        let calculated = self.calculate_derived_values(character_data);
-        for (key, value) in calculated {
-            content.push_str(&format!(
-                "{}: {}\n",
-                key.replace("_", " ").to_uppercase(),
-                value
-            ));
-        }
-
-        content
-    }
-
     fn calculate_derived_values(&self, character_data: &CharacterData) -> HashMap<String, String> {
         let mut calculated = HashMap::new();
-        let abilities = &character_data.abilities;
+        let derived = DerivedStats::calculate(character_data);
+        let abilities = &derived.abilities;
 
         calculated.insert(
             "strength_modifier".to_string(),
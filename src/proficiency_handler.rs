@@ -0,0 +1,72 @@
+use crate::character_model::{OtherProficiencies, Proficiencies};
+
+pub struct ProficiencyHandler;
+
+impl ProficiencyHandler {
+    /// Merge the top-level lists with every race/class/background source,
+    /// dropping case-insensitive duplicates while keeping first-seen order.
+    pub fn merge(proficiencies: &Proficiencies) -> OtherProficiencies {
+        let mut merged = OtherProficiencies::default();
+
+        let all = std::iter::once(&proficiencies.other)
+            .chain(proficiencies.sources.iter().map(|s| &s.proficiencies));
+
+        for other in all {
+            Self::extend_unique(&mut merged.languages, &other.languages);
+            Self::extend_unique(&mut merged.armor, &other.armor);
+            Self::extend_unique(&mut merged.weapons, &other.weapons);
+            Self::extend_unique(&mut merged.tools, &other.tools);
+            Self::extend_unique(&mut merged.tool_expertise, &other.tool_expertise);
+        }
+
+        // Expertise implies proficiency, so every expertise tool is also a tool
+        let expertise = merged.tool_expertise.clone();
+        Self::extend_unique(&mut merged.tools, &expertise);
+
+        merged
+    }
+
+    /// Render the Other Proficiencies & Languages box, one line per group
+    pub fn format_other_proficiencies(other: &OtherProficiencies) -> String {
+        let mut lines = Vec::new();
+
+        if !other.armor.is_empty() {
+            lines.push(format!("Armor: {}", other.armor.join(", ")));
+        }
+        if !other.weapons.is_empty() {
+            lines.push(format!("Weapons: {}", other.weapons.join(", ")));
+        }
+        if !other.tools.is_empty() {
+            let tools: Vec<String> = other
+                .tools
+                .iter()
+                .map(|tool| {
+                    if Self::contains_ignore_case(&other.tool_expertise, tool) {
+                        format!("{} (expertise)", tool)
+                    } else {
+                        tool.clone()
+                    }
+                })
+                .collect();
+            lines.push(format!("Tools: {}", tools.join(", ")));
+        }
+        if !other.languages.is_empty() {
+            lines.push(format!("Languages: {}", other.languages.join(", ")));
+        }
+
+        lines.join("\n")
+    }
+
+    fn extend_unique(target: &mut Vec<String>, items: &[String]) {
+        for item in items {
+            let trimmed = item.trim();
+            if !trimmed.is_empty() && !Self::contains_ignore_case(target, trimmed) {
+                target.push(trimmed.to_string());
+            }
+        }
+    }
+
+    fn contains_ignore_case(list: &[String], item: &str) -> bool {
+        list.iter().any(|existing| existing.eq_ignore_ascii_case(item.trim()))
+    }
+}
@@ -0,0 +1,237 @@
+use crate::character_model::CharacterData;
+use crate::class_catalog::ClassCatalog;
+use crate::derived_stats::DerivedStats;
+use crate::dnd_validator::DndValidator;
+use crate::errors::{LevelUpError, PromptError};
+use crate::resource_provider::{RULES_REFERENCE, RULES_URI};
+use crate::schema::SchemaGenerator;
+use serde_json::{json, Map, Value};
+
+const ASSISTANT_GUIDE: &str = include_str!("../docs/dnd-assistant-tool-descriptions.md");
+
+/// The guide's "Key requirements" for character_data, less the XP note,
+/// which is campaign policy rather than a data rule
+const KEY_REQUIREMENTS: &str = r#"Key requirements:
+- All spell levels (cantrips through ninth_level) must be present, even if empty arrays
+- The number of prepared spells should be correctly limited based on the character stats
+- Skills and saving throws use lowercase ability names
+- Languages, armor, weapon and tool proficiencies can be listed at the top level of `proficiencies` or per `sources` entry (`race`, `class`, `background`, `feat`, `other`); duplicates are removed automatically
+- Death save successes and failures are 0-3 each, exhaustion level is 0-6; active conditions are listed at the top of Features & Traits
+- Currency uses abbreviated keys: cp, sp, ep, gp, pp
+- Spells need name, level, and prepared fields
+- Equipment items go in a single string, not an array
+- `abilities` are the base scores; ASIs and feat increases in `level_history` are applied on top and must only be taken at the class's Ability Score Improvement levels
+- Initiative, passive perception and hit point maximum include feat effects (Alert, Observant, Tough) when not given explicitly; feats not in the built-in catalog can be described in `feat_definitions`
+- Images can be given as `{"path": "..."}` (a file in the output directory) or `{"base64": "..."}` and must be PNG or JPEG"#;
+
+/// Parameterized MCP prompts for the common character workflows, built from
+/// the same guide and rules reference the assistant docs use
+pub struct PromptLibrary;
+
+impl PromptLibrary {
+    pub fn list() -> Vec<Value> {
+        vec![
+            json!({
+                "name": "create_character",
+                "description": "Guided creation of a new D&D 5e character, producing character_data ready for fill_dnd_character_sheet",
+                "arguments": [
+                    {"name": "concept", "description": "Short character concept, e.g. \"grumpy dwarf cleric\"", "required": false},
+                    {"name": "class", "description": "Class to build", "required": false},
+                    {"name": "race", "description": "Race to build", "required": false},
+                    {"name": "level", "description": "Starting level (default 1)", "required": false}
+                ]
+            }),
+            json!({
+                "name": "level_up",
+                "description": "Walk the player through leveling a character up with the level_up_character tool",
+                "arguments": [
+                    {"name": "character_data", "description": "Current character_data as JSON", "required": true}
+                ]
+            }),
+            json!({
+                "name": "review_sheet",
+                "description": "Review character_data against the D&D 5e rules before generating a sheet",
+                "arguments": [
+                    {"name": "character_data", "description": "character_data as JSON", "required": true}
+                ]
+            }),
+        ]
+    }
+
+    /// Prompt messages in MCP `prompts/get` form
+    pub fn get(name: &str, arguments: &Map<String, Value>) -> Result<Value, PromptError> {
+        match name {
+            "create_character" => Ok(Self::create_character(arguments)),
+            "level_up" => Self::level_up(arguments),
+            "review_sheet" => Self::review_sheet(arguments),
+            _ => Err(PromptError::UnknownPrompt(name.to_string())),
+        }
+    }
+
+    fn create_character(arguments: &Map<String, Value>) -> Value {
+        let mut request = String::from("Help me create a new D&D 5e character");
+        let details: Vec<String> = ["concept", "race", "class", "level"]
+            .iter()
+            .filter_map(|key| Self::optional_argument(arguments, key).map(|value| format!("{}: {}", key, value)))
+            .collect();
+        if !details.is_empty() {
+            request.push_str(&format!(" ({})", details.join(", ")));
+        }
+
+        let text = format!(
+            "{}.\n\nAsk me about anything that is missing or ambiguous before deciding for me. \
+             When the character is complete, build `character_data` matching the JSON Schema below, \
+             check it against the rules reference, and call `fill_dnd_character_sheet`.\n\n{}\n\n\
+             character_data JSON Schema:\n```json\n{}\n```",
+            request,
+            KEY_REQUIREMENTS,
+            Self::schema_text(),
+        );
+
+        Self::prompt(
+            "Create a new D&D 5e character",
+            vec![Self::user_text(&text), Self::rules_resource()],
+        )
+    }
+
+    fn level_up(arguments: &Map<String, Value>) -> Result<Value, PromptError> {
+        let character = Self::character_argument(arguments)?;
+        if character.character.level >= 20 {
+            return Err(PromptError::InvalidArgument {
+                argument: "character_data".to_string(),
+                message: LevelUpError::MaxLevel(character.character.level).to_string(),
+            });
+        }
+        let class_notes = match ClassCatalog::get(&character.character.class) {
+            Some(class) => format!(
+                "{} uses a d{} hit die, gains Ability Score Improvements at levels {:?} and chooses a subclass at level {}.",
+                class.name, class.hit_die, class.asi_levels, class.subclass_level
+            ),
+            None => format!("{} is not a catalog class; check the rules manually.", character.character.class),
+        };
+
+        let text = format!(
+            "Level up {} from level {} to level {}.\n\n{}\n\n{}\n\n\
+             Call `level_up_character` with the character below; for every entry in `pending_choices`, \
+             ask me and call the tool again with my answers. Then summarize the returned `changes`.\n\n\
+             ```json\n{}\n```",
+            character.character.name,
+            character.character.level,
+            character.character.level + 1,
+            class_notes,
+            Self::section(ASSISTANT_GUIDE, "## Leveling Up a Character"),
+            Self::pretty(&character),
+        );
+
+        Ok(Self::prompt(
+            &format!("Level up {}", character.character.name),
+            vec![Self::user_text(&text), Self::rules_resource()],
+        ))
+    }
+
+    fn review_sheet(arguments: &Map<String, Value>) -> Result<Value, PromptError> {
+        let character = Self::character_argument(arguments)?;
+        let findings = match DndValidator::new(false).validate(&character) {
+            Ok(_) => "The server's validator found no rule violations.".to_string(),
+            Err(errors) => {
+                let lines: Vec<String> = errors.iter().map(|e| format!("- {}", e)).collect();
+                format!("The server's validator reported:\n{}", lines.join("\n"))
+            }
+        };
+        let derived = DerivedStats::calculate(&character);
+
+        let text = format!(
+            "Review {}'s character data before we generate the sheet. Check it against the rules reference \
+             and point out anything illegal, inconsistent or missing, and suggest fixes. Don't change anything \
+             without asking me.\n\n{}\n\nDerived values: proficiency bonus +{}, initiative {:+}, passive perception {}{}.\n\n\
+             ```json\n{}\n```",
+            character.character.name,
+            findings,
+            derived.proficiency_bonus,
+            derived.initiative,
+            derived.passive_perception,
+            derived
+                .hit_point_maximum
+                .map(|hp| format!(", hit point maximum {}", hp))
+                .unwrap_or_default(),
+            Self::pretty(&character),
+        );
+
+        Ok(Self::prompt(
+            &format!("Review {}'s character sheet", character.character.name),
+            vec![Self::user_text(&text), Self::rules_resource()],
+        ))
+    }
+
+    fn character_argument(arguments: &Map<String, Value>) -> Result<CharacterData, PromptError> {
+        let raw = arguments
+            .get("character_data")
+            .ok_or_else(|| PromptError::MissingArgument("character_data".to_string()))?;
+        // Prompt arguments are strings, but accept an inline object too
+        let parsed = match raw {
+            Value::String(text) => serde_json::from_str(text),
+            other => serde_json::from_value(other.clone()),
+        };
+        parsed.map_err(|e| PromptError::InvalidArgument {
+            argument: "character_data".to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    fn optional_argument<'a>(arguments: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+        arguments
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Text from `heading` up to the next heading of the same or higher level
+    fn section(document: &str, heading: &str) -> String {
+        let Some(start) = document.find(heading) else {
+            return String::new();
+        };
+        let rest = &document[start..];
+        let end = rest[heading.len()..]
+            .find("\n## ")
+            .map(|offset| offset + heading.len())
+            .unwrap_or(rest.len());
+        rest[..end].trim().to_string()
+    }
+
+    fn schema_text() -> String {
+        serde_json::to_string(&SchemaGenerator::character_data()).unwrap_or_default()
+    }
+
+    fn pretty(character: &CharacterData) -> String {
+        serde_json::to_string_pretty(character).unwrap_or_default()
+    }
+
+    fn prompt(description: &str, messages: Vec<Value>) -> Value {
+        json!({
+            "description": description,
+            "messages": messages
+        })
+    }
+
+    fn user_text(text: &str) -> Value {
+        json!({
+            "role": "user",
+            "content": {"type": "text", "text": text}
+        })
+    }
+
+    fn rules_resource() -> Value {
+        json!({
+            "role": "user",
+            "content": {
+                "type": "resource",
+                "resource": {
+                    "uri": RULES_URI,
+                    "mimeType": "text/markdown",
+                    "text": RULES_REFERENCE
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use serde::Serialize;
+
+/// SRD race reference data exposed as MCP resources
+#[derive(Debug, Clone, Serialize)]
+pub struct RaceInfo {
+    pub name: &'static str,
+    /// Ability score increases as (ability, amount) pairs
+    pub ability_score_increases: &'static [(&'static str, u8)],
+    pub size: &'static str,
+    pub speed: u8,
+    pub languages: &'static [&'static str],
+    pub traits: &'static [&'static str],
+    /// The SRD subrace, where the race has one
+    pub subrace: Option<&'static str>,
+}
+
+const RACES: &[RaceInfo] = &[
+    RaceInfo {
+        name: "Dwarf",
+        ability_score_increases: &[("constitution", 2), ("wisdom", 1)],
+        size: "Medium",
+        speed: 25,
+        languages: &["Common", "Dwarvish"],
+        traits: &[
+            "Darkvision",
+            "Dwarven Resilience",
+            "Dwarven Combat Training",
+            "Tool Proficiency",
+            "Stonecunning",
+            "Dwarven Toughness",
+        ],
+        subrace: Some("Hill Dwarf"),
+    },
+    RaceInfo {
+        name: "Elf",
+        ability_score_increases: &[("dexterity", 2), ("intelligence", 1)],
+        size: "Medium",
+        speed: 30,
+        languages: &["Common", "Elvish"],
+        traits: &[
+            "Darkvision",
+            "Keen Senses",
+            "Fey Ancestry",
+            "Trance",
+            "Elf Weapon Training",
+            "Cantrip",
+            "Extra Language",
+        ],
+        subrace: Some("High Elf"),
+    },
+    RaceInfo {
+        name: "Halfling",
+        ability_score_increases: &[("dexterity", 2), ("charisma", 1)],
+        size: "Small",
+        speed: 25,
+        languages: &["Common", "Halfling"],
+        traits: &["Lucky", "Brave", "Halfling Nimbleness", "Naturally Stealthy"],
+        subrace: Some("Lightfoot"),
+    },
+    RaceInfo {
+        name: "Human",
+        ability_score_increases: &[
+            ("strength", 1),
+            ("dexterity", 1),
+            ("constitution", 1),
+            ("intelligence", 1),
+            ("wisdom", 1),
+            ("charisma", 1),
+        ],
+        size: "Medium",
+        speed: 30,
+        languages: &["Common", "One extra language"],
+        traits: &[],
+        subrace: None,
+    },
+    RaceInfo {
+        name: "Dragonborn",
+        ability_score_increases: &[("strength", 2), ("charisma", 1)],
+        size: "Medium",
+        speed: 30,
+        languages: &["Common", "Draconic"],
+        traits: &["Draconic Ancestry", "Breath Weapon", "Damage Resistance"],
+        subrace: None,
+    },
+    RaceInfo {
+        name: "Gnome",
+        ability_score_increases: &[("intelligence", 2), ("constitution", 1)],
+        size: "Small",
+        speed: 25,
+        languages: &["Common", "Gnomish"],
+        traits: &["Darkvision", "Gnome Cunning", "Artificer's Lore", "Tinker"],
+        subrace: Some("Rock Gnome"),
+    },
+    RaceInfo {
+        name: "Half-Elf",
+        ability_score_increases: &[("charisma", 2)],
+        size: "Medium",
+        speed: 30,
+        languages: &["Common", "Elvish", "One extra language"],
+        traits: &[
+            "Two other ability scores increase by 1",
+            "Darkvision",
+            "Fey Ancestry",
+            "Skill Versatility",
+        ],
+        subrace: None,
+    },
+    RaceInfo {
+        name: "Half-Orc",
+        ability_score_increases: &[("strength", 2), ("constitution", 1)],
+        size: "Medium",
+        speed: 30,
+        languages: &["Common", "Orc"],
+        traits: &["Darkvision", "Menacing", "Relentless Endurance", "Savage Attacks"],
+        subrace: None,
+    },
+    RaceInfo {
+        name: "Tiefling",
+        ability_score_increases: &[("intelligence", 1), ("charisma", 2)],
+        size: "Medium",
+        speed: 30,
+        languages: &["Common", "Infernal"],
+        traits: &["Darkvision", "Hellish Resistance", "Infernal Legacy"],
+        subrace: None,
+    },
+];
+
+pub struct RaceCatalog;
+
+impl RaceCatalog {
+    /// Look up a race by name (case-insensitive); subrace names match their race
+    pub fn get(race: &str) -> Option<&'static RaceInfo> {
+        let race = race.trim();
+        RACES.iter().find(|info| {
+            info.name.eq_ignore_ascii_case(race)
+                || info
+                    .subrace
+                    .is_some_and(|subrace| subrace.eq_ignore_ascii_case(race))
+        })
+    }
+
+    pub fn all() -> &'static [RaceInfo] {
+        RACES
+    }
+}
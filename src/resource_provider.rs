@@ -0,0 +1,185 @@
+use crate::class_catalog::ClassCatalog;
+use crate::errors::ResourceError;
+use crate::race_catalog::RaceCatalog;
+use crate::spell_catalog::SpellCatalog;
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE_URI: &str = "dnd://templates/5e-character-sheet.pdf";
+pub(crate) const RULES_URI: &str = "dnd://rules/dnd-rules.md";
+const SHEETS_PREFIX: &str = "dnd://sheets/";
+
+pub(crate) const RULES_REFERENCE: &str = include_str!("../docs/dnd-rules.md");
+
+/// Serves MCP resources: the sheet template, rules reference, SRD catalogs
+/// and sheets previously generated in the output directory
+pub struct ResourceProvider {
+    output_dir: PathBuf,
+    template: Cow<'static, [u8]>,
+}
+
+impl ResourceProvider {
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            template: Cow::Borrowed(crate::mcp_server::PDF_TEMPLATE),
+        }
+    }
+
+    /// Serve `template` as the sheet template instead of the bundled one
+    pub fn with_template(mut self, template: Cow<'static, [u8]>) -> Self {
+        self.template = template;
+        self
+    }
+
+    pub fn list(&self) -> Vec<Value> {
+        let mut resources = vec![
+            resource(TEMPLATE_URI, "5e Character Sheet", "Fillable D&D 5e character sheet template", "application/pdf"),
+            resource(RULES_URI, "D&D 5e Rules Reference", "Rules and validation reference used by the server", "text/markdown"),
+            resource("dnd://classes", "Classes", "All SRD classes", "application/json"),
+            resource("dnd://races", "Races", "All SRD races", "application/json"),
+            resource("dnd://spells", "Spells", "All catalog spells", "application/json"),
+        ];
+
+        for class in ClassCatalog::all() {
+            let description = format!("d{} hit die, ASIs at {:?}", class.hit_die, class.asi_levels);
+            resources.push(resource(&format!("dnd://classes/{}", slug(class.name)), class.name, &description, "application/json"));
+        }
+        for race in RaceCatalog::all() {
+            let description = format!("{}, speed {} ft", race.size, race.speed);
+            resources.push(resource(&format!("dnd://races/{}", slug(race.name)), race.name, &description, "application/json"));
+        }
+        for spell in SpellCatalog::all() {
+            let description = match spell.level {
+                0 => format!("{} cantrip", spell.school),
+                level => format!("Level {} {}", level, spell.school.to_lowercase()),
+            };
+            resources.push(resource(&format!("dnd://spells/{}", slug(spell.name)), spell.name, &description, "application/json"));
+        }
+        for sheet in self.generated_sheets() {
+            let description = format!("Character sheet generated in {}", self.output_dir.display());
+            resources.push(resource(&format!("{}{}", SHEETS_PREFIX, sheet), &sheet, &description, "application/pdf"));
+        }
+
+        resources
+    }
+
+    /// URI templates for clients that build catalog URIs themselves
+    pub fn templates(&self) -> Vec<Value> {
+        vec![
+            json!({"uriTemplate": "dnd://classes/{name}", "name": "Class", "mimeType": "application/json"}),
+            json!({"uriTemplate": "dnd://races/{name}", "name": "Race", "mimeType": "application/json"}),
+            json!({"uriTemplate": "dnd://spells/{name}", "name": "Spell", "mimeType": "application/json"}),
+            json!({"uriTemplate": "dnd://sheets/{file}", "name": "Generated character sheet", "mimeType": "application/pdf"}),
+        ]
+    }
+
+    /// Resource contents in MCP `resources/read` form
+    pub fn read(&self, uri: &str) -> Result<Value, ResourceError> {
+        let not_found = || ResourceError::NotFound(uri.to_string());
+
+        match uri {
+            TEMPLATE_URI => return Ok(blob(uri, "application/pdf", &self.template)),
+            RULES_URI => return Ok(text(uri, "text/markdown", RULES_REFERENCE.to_string())),
+            "dnd://classes" => return json_contents(uri, ClassCatalog::all()),
+            "dnd://races" => return json_contents(uri, RaceCatalog::all()),
+            "dnd://spells" => return json_contents(uri, SpellCatalog::all()),
+            _ => {}
+        }
+
+        if let Some(name) = uri.strip_prefix("dnd://classes/") {
+            let class = ClassCatalog::all().iter().find(|c| slug(c.name) == slug(name)).ok_or_else(not_found)?;
+            return json_contents(uri, class);
+        }
+        if let Some(name) = uri.strip_prefix("dnd://races/") {
+            let race = RaceCatalog::all().iter().find(|r| slug(r.name) == slug(name)).ok_or_else(not_found)?;
+            return json_contents(uri, race);
+        }
+        if let Some(name) = uri.strip_prefix("dnd://spells/") {
+            let spell = SpellCatalog::all().iter().find(|s| slug(s.name) == slug(name)).ok_or_else(not_found)?;
+            return json_contents(uri, spell);
+        }
+        if let Some(file) = uri.strip_prefix(SHEETS_PREFIX) {
+            // Only serve files that are actually listed, never arbitrary paths
+            if !self.generated_sheets().iter().any(|sheet| sheet == file) {
+                return Err(not_found());
+            }
+            let bytes = fs::read(self.output_dir.join(file))
+                .map_err(|e| ResourceError::ReadFailed(format!("{}: {}", uri, e)))?;
+            return Ok(blob(uri, "application/pdf", &bytes));
+        }
+
+        Err(not_found())
+    }
+
+    /// `dnd://sheets/` URI for sheets in the output directory, `file://` otherwise
+    pub fn sheet_uri(&self, path: &Path) -> String {
+        let absolute = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let in_output_dir = self
+            .output_dir
+            .canonicalize()
+            .map(|dir| absolute.parent() == Some(dir.as_path()))
+            .unwrap_or(false);
+        match absolute.file_name() {
+            Some(name) if in_output_dir => format!("{}{}", SHEETS_PREFIX, name.to_string_lossy()),
+            _ => format!("file://{}", absolute.display()),
+        }
+    }
+
+    /// File names of the PDFs in the output directory, sorted
+    fn generated_sheets(&self) -> Vec<String> {
+        let mut sheets: Vec<String> = fs::read_dir(&self.output_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && is_pdf(path))
+            .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .collect();
+        sheets.sort();
+        sheets
+    }
+}
+
+/// URI-friendly catalog name, e.g. "Hunter's Mark" -> "hunters-mark"
+pub fn slug(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| *c != '\'')
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("pdf"))
+        .unwrap_or(false)
+}
+
+fn resource(uri: &str, name: &str, description: &str, mime_type: &str) -> Value {
+    json!({
+        "uri": uri,
+        "name": name,
+        "description": description,
+        "mimeType": mime_type
+    })
+}
+
+fn text(uri: &str, mime_type: &str, text: String) -> Value {
+    json!({"contents": [{"uri": uri, "mimeType": mime_type, "text": text}]})
+}
+
+fn blob(uri: &str, mime_type: &str, bytes: &[u8]) -> Value {
+    json!({"contents": [{"uri": uri, "mimeType": mime_type, "blob": general_purpose::STANDARD.encode(bytes)}]})
+}
+
+fn json_contents<T: Serialize + ?Sized>(uri: &str, value: &T) -> Result<Value, ResourceError> {
+    let body = serde_json::to_string_pretty(value)
+        .map_err(|e| ResourceError::ReadFailed(format!("{}: {}", uri, e)))?;
+    Ok(text(uri, "application/json", body))
+}
@@ -0,0 +1,94 @@
+use crate::character_model::CharacterData;
+use crate::level_up::LevelUpChoices;
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+pub struct SchemaGenerator;
+
+impl SchemaGenerator {
+    /// JSON Schema for `character_data`, generated from the model types so the
+    /// tool schema can't drift from what the server actually parses
+    pub fn character_data() -> Value {
+        Self::inline_schema_for::<CharacterData>()
+    }
+
+    /// JSON Schema for the `choices` argument of `level_up_character`
+    pub fn level_up_choices() -> Value {
+        Self::inline_schema_for::<LevelUpChoices>()
+    }
+
+    /// Standalone schema document with `$schema` and `$defs`, for the `schema` CLI subcommand
+    pub fn character_data_document() -> Value {
+        let generator = SchemaSettings::draft2020_12().into_generator();
+        serde_json::to_value(generator.into_root_schema_for::<CharacterData>())
+            .unwrap_or(Value::Null)
+    }
+
+    /// Tool schemas are embedded inside `inputSchema`/`outputSchema`, where `$ref`s
+    /// to a root `$defs` wouldn't resolve, so every subschema is inlined
+    pub fn inline_schema_for<T: JsonSchema>() -> Value {
+        let settings = SchemaSettings::draft07().with(|s| {
+            s.meta_schema = None;
+            s.inline_subschemas = true;
+        });
+        let mut schema =
+            serde_json::to_value(settings.into_generator().into_root_schema_for::<T>())
+                .unwrap_or(Value::Null);
+        if let Some(object) = schema.as_object_mut() {
+            object.remove("title");
+        }
+        schema
+    }
+}
+
+/// Example shown in the schema; a 1st level wizard with the common sections filled in
+pub fn example_character() -> Value {
+    json!({
+        "character": {
+            "name": "Elara Moonwhisper",
+            "class": "Wizard",
+            "level": 1,
+            "race": "High Elf",
+            "background": "Sage",
+            "alignment": "Neutral Good"
+        },
+        "abilities": {
+            "strength": 8,
+            "dexterity": 14,
+            "constitution": 13,
+            "intelligence": 16,
+            "wisdom": 12,
+            "charisma": 10
+        },
+        "proficiencies": {
+            "saving_throws": ["intelligence", "wisdom"],
+            "skills": ["arcana", "history"],
+            "languages": ["Common", "Elvish"]
+        },
+        "combat": {
+            "armor_class": 12,
+            "speed": 30,
+            "hit_point_maximum": 7,
+            "current_hit_points": 7
+        },
+        "spells": {
+            "spellcasting_class": "Wizard",
+            "spellcasting_ability": "Intelligence",
+            "cantrips": [{"name": "Fire Bolt", "level": 0, "prepared": true}],
+            "first_level": [{"name": "Magic Missile", "level": 1, "prepared": true}],
+            "second_level": [],
+            "third_level": [],
+            "fourth_level": [],
+            "fifth_level": [],
+            "sixth_level": [],
+            "seventh_level": [],
+            "eighth_level": [],
+            "ninth_level": []
+        },
+        "equipment": {
+            "currency": {"cp": 0, "sp": 0, "ep": 0, "gp": 10, "pp": 0},
+            "items": "Quarterstaff, Spellbook, Scholar's pack"
+        }
+    })
+}
@@ -0,0 +1,72 @@
+use crate::character_model::CURRENT_SCHEMA_VERSION;
+use crate::errors::MigrationError;
+use serde_json::{json, Map, Value};
+
+/// One step per version: `STEPS[n]` takes a version `n + 1` document to `n + 2`
+const STEPS: [fn(&mut Map<String, Value>); CURRENT_SCHEMA_VERSION as usize - 1] = [SchemaMigration::v1_to_v2];
+
+const SPELL_LEVELS: [&str; 10] = [
+    "cantrips",
+    "first_level",
+    "second_level",
+    "third_level",
+    "fourth_level",
+    "fifth_level",
+    "sixth_level",
+    "seventh_level",
+    "eighth_level",
+    "ninth_level",
+];
+
+/// Brings saved character_data JSON up to `CURRENT_SCHEMA_VERSION`, one
+/// version at a time. Documents without `schema_version` are version 1.
+///
+/// | Version | Layout |
+/// |---|---|
+/// | 1 | No `schema_version`; hand-written files often leave out the spell levels they don't use |
+/// | 2 | `schema_version` added; every spell level list is present |
+pub struct SchemaMigration;
+
+impl SchemaMigration {
+    /// The document's `schema_version`, 1 when it has none
+    pub fn version_of(value: &Value) -> Result<u64, MigrationError> {
+        let object = value.as_object().ok_or(MigrationError::NotAnObject)?;
+        match object.get("schema_version") {
+            None | Some(Value::Null) => Ok(1),
+            Some(version) => version
+                .as_u64()
+                .filter(|version| *version >= 1)
+                .ok_or_else(|| MigrationError::InvalidVersion(version.to_string())),
+        }
+    }
+
+    /// Migrate in place and return the version the document started at
+    pub fn migrate(value: &mut Value) -> Result<u64, MigrationError> {
+        let from = Self::version_of(value)?;
+        if from > CURRENT_SCHEMA_VERSION as u64 {
+            return Err(MigrationError::NewerVersion {
+                found: from,
+                current: CURRENT_SCHEMA_VERSION,
+            });
+        }
+        let Some(object) = value.as_object_mut() else {
+            return Err(MigrationError::NotAnObject);
+        };
+        for (index, step) in STEPS.iter().enumerate().skip(from as usize - 1) {
+            step(object);
+            object.insert("schema_version".to_string(), json!(index + 2));
+        }
+        Ok(from)
+    }
+
+    /// Fill in the spell level lists version 1 files left out
+    fn v1_to_v2(character: &mut Map<String, Value>) {
+        if let Some(Value::Object(spells)) = character.get_mut("spells") {
+            for level in SPELL_LEVELS {
+                if spells.get(level).is_none_or(Value::is_null) {
+                    spells.insert(level.to_string(), json!([]));
+                }
+            }
+        }
+    }
+}
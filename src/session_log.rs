@@ -0,0 +1,262 @@
+use crate::character_model::{AdvancementMode, CharacterData, Currency};
+use crate::character_store::{CharacterStore, StoreLock};
+use crate::dnd_validator::DndValidator;
+use crate::errors::StoreError;
+use crate::logging::Logger;
+use crate::party::Party;
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const LOGGER: &str = "session_log";
+
+/// Treasure handed out during a session
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Loot {
+    /// Character id (or, for a party session, player name) receiving it;
+    /// the party's shared treasure when omitted
+    pub character_id: Option<String>,
+    pub currency: Option<Currency>,
+    /// e.g. "Potion of Healing"
+    pub items: Vec<String>,
+}
+
+/// What happened in one game session, recorded in the character store
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Session {
+    /// 1 for the first session
+    pub number: u32,
+    /// When it was played, e.g. "2026-03-14"
+    pub date: String,
+    /// Id of the party that played
+    #[serde(default)]
+    pub party: Option<String>,
+    /// Ids of the characters who took part
+    pub attendees: Vec<String>,
+    /// XP awarded to each attending character
+    #[serde(default)]
+    pub experience_points: u32,
+    #[serde(default)]
+    pub loot: Vec<Loot>,
+    /// Story milestones reached, e.g. "Cleared Wave Echo Cave"
+    #[serde(default)]
+    pub milestones: Vec<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Set once the XP and loot have been given out
+    #[serde(default)]
+    pub applied: bool,
+}
+
+/// Where an attending character stands after a session is applied
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SessionAward {
+    pub character_id: String,
+    pub name: String,
+    pub level: u8,
+    /// Total after this session's award
+    pub experience_points: u32,
+    /// Snapshot saved with the award
+    pub version: u32,
+    /// Has the experience for a higher level than their own
+    pub ready_to_level_up: bool,
+    /// XP needed for the next level; absent at level 20 and for milestone characters
+    pub next_level_at: Option<u32>,
+    /// Coins and items received, e.g. "50 gp"
+    pub loot: Vec<String>,
+}
+
+/// Records sessions and hands out their XP and loot
+pub struct SessionLog;
+
+impl SessionLog {
+    /// Ready `session` to be recorded: player names are swapped for their
+    /// party's character ids, and the date, attendees and loot recipients
+    /// are checked against the store
+    pub fn prepare(store: &CharacterStore, session: &mut Session) -> Result<(), StoreError> {
+        let invalid = |message: String| Err(StoreError::InvalidSession(message));
+        if NaiveDate::parse_from_str(&session.date, "%Y-%m-%d").is_err() {
+            return invalid(format!("date '{}' is not YYYY-MM-DD", session.date));
+        }
+        if session.attendees.is_empty() {
+            return invalid("no attendees".to_string());
+        }
+
+        let party = match &session.party {
+            Some(party) => {
+                let loaded = store.load_party(party)?;
+                session.party = Some(CharacterStore::id_for(party));
+                Some(loaded)
+            }
+            None => None,
+        };
+        let resolve = |name: &str| -> String {
+            let character_id = party.as_ref().and_then(|party| party.character_for(name));
+            match character_id {
+                Some(character_id) if store.load(name, None).is_err() => character_id.to_string(),
+                _ => name.to_string(),
+            }
+        };
+
+        let mut attendees: Vec<String> = Vec::new();
+        for attendee in &session.attendees {
+            let character_id = resolve(attendee);
+            store.load(&character_id, None)?;
+            if !attendees.contains(&character_id) {
+                attendees.push(character_id);
+            }
+        }
+        session.attendees = attendees;
+        for loot in session.loot.iter_mut() {
+            match &loot.character_id {
+                Some(recipient) => {
+                    let character_id = resolve(recipient);
+                    if !session.attendees.contains(&character_id) {
+                        return invalid(format!("loot for {}, who didn't attend", recipient));
+                    }
+                    loot.character_id = Some(character_id);
+                }
+                None if party.is_none() => {
+                    return invalid("loot without a character_id needs the session's party".to_string());
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Prepare and save `session` with the store locked, so it can neither
+    /// replace a session that is being (or has been) applied nor share its
+    /// number with another recorded at the same time. Without a `number` it
+    /// gets the one after the last recorded session
+    pub fn record(store: &CharacterStore, session: &mut Session, number: Option<u32>) -> Result<(), StoreError> {
+        let _lock = store.lock();
+        session.number = number.unwrap_or_else(|| store.session_numbers().last().copied().unwrap_or(0) + 1);
+        session.applied = false;
+        match store.load_session(session.number) {
+            Ok(existing) if existing.applied => Err(StoreError::SessionApplied(session.number)),
+            _ => Self::prepare(store, session).and_then(|_| store.save_session(session)),
+        }
+    }
+
+    /// Mark the session applied, then give each attendee the session's XP
+    /// (unless they level by milestone) and their loot, and the party its
+    /// shared loot. Every character gets a new snapshot, so `character_diff`
+    /// shows what the session changed. The store stays locked throughout,
+    /// and since the session is marked first, a save failing partway can't
+    /// lead to anything being given twice; the log names who was awarded
+    pub fn apply(store: &CharacterStore, number: u32) -> Result<(Vec<SessionAward>, Option<Party>), StoreError> {
+        let lock = store.lock();
+        let mut session = store.load_session(number)?;
+        if session.applied {
+            return Err(StoreError::SessionApplied(number));
+        }
+        let mut party = session.party.as_deref().map(|party| store.load_party(party)).transpose()?;
+        // Load everyone first, so a missing character stops the session before anything is saved
+        let characters = session
+            .attendees
+            .iter()
+            .map(|character_id| store.load(character_id, None).map(|(character, _)| (character_id.clone(), character)))
+            .collect::<Result<Vec<_>, _>>()?;
+        session.applied = true;
+        store.save_session(&session)?;
+
+        let mut awards = Vec::new();
+        let awarded = Self::give_out(store, &lock, &session, characters, party.as_mut(), &mut awards);
+        if let Err(e) = awarded {
+            let given: Vec<&str> = awards.iter().map(|award| award.character_id.as_str()).collect();
+            Logger::warning(
+                LOGGER,
+                &format!("Session {} stopped partway ({}); already awarded: {}", number, e, given.join(", ")),
+            );
+            return Err(e);
+        }
+        Ok((awards, party))
+    }
+
+    fn give_out(
+        store: &CharacterStore,
+        lock: &StoreLock<'_>,
+        session: &Session,
+        characters: Vec<(String, CharacterData)>,
+        party: Option<&mut Party>,
+        awards: &mut Vec<SessionAward>,
+    ) -> Result<(), StoreError> {
+        for (character_id, mut character) in characters {
+            let milestone = character.character.advancement_mode == Some(AdvancementMode::Milestone);
+            if session.experience_points > 0 && !milestone {
+                let xp = character.character.experience_points.unwrap_or(0);
+                character.character.experience_points = Some(xp.saturating_add(session.experience_points));
+            }
+            let mut received = Vec::new();
+            for loot in session.loot.iter().filter(|loot| loot.character_id.as_ref() == Some(&character_id)) {
+                received.extend(Self::give(&mut character, loot));
+            }
+            let saved = store.save_locked(lock, Some(&character_id), &character)?;
+            awards.push(Self::award(&character_id, &character, saved.version, received));
+        }
+
+        if let Some(party) = party {
+            party.experience_points = party.experience_points.saturating_add(session.experience_points);
+            for loot in session.loot.iter().filter(|loot| loot.character_id.is_none()) {
+                if let Some(currency) = &loot.currency {
+                    Self::add_currency(&mut party.currency, currency);
+                }
+                party.treasure.extend(loot.items.iter().cloned());
+            }
+            store.save_party(party)?;
+        }
+        Ok(())
+    }
+
+    /// Add `loot` to the character's equipment, returning what was added
+    fn give(character: &mut CharacterData, loot: &Loot) -> Vec<String> {
+        let equipment = character.equipment.get_or_insert_with(Default::default);
+        let mut received = Vec::new();
+        if let Some(currency) = &loot.currency {
+            Self::add_currency(equipment.currency.get_or_insert_with(Default::default), currency);
+            received.extend(Self::describe_currency(currency));
+        }
+        if !loot.items.is_empty() {
+            let mut items: Vec<String> = equipment.items.iter().filter(|items| !items.trim().is_empty()).cloned().collect();
+            items.extend(loot.items.iter().cloned());
+            equipment.items = Some(items.join(", "));
+            received.extend(loot.items.iter().cloned());
+        }
+        received
+    }
+
+    fn award(character_id: &str, character: &CharacterData, version: u32, loot: Vec<String>) -> SessionAward {
+        SessionAward {
+            character_id: character_id.to_string(),
+            name: character.character.name.clone(),
+            level: character.character.level,
+            experience_points: character.character.experience_points.unwrap_or(0),
+            version,
+            ready_to_level_up: DndValidator::ready_to_level_up(character),
+            next_level_at: match character.character.advancement_mode {
+                Some(AdvancementMode::Milestone) => None,
+                _ => DndValidator::experience_for_level(character.character.level.saturating_add(1)),
+            },
+            loot,
+        }
+    }
+
+    fn add_currency(total: &mut Currency, amount: &Currency) {
+        total.cp = total.cp.saturating_add(amount.cp);
+        total.sp = total.sp.saturating_add(amount.sp);
+        total.ep = total.ep.saturating_add(amount.ep);
+        total.gp = total.gp.saturating_add(amount.gp);
+        total.pp = total.pp.saturating_add(amount.pp);
+    }
+
+    /// "50 gp", "12 sp", ... for each coin that isn't zero
+    fn describe_currency(currency: &Currency) -> Vec<String> {
+        [("pp", currency.pp), ("gp", currency.gp), ("ep", currency.ep), ("sp", currency.sp), ("cp", currency.cp)]
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0)
+            .map(|(coin, amount)| format!("{} {}", amount, coin))
+            .collect()
+    }
+}
@@ -0,0 +1,373 @@
+use crate::errors::PdfError;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+
+/// Multiline text field flag (bit 13 of Ff)
+const MULTILINE: i64 = 1 << 12;
+/// Hidden annotation flag (bit 2 of F)
+const HIDDEN: i64 = 1 << 1;
+const MIN_FONT_SIZE: f64 = 6.0;
+const PADDING: f64 = 2.0;
+
+/// Where and what to draw for one widget annotation
+struct Placement {
+    rect: [f64; 4],
+    bbox: [f64; 4],
+    appearance: ObjectId,
+}
+
+/// Burns filled form fields into the page content and removes the form,
+/// so the sheet prints the same everywhere and can't be edited
+pub struct SheetFlattener;
+
+impl SheetFlattener {
+    /// Flatten every widget on every page; returns the number of fields drawn
+    pub fn flatten(doc: &mut Document) -> Result<usize, PdfError> {
+        let font_id = doc.add_object(Self::helvetica());
+        let mut drawn = 0;
+
+        for (_, page_id) in doc.get_pages() {
+            let page = doc
+                .get_dictionary(page_id)
+                .map_err(|e| PdfError::ParseError(format!("Invalid page {:?}: {}", page_id, e)))?
+                .clone();
+            let annotations = match page.get(b"Annots") {
+                Ok(annots) => match doc.dereference(annots) {
+                    Ok((_, Object::Array(annots))) => annots.clone(),
+                    _ => Vec::new(),
+                },
+                Err(_) => continue,
+            };
+
+            let mut kept = Vec::new();
+            let mut placements = Vec::new();
+            for annotation in annotations {
+                let widget = match annotation.as_reference().ok().and_then(|id| doc.get_dictionary(id).ok()) {
+                    Some(widget) if widget.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Widget") => widget.clone(),
+                    _ => {
+                        kept.push(annotation);
+                        continue;
+                    }
+                };
+                if let Some(placement) = Self::placement(doc, &widget, font_id) {
+                    placements.push(placement);
+                }
+            }
+
+            drawn += placements.len();
+            Self::draw_on_page(doc, page_id, page, &placements, kept)?;
+        }
+
+        if let Ok(catalog_id) = doc.trailer.get(b"Root").and_then(Object::as_reference) {
+            if let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(catalog_id) {
+                catalog.remove(b"AcroForm");
+            }
+        }
+        doc.prune_objects();
+        Ok(drawn)
+    }
+
+    /// The appearance to draw for a widget, or None when it shows nothing
+    fn placement(doc: &mut Document, widget: &Dictionary, font_id: ObjectId) -> Option<Placement> {
+        let flags = Self::inherited(doc, widget, b"F").and_then(|f| f.as_i64().ok()).unwrap_or(0);
+        if flags & HIDDEN != 0 {
+            return None;
+        }
+        let rect = Self::numbers(doc, widget.get(b"Rect").ok()?)?;
+        let field_type = Self::inherited(doc, widget, b"FT").and_then(|t| t.as_name().ok().map(<[u8]>::to_vec));
+        let value = Self::inherited(doc, widget, b"V");
+
+        let appearance = match field_type.as_deref() {
+            Some(b"Tx") => {
+                let text = match value {
+                    Some(Object::String(bytes, _)) => String::from_utf8_lossy(&bytes).into_owned(),
+                    _ => return None,
+                };
+                if text.trim().is_empty() {
+                    return None;
+                }
+                let field_flags = Self::inherited(doc, widget, b"Ff").and_then(|f| f.as_i64().ok()).unwrap_or(0);
+                let alignment = Self::inherited(doc, widget, b"Q").and_then(|q| q.as_i64().ok()).unwrap_or(0);
+                let font_size = Self::inherited(doc, widget, b"DA")
+                    .and_then(|da| da.as_str().ok().map(Self::font_size))
+                    .unwrap_or(0.0);
+                let stream = Self::text_appearance(&text, &rect, font_size, field_flags & MULTILINE != 0, alignment, font_id);
+                doc.add_object(stream)
+            }
+            Some(b"Btn") => {
+                let normal = match doc.dereference(widget.get(b"AP").ok()?) {
+                    Ok((_, Object::Dictionary(ap))) => ap.get(b"N").ok()?.clone(),
+                    _ => return None,
+                };
+                match doc.dereference(&normal) {
+                    // Push buttons (the portrait and faction symbol) have a single appearance
+                    Ok((Some(id), Object::Stream(_))) => id,
+                    // Check boxes pick the "on" state when the value isn't Off
+                    Ok((_, Object::Dictionary(states))) => {
+                        let on = match value {
+                            Some(Object::Name(name)) | Some(Object::String(name, _)) => {
+                                !name.is_empty() && name != b"Off"
+                            }
+                            _ => false,
+                        };
+                        if !on {
+                            return None;
+                        }
+                        states
+                            .iter()
+                            .find(|(state, _)| state.as_slice() != b"Off")
+                            .and_then(|(_, stream)| stream.as_reference().ok())?
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        let bbox = match doc.get_object(appearance) {
+            Ok(Object::Stream(stream)) => stream
+                .dict
+                .get(b"BBox")
+                .ok()
+                .and_then(|bbox| Self::numbers(doc, bbox))
+                .unwrap_or(rect),
+            _ => return None,
+        };
+        Some(Placement { rect, bbox, appearance })
+    }
+
+    /// Append the appearances to the page content and drop the flattened widgets
+    fn draw_on_page(
+        doc: &mut Document,
+        page_id: ObjectId,
+        mut page: Dictionary,
+        placements: &[Placement],
+        kept: Vec<Object>,
+    ) -> Result<(), PdfError> {
+        let mut resources = match page.get(b"Resources") {
+            Ok(resources) => match doc.dereference(resources) {
+                Ok((_, Object::Dictionary(resources))) => resources.clone(),
+                _ => Dictionary::new(),
+            },
+            Err(_) => Dictionary::new(),
+        };
+        let mut xobjects = match resources.get(b"XObject") {
+            Ok(xobjects) => match doc.dereference(xobjects) {
+                Ok((_, Object::Dictionary(xobjects))) => xobjects.clone(),
+                _ => Dictionary::new(),
+            },
+            Err(_) => Dictionary::new(),
+        };
+
+        let mut content = String::from("Q\n");
+        for (index, placement) in placements.iter().enumerate() {
+            let name = format!("FlatField{}", index);
+            xobjects.set(name.clone(), Object::Reference(placement.appearance));
+
+            // Map the appearance's bounding box onto the widget rectangle
+            let [x1, y1, x2, y2] = placement.rect;
+            let [bx1, by1, bx2, by2] = placement.bbox;
+            let scale = |span: f64, box_span: f64| if box_span.abs() > f64::EPSILON { span / box_span } else { 1.0 };
+            let sx = scale((x2 - x1).abs(), (bx2 - bx1).abs());
+            let sy = scale((y2 - y1).abs(), (by2 - by1).abs());
+            content.push_str(&format!(
+                "q {:.4} 0 0 {:.4} {:.3} {:.3} cm /{} Do Q\n",
+                sx,
+                sy,
+                x1.min(x2) - bx1.min(bx2) * sx,
+                y1.min(y2) - by1.min(by2) * sy,
+                name
+            ));
+        }
+        resources.set("XObject", Object::Dictionary(xobjects));
+        page.set("Resources", Object::Dictionary(resources));
+
+        // Wrap the original content in q/Q so its graphics state can't leak into ours
+        let mut contents = vec![Object::Reference(doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec())))];
+        match page.get(b"Contents") {
+            Ok(Object::Array(streams)) => contents.extend(streams.iter().cloned()),
+            Ok(stream) => contents.push(stream.clone()),
+            Err(_) => {}
+        }
+        contents.push(Object::Reference(doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()))));
+        page.set("Contents", Object::Array(contents));
+
+        if kept.is_empty() {
+            page.remove(b"Annots");
+        } else {
+            page.set("Annots", Object::Array(kept));
+        }
+        doc.objects.insert(page_id, Object::Dictionary(page));
+        Ok(())
+    }
+
+    /// A form XObject showing `text` in Helvetica, shrunk to fit the field
+    fn text_appearance(
+        text: &str,
+        rect: &[f64; 4],
+        font_size: f64,
+        multiline: bool,
+        alignment: i64,
+        font_id: ObjectId,
+    ) -> Stream {
+        let width = (rect[2] - rect[0]).abs();
+        let height = (rect[3] - rect[1]).abs();
+        let inner_width = (width - 2.0 * PADDING).max(1.0);
+
+        let (size, lines) = if multiline {
+            let mut size = if font_size > 0.0 { font_size } else { 10.0 };
+            loop {
+                let lines = Self::wrap(text, inner_width, size);
+                if lines.len() as f64 * size * 1.15 <= height - PADDING || size <= MIN_FONT_SIZE {
+                    break (size, lines);
+                }
+                size -= 0.5;
+            }
+        } else {
+            let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let mut size = if font_size > 0.0 { font_size } else { 12.0 };
+            size = size.min(height * 0.75);
+            let natural = Self::text_width(&line, size);
+            if natural > inner_width {
+                size = (size * inner_width / natural).max(MIN_FONT_SIZE);
+            }
+            (size, vec![line])
+        };
+
+        let mut content = format!("/Tx BMC q {:.3} {:.3} {:.3} {:.3} re W n BT /Helv {:.2} Tf 0 g\n", PADDING / 2.0, PADDING / 2.0, width - PADDING, height - PADDING, size);
+        let leading = size * 1.15;
+        for (index, line) in lines.iter().enumerate() {
+            let line_width = Self::text_width(line, size);
+            let x = match alignment {
+                1 => (width - line_width) / 2.0,
+                2 => width - PADDING - line_width,
+                _ => PADDING,
+            };
+            let y = if multiline {
+                height - PADDING - size - index as f64 * leading
+            } else {
+                (height - size * 0.7) / 2.0
+            };
+            content.push_str(&format!("1 0 0 1 {:.3} {:.3} Tm ({}) Tj\n", x.max(PADDING / 2.0), y, Self::escape(line)));
+        }
+        content.push_str("ET Q EMC");
+
+        let mut fonts = Dictionary::new();
+        fonts.set("Helv", Object::Reference(font_id));
+        let mut resources = Dictionary::new();
+        resources.set("Font", Object::Dictionary(fonts));
+
+        let mut form = Dictionary::new();
+        form.set("Type", Object::Name(b"XObject".to_vec()));
+        form.set("Subtype", Object::Name(b"Form".to_vec()));
+        form.set("BBox", Object::Array(vec![0.into(), 0.into(), Object::Real(width), Object::Real(height)]));
+        form.set("Resources", Object::Dictionary(resources));
+        Stream::new(form, Self::win_ansi(&content))
+    }
+
+    /// Greedy word wrap; explicit newlines start new lines
+    fn wrap(text: &str, width: f64, size: f64) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+                if !line.is_empty() && Self::text_width(&candidate, size) > width {
+                    lines.push(std::mem::replace(&mut line, word.to_string()));
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Width of `text` in Helvetica at `size` points
+    fn text_width(text: &str, size: f64) -> f64 {
+        text.chars().map(|c| Self::glyph_width(c) as f64).sum::<f64>() * size / 1000.0
+    }
+
+    /// Standard Helvetica advance widths for printable ASCII
+    fn glyph_width(c: char) -> u16 {
+        const WIDTHS: [u16; 95] = [
+            278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // space - /
+            556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // 0 - ?
+            1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // @ - O
+            667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // P - _
+            333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // ` - o
+            556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // p - ~
+        ];
+        match c as u32 {
+            code @ 32..=126 => WIDTHS[(code - 32) as usize],
+            _ => 556,
+        }
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\").replace('(', "\\(").replace(')', "\\)")
+    }
+
+    /// Encode for the WinAnsi font; characters it can't show become '?'
+    fn win_ansi(text: &str) -> Vec<u8> {
+        text.chars()
+            .map(|c| match c {
+                '\u{2018}' | '\u{2019}' => b'\'',
+                '\u{201C}' | '\u{201D}' => b'"',
+                '\u{2013}' | '\u{2014}' => b'-',
+                '\u{2022}' => 0x95,
+                c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u32 as u8,
+                _ => b'?',
+            })
+            .collect()
+    }
+
+    /// Font size from a default appearance string like "/Helvetica 12 Tf 0 g" (0 = auto)
+    fn font_size(default_appearance: &[u8]) -> f64 {
+        let da = String::from_utf8_lossy(default_appearance);
+        let tokens: Vec<&str> = da.split_whitespace().collect();
+        tokens
+            .iter()
+            .position(|token| *token == "Tf")
+            .filter(|&index| index > 0)
+            .and_then(|index| tokens[index - 1].parse().ok())
+            .unwrap_or(0.0)
+    }
+
+    fn helvetica() -> Dictionary {
+        let mut font = Dictionary::new();
+        font.set("Type", Object::Name(b"Font".to_vec()));
+        font.set("Subtype", Object::Name(b"Type1".to_vec()));
+        font.set("BaseFont", Object::Name(b"Helvetica".to_vec()));
+        font.set("Encoding", Object::Name(b"WinAnsiEncoding".to_vec()));
+        font
+    }
+
+    /// A field attribute from the widget or, failing that, its parent fields
+    fn inherited(doc: &Document, widget: &Dictionary, key: &[u8]) -> Option<Object> {
+        let mut dict = widget.clone();
+        for _ in 0..16 {
+            if let Ok(value) = dict.get(key) {
+                return doc.dereference(value).ok().map(|(_, value)| value.clone());
+            }
+            let parent = dict.get(b"Parent").and_then(Object::as_reference).ok()?;
+            dict = doc.get_dictionary(parent).ok()?.clone();
+        }
+        None
+    }
+
+    fn numbers(doc: &Document, object: &Object) -> Option<[f64; 4]> {
+        let array = match doc.dereference(object) {
+            Ok((_, Object::Array(array))) => array,
+            _ => return None,
+        };
+        let values: Vec<f64> = array
+            .iter()
+            .filter_map(|value| match value {
+                Object::Integer(i) => Some(*i as f64),
+                Object::Real(r) => Some(*r),
+                _ => None,
+            })
+            .collect();
+        values.try_into().ok()
+    }
+}
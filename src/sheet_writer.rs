@@ -0,0 +1,112 @@
+use crate::errors::OutputError;
+use std::ffi::OsStr;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Highest `name (N).pdf` suffix tried before giving up
+const MAX_SUFFIX: usize = 999;
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Writes generated files atomically: readers see either nothing or the
+/// complete file, never a half-written PDF
+pub struct SheetWriter;
+
+impl SheetWriter {
+    /// Write `bytes` to `path` and return the path actually written. An
+    /// existing file is replaced only with `overwrite`; otherwise the first
+    /// free `name (2).pdf`, `name (3).pdf`, ... is used
+    pub fn write(path: &Path, bytes: &[u8], overwrite: bool) -> Result<PathBuf, OutputError> {
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        if !dir.is_dir() {
+            return Err(OutputError::DirectoryMissing(dir.display().to_string()));
+        }
+        let file_name = path.file_name().unwrap_or(OsStr::new("sheet.pdf"));
+
+        let temp = Self::write_temp(dir, file_name, bytes)?;
+        let result = if overwrite {
+            fs::rename(&temp, path)
+                .map(|_| path.to_path_buf())
+                .map_err(|e| write_failed(path, e))
+        } else {
+            Self::link_unused_name(&temp, path)
+        };
+        // Already gone after a rename; left behind by a failed or linked write
+        let _ = fs::remove_file(&temp);
+        result
+    }
+
+    /// `sheet.pdf` -> `sheet (2).pdf`
+    pub fn numbered(path: &Path, number: usize) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(ext) => format!("{} ({}).{}", stem, number, ext.to_string_lossy()),
+            None => format!("{} ({})", stem, number),
+        };
+        path.with_file_name(name)
+    }
+
+    /// Synced hidden temp file next to the target, so the final rename or
+    /// link stays on one filesystem
+    fn write_temp(dir: &Path, file_name: &OsStr, bytes: &[u8]) -> Result<PathBuf, OutputError> {
+        let temp = dir.join(format!(
+            ".{}.{}-{}.tmp",
+            file_name.to_string_lossy(),
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)
+            .map_err(|e| match e.kind() {
+                ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => OutputError::NotWritable {
+                    path: dir.display().to_string(),
+                    message: e.to_string(),
+                },
+                _ => write_failed(&temp, e),
+            })?;
+        let written = file.write_all(bytes).and_then(|_| file.sync_all());
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp);
+            return Err(write_failed(&temp, e));
+        }
+        Ok(temp)
+    }
+
+    /// Hard links never replace an existing file, so two writers racing for
+    /// the same name can't clobber each other
+    fn link_unused_name(temp: &Path, path: &Path) -> Result<PathBuf, OutputError> {
+        for number in 1..=MAX_SUFFIX {
+            let candidate = match number {
+                1 => path.to_path_buf(),
+                n => Self::numbered(path, n),
+            };
+            match fs::hard_link(temp, &candidate) {
+                Ok(()) => return Ok(candidate),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                // Filesystems without hard links: check, then rename
+                Err(_) if candidate.exists() => continue,
+                Err(_) => {
+                    return fs::rename(temp, &candidate)
+                        .map(|_| candidate.clone())
+                        .map_err(|e| write_failed(&candidate, e))
+                }
+            }
+        }
+        Err(OutputError::NoFreeName(path.display().to_string()))
+    }
+}
+
+fn write_failed(path: &Path, e: std::io::Error) -> OutputError {
+    OutputError::WriteFailed {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    }
+}
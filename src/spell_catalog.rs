@@ -0,0 +1,139 @@
+use serde::Serialize;
+
+/// SRD spell reference data exposed as MCP resources
+#[derive(Debug, Clone, Serialize)]
+pub struct SpellInfo {
+    pub name: &'static str,
+    /// Spell level, 0 for cantrips
+    pub level: u8,
+    pub school: &'static str,
+    pub casting_time: &'static str,
+    pub range: &'static str,
+    pub components: &'static str,
+    pub duration: &'static str,
+    pub concentration: bool,
+    pub ritual: bool,
+    pub classes: &'static [&'static str],
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn spell(
+    name: &'static str,
+    level: u8,
+    school: &'static str,
+    casting_time: &'static str,
+    range: &'static str,
+    components: &'static str,
+    duration: &'static str,
+    classes: &'static [&'static str],
+) -> SpellInfo {
+    SpellInfo {
+        name,
+        level,
+        school,
+        casting_time,
+        range,
+        components,
+        duration,
+        concentration: false,
+        ritual: false,
+        classes,
+    }
+}
+
+const fn concentration(info: SpellInfo) -> SpellInfo {
+    SpellInfo {
+        concentration: true,
+        ..info
+    }
+}
+
+const fn ritual(info: SpellInfo) -> SpellInfo {
+    SpellInfo {
+        ritual: true,
+        ..info
+    }
+}
+
+const ACTION: &str = "1 action";
+const BONUS_ACTION: &str = "1 bonus action";
+const REACTION: &str = "1 reaction";
+
+const SPELLS: &[SpellInfo] = &[
+    // Cantrips
+    spell("Acid Splash", 0, "Conjuration", ACTION, "60 feet", "V, S", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Eldritch Blast", 0, "Evocation", ACTION, "120 feet", "V, S", "Instantaneous", &["Warlock"]),
+    spell("Fire Bolt", 0, "Evocation", ACTION, "120 feet", "V, S", "Instantaneous", &["Sorcerer", "Wizard"]),
+    concentration(spell("Guidance", 0, "Divination", ACTION, "Touch", "V, S", "Up to 1 minute", &["Cleric", "Druid"])),
+    spell("Light", 0, "Evocation", ACTION, "Touch", "V, M", "1 hour", &["Bard", "Cleric", "Sorcerer", "Wizard"]),
+    spell("Mage Hand", 0, "Conjuration", ACTION, "30 feet", "V, S", "1 minute", &["Bard", "Sorcerer", "Warlock", "Wizard"]),
+    spell("Minor Illusion", 0, "Illusion", ACTION, "30 feet", "S, M", "1 minute", &["Bard", "Sorcerer", "Warlock", "Wizard"]),
+    spell("Prestidigitation", 0, "Transmutation", ACTION, "10 feet", "V, S", "Up to 1 hour", &["Bard", "Sorcerer", "Warlock", "Wizard"]),
+    spell("Ray of Frost", 0, "Evocation", ACTION, "60 feet", "V, S", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Sacred Flame", 0, "Evocation", ACTION, "60 feet", "V, S", "Instantaneous", &["Cleric"]),
+    spell("Shocking Grasp", 0, "Evocation", ACTION, "Touch", "V, S", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Thaumaturgy", 0, "Transmutation", ACTION, "30 feet", "V", "Up to 1 minute", &["Cleric"]),
+    spell("Vicious Mockery", 0, "Enchantment", ACTION, "60 feet", "V", "Instantaneous", &["Bard"]),
+    // 1st level
+    concentration(spell("Bless", 1, "Enchantment", ACTION, "30 feet", "V, S, M", "Up to 1 minute", &["Cleric", "Paladin"])),
+    spell("Burning Hands", 1, "Evocation", ACTION, "Self (15-foot cone)", "V, S", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Charm Person", 1, "Enchantment", ACTION, "30 feet", "V, S", "1 hour", &["Bard", "Druid", "Sorcerer", "Warlock", "Wizard"]),
+    spell("Cure Wounds", 1, "Evocation", ACTION, "Touch", "V, S", "Instantaneous", &["Bard", "Cleric", "Druid", "Paladin", "Ranger"]),
+    ritual(spell("Detect Magic", 1, "Divination", ACTION, "Self", "V, S", "Up to 10 minutes", &["Bard", "Cleric", "Druid", "Paladin", "Ranger", "Sorcerer", "Wizard"])),
+    concentration(spell("Faerie Fire", 1, "Evocation", ACTION, "60 feet", "V", "Up to 1 minute", &["Bard", "Druid"])),
+    spell("Guiding Bolt", 1, "Evocation", ACTION, "120 feet", "V, S", "1 round", &["Cleric"]),
+    spell("Healing Word", 1, "Evocation", BONUS_ACTION, "60 feet", "V", "Instantaneous", &["Bard", "Cleric", "Druid"]),
+    concentration(spell("Hunter's Mark", 1, "Divination", BONUS_ACTION, "90 feet", "V", "Up to 1 hour", &["Ranger"])),
+    ritual(spell("Identify", 1, "Divination", "1 minute", "Touch", "V, S, M", "Instantaneous", &["Bard", "Wizard"])),
+    spell("Mage Armor", 1, "Abjuration", ACTION, "Touch", "V, S, M", "8 hours", &["Sorcerer", "Wizard"]),
+    spell("Magic Missile", 1, "Evocation", ACTION, "120 feet", "V, S", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Shield", 1, "Abjuration", REACTION, "Self", "V, S", "1 round", &["Sorcerer", "Wizard"]),
+    spell("Sleep", 1, "Enchantment", ACTION, "90 feet", "V, S, M", "1 minute", &["Bard", "Sorcerer", "Wizard"]),
+    spell("Thunderwave", 1, "Evocation", ACTION, "Self (15-foot cube)", "V, S", "Instantaneous", &["Bard", "Druid", "Sorcerer", "Wizard"]),
+    // 2nd level
+    spell("Aid", 2, "Abjuration", ACTION, "30 feet", "V, S, M", "8 hours", &["Cleric", "Paladin"]),
+    concentration(spell("Hold Person", 2, "Enchantment", ACTION, "60 feet", "V, S, M", "Up to 1 minute", &["Bard", "Cleric", "Druid", "Sorcerer", "Warlock", "Wizard"])),
+    concentration(spell("Invisibility", 2, "Illusion", ACTION, "Touch", "V, S, M", "Up to 1 hour", &["Bard", "Sorcerer", "Warlock", "Wizard"])),
+    spell("Lesser Restoration", 2, "Abjuration", ACTION, "Touch", "V, S", "Instantaneous", &["Bard", "Cleric", "Druid", "Paladin", "Ranger"]),
+    spell("Misty Step", 2, "Conjuration", BONUS_ACTION, "Self", "V", "Instantaneous", &["Sorcerer", "Warlock", "Wizard"]),
+    spell("Scorching Ray", 2, "Evocation", ACTION, "120 feet", "V, S", "Instantaneous", &["Sorcerer", "Wizard"]),
+    concentration(spell("Spiritual Weapon", 2, "Evocation", BONUS_ACTION, "60 feet", "V, S", "1 minute", &["Cleric"])),
+    // 3rd level
+    spell("Counterspell", 3, "Abjuration", REACTION, "60 feet", "S", "Instantaneous", &["Sorcerer", "Warlock", "Wizard"]),
+    spell("Dispel Magic", 3, "Abjuration", ACTION, "120 feet", "V, S", "Instantaneous", &["Bard", "Cleric", "Druid", "Paladin", "Sorcerer", "Warlock", "Wizard"]),
+    spell("Fireball", 3, "Evocation", ACTION, "150 feet", "V, S, M", "Instantaneous", &["Sorcerer", "Wizard"]),
+    concentration(spell("Fly", 3, "Transmutation", ACTION, "Touch", "V, S, M", "Up to 10 minutes", &["Sorcerer", "Warlock", "Wizard"])),
+    concentration(spell("Haste", 3, "Transmutation", ACTION, "30 feet", "V, S, M", "Up to 1 minute", &["Sorcerer", "Wizard"])),
+    spell("Lightning Bolt", 3, "Evocation", ACTION, "Self (100-foot line)", "V, S, M", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Revivify", 3, "Necromancy", ACTION, "Touch", "V, S, M", "Instantaneous", &["Cleric", "Paladin"]),
+    concentration(spell("Spirit Guardians", 3, "Conjuration", ACTION, "Self (15-foot radius)", "V, S, M", "Up to 10 minutes", &["Cleric"])),
+    // 4th level
+    concentration(spell("Banishment", 4, "Abjuration", ACTION, "60 feet", "V, S, M", "Up to 1 minute", &["Cleric", "Paladin", "Sorcerer", "Warlock", "Wizard"])),
+    concentration(spell("Greater Invisibility", 4, "Illusion", ACTION, "Touch", "V, S", "Up to 1 minute", &["Bard", "Sorcerer", "Wizard"])),
+    concentration(spell("Polymorph", 4, "Transmutation", ACTION, "60 feet", "V, S, M", "Up to 1 hour", &["Bard", "Druid", "Sorcerer", "Wizard"])),
+    // 5th level
+    spell("Cone of Cold", 5, "Evocation", ACTION, "Self (60-foot cone)", "V, S, M", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Mass Cure Wounds", 5, "Evocation", ACTION, "60 feet", "V, S", "Instantaneous", &["Bard", "Cleric", "Druid"]),
+    spell("Raise Dead", 5, "Necromancy", "1 hour", "Touch", "V, S, M", "Instantaneous", &["Bard", "Cleric", "Paladin"]),
+    // 6th level and higher
+    spell("Chain Lightning", 6, "Evocation", ACTION, "150 feet", "V, S, M", "Instantaneous", &["Sorcerer", "Wizard"]),
+    spell("Heal", 6, "Evocation", ACTION, "60 feet", "V, S", "Instantaneous", &["Cleric", "Druid"]),
+    spell("Teleport", 7, "Conjuration", ACTION, "10 feet", "V", "Instantaneous", &["Bard", "Sorcerer", "Wizard"]),
+    spell("Sunburst", 8, "Evocation", ACTION, "150 feet", "V, S, M", "Instantaneous", &["Druid", "Sorcerer", "Wizard"]),
+    spell("Wish", 9, "Conjuration", ACTION, "Self", "V", "Instantaneous", &["Sorcerer", "Wizard"]),
+];
+
+pub struct SpellCatalog;
+
+impl SpellCatalog {
+    /// Look up a spell by name (case-insensitive)
+    pub fn get(name: &str) -> Option<&'static SpellInfo> {
+        SPELLS
+            .iter()
+            .find(|info| info.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn all() -> &'static [SpellInfo] {
+        SPELLS
+    }
+}
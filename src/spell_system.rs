@@ -1,4 +1,6 @@
 use crate::character_model::{Spell, Spells};
+use crate::class_catalog::ClassCatalog;
+use crate::logging::Logger;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -12,7 +14,6 @@ pub enum CasterType {
 pub struct SpellSystem;
 
 impl SpellSystem {
-    #[allow(dead_code)]
     pub fn organize_spells_by_level(spells: &Spells) -> Vec<(u8, Vec<&Spell>)> {
         let mut organized = Vec::new();
 
@@ -158,14 +159,14 @@ impl SpellSystem {
         slots
     }
 
-    /// Determine caster type from character class
+    /// Determine caster type from character class, as listed in the class catalog
     pub fn get_caster_type(class: &str) -> CasterType {
-        match class.to_lowercase().as_str() {
-            "wizard" | "sorcerer" | "cleric" | "druid" | "bard" => CasterType::Full,
-            "paladin" | "ranger" => CasterType::Half,
-            "fighter" | "rogue" => CasterType::Third, // Assumes Eldritch Knight/Arcane Trickster
-            _ => CasterType::None,
-        }
+        ClassCatalog::get(class).map_or(CasterType::None, |info| info.caster_type)
+    }
+
+    /// Slot level of a Warlock's Pact Magic slots, which rises to 5th at level 9
+    pub fn pact_slot_level(level: u8) -> u8 {
+        level.clamp(1, 9).div_ceil(2)
     }
 
     /// Calculate spell slots for a single-class character
@@ -177,7 +178,7 @@ impl SpellSystem {
         
         // Log warning for unknown classes that default to non-caster
         if caster_type == CasterType::None && !Self::is_known_non_caster(&character.character.class) {
-            eprintln!("Warning: Unknown character class '{}' treated as non-spellcaster", character.character.class);
+            Logger::warning("spell_system", &format!("Unknown character class '{}' treated as non-spellcaster", character.character.class));
         }
         
         // Get spell slots from appropriate progression table
@@ -203,8 +204,8 @@ impl SpellSystem {
     /// Get spell slots for single-class character based on level and caster type
     pub fn get_spell_slots_for_single_class(level: u8, caster_type: CasterType) -> HashMap<u8, u8> {
         // Validate level range
-        if level < 1 || level > 20 {
-            eprintln!("Warning: Character level {} is outside valid range (1-20), clamping", level);
+        if !(1..=20).contains(&level) {
+            Logger::warning("spell_system", &format!("Character level {} is outside valid range (1-20), clamping", level));
         }
         
         let clamped_level = level.clamp(1, 20);
@@ -221,7 +222,7 @@ impl SpellSystem {
             let slot_summary: Vec<String> = (1..=9)
                 .filter_map(|level| slots.get(&level).map(|count| format!("{}:{}", level, count)))
                 .collect();
-            eprintln!("Spell slots calculated: {} (level {}, {:?})", slot_summary.join("/"), clamped_level, caster_type);
+            Logger::debug("spell_system", &format!("Spell slots calculated: {} (level {}, {:?})", slot_summary.join("/"), clamped_level, caster_type));
         }
         
         slots
@@ -0,0 +1,96 @@
+use serde::Serialize;
+
+/// SRD weapon reference data, used to turn inventory weapons into attack rows
+#[derive(Debug, Clone, Serialize)]
+pub struct WeaponInfo {
+    pub name: &'static str,
+    /// "Simple" or "Martial"
+    pub category: &'static str,
+    pub ranged: bool,
+    /// e.g. "1d8"
+    pub damage: &'static str,
+    pub damage_type: &'static str,
+    pub properties: &'static [&'static str],
+}
+
+const fn weapon(
+    name: &'static str,
+    category: &'static str,
+    ranged: bool,
+    damage: &'static str,
+    damage_type: &'static str,
+    properties: &'static [&'static str],
+) -> WeaponInfo {
+    WeaponInfo {
+        name,
+        category,
+        ranged,
+        damage,
+        damage_type,
+        properties,
+    }
+}
+
+const SIMPLE: &str = "Simple";
+const MARTIAL: &str = "Martial";
+
+const WEAPONS: &[WeaponInfo] = &[
+    weapon("Club", SIMPLE, false, "1d4", "bludgeoning", &["Light"]),
+    weapon("Dagger", SIMPLE, false, "1d4", "piercing", &["Finesse", "Light", "Thrown (20/60)"]),
+    weapon("Greatclub", SIMPLE, false, "1d8", "bludgeoning", &["Two-handed"]),
+    weapon("Handaxe", SIMPLE, false, "1d6", "slashing", &["Light", "Thrown (20/60)"]),
+    weapon("Javelin", SIMPLE, false, "1d6", "piercing", &["Thrown (30/120)"]),
+    weapon("Light Hammer", SIMPLE, false, "1d4", "bludgeoning", &["Light", "Thrown (20/60)"]),
+    weapon("Mace", SIMPLE, false, "1d6", "bludgeoning", &[]),
+    weapon("Quarterstaff", SIMPLE, false, "1d6", "bludgeoning", &["Versatile (1d8)"]),
+    weapon("Sickle", SIMPLE, false, "1d4", "slashing", &["Light"]),
+    weapon("Spear", SIMPLE, false, "1d6", "piercing", &["Thrown (20/60)", "Versatile (1d8)"]),
+    weapon("Light Crossbow", SIMPLE, true, "1d8", "piercing", &["Ammunition (80/320)", "Loading", "Two-handed"]),
+    weapon("Dart", SIMPLE, true, "1d4", "piercing", &["Finesse", "Thrown (20/60)"]),
+    weapon("Shortbow", SIMPLE, true, "1d6", "piercing", &["Ammunition (80/320)", "Two-handed"]),
+    weapon("Sling", SIMPLE, true, "1d4", "bludgeoning", &["Ammunition (30/120)"]),
+    weapon("Battleaxe", MARTIAL, false, "1d8", "slashing", &["Versatile (1d10)"]),
+    weapon("Flail", MARTIAL, false, "1d8", "bludgeoning", &[]),
+    weapon("Glaive", MARTIAL, false, "1d10", "slashing", &["Heavy", "Reach", "Two-handed"]),
+    weapon("Greataxe", MARTIAL, false, "1d12", "slashing", &["Heavy", "Two-handed"]),
+    weapon("Greatsword", MARTIAL, false, "2d6", "slashing", &["Heavy", "Two-handed"]),
+    weapon("Halberd", MARTIAL, false, "1d10", "slashing", &["Heavy", "Reach", "Two-handed"]),
+    weapon("Lance", MARTIAL, false, "1d12", "piercing", &["Reach", "Special"]),
+    weapon("Longsword", MARTIAL, false, "1d8", "slashing", &["Versatile (1d10)"]),
+    weapon("Maul", MARTIAL, false, "2d6", "bludgeoning", &["Heavy", "Two-handed"]),
+    weapon("Morningstar", MARTIAL, false, "1d8", "piercing", &[]),
+    weapon("Pike", MARTIAL, false, "1d10", "piercing", &["Heavy", "Reach", "Two-handed"]),
+    weapon("Rapier", MARTIAL, false, "1d8", "piercing", &["Finesse"]),
+    weapon("Scimitar", MARTIAL, false, "1d6", "slashing", &["Finesse", "Light"]),
+    weapon("Shortsword", MARTIAL, false, "1d6", "piercing", &["Finesse", "Light"]),
+    weapon("Trident", MARTIAL, false, "1d6", "piercing", &["Thrown (20/60)", "Versatile (1d8)"]),
+    weapon("War Pick", MARTIAL, false, "1d8", "piercing", &[]),
+    weapon("Warhammer", MARTIAL, false, "1d8", "bludgeoning", &["Versatile (1d10)"]),
+    weapon("Whip", MARTIAL, false, "1d4", "slashing", &["Finesse", "Reach"]),
+    weapon("Blowgun", MARTIAL, true, "1", "piercing", &["Ammunition (25/100)", "Loading"]),
+    weapon("Hand Crossbow", MARTIAL, true, "1d6", "piercing", &["Ammunition (30/120)", "Light", "Loading"]),
+    weapon("Heavy Crossbow", MARTIAL, true, "1d10", "piercing", &["Ammunition (100/400)", "Heavy", "Loading", "Two-handed"]),
+    weapon("Longbow", MARTIAL, true, "1d8", "piercing", &["Ammunition (150/600)", "Heavy", "Two-handed"]),
+];
+
+pub struct WeaponCatalog;
+
+impl WeaponCatalog {
+    /// Look up a weapon by name (case-insensitive); plurals such as
+    /// "Javelins" and "Light crossbows" match too
+    pub fn get(name: &str) -> Option<&'static WeaponInfo> {
+        let name = name.trim();
+        let singular = name.strip_suffix('s').unwrap_or(name);
+        WEAPONS
+            .iter()
+            .find(|info| info.name.eq_ignore_ascii_case(name) || info.name.eq_ignore_ascii_case(singular))
+    }
+
+    pub fn all() -> &'static [WeaponInfo] {
+        WEAPONS
+    }
+
+    pub fn is_finesse(weapon: &WeaponInfo) -> bool {
+        weapon.properties.contains(&"Finesse")
+    }
+}
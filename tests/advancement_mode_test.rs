@@ -0,0 +1,92 @@
+use dnd_character_sheet_filler::character_model::{AdvancementMode, CharacterData};
+use dnd_character_sheet_filler::character_store::CharacterStore;
+use dnd_character_sheet_filler::dnd_validator::DndValidator;
+use dnd_character_sheet_filler::fight_club_exporter::FightClubExporter;
+use dnd_character_sheet_filler::markdown_exporter::MarkdownExporter;
+use dnd_character_sheet_filler::markdown_importer::MarkdownImporter;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use dnd_character_sheet_filler::pdf_filler::PdfFiller;
+use dnd_character_sheet_filler::session_log::{Session, SessionLog};
+use serde_json::{json, Value};
+
+mod common;
+use common::{fixture, fresh_dir, tool_response};
+
+/// Elara at level 5 with far too little XP for it, as milestone campaigns leave it
+fn milestone_elara() -> CharacterData {
+    let mut elara = fixture("elara");
+    elara.character.experience_points = Some(300);
+    elara.character.advancement_mode = Some(AdvancementMode::Milestone);
+    elara
+}
+
+#[test]
+fn test_milestone_mode_skips_only_the_xp_check() {
+    let mut elara = milestone_elara();
+    assert!(DndValidator::new(false).validate(&elara).unwrap().is_empty());
+    let errors = DndValidator::new(false)
+        .with_advancement_mode(Some(AdvancementMode::Xp))
+        .validate(&elara)
+        .unwrap_err();
+    assert_eq!(errors[0].to_string(), "Experience points 300 insufficient for level 5 (requires 6500)");
+
+    // Other rules still apply in milestone mode
+    elara.abilities.strength = 25;
+    let errors = DndValidator::new(false).validate(&elara).unwrap_err();
+    assert!(errors.iter().any(|error| error.to_string().contains("Strength")));
+    assert!(errors.iter().all(|error| !error.to_string().contains("Experience")));
+    elara.abilities.strength = 8;
+
+    elara.character.experience_points = Some(20000);
+    assert!(!DndValidator::ready_to_level_up(&elara));
+    let fields = PdfFiller::new(false).get_field_values(&elara);
+    assert!(!fields.contains_key("ExperiencePoints"));
+
+    let markdown = MarkdownExporter::export(&elara);
+    assert!(markdown.contains("| Leveling | Milestone |"));
+    assert!(!markdown.contains("20000"));
+    let imported = MarkdownImporter::parse(&markdown).unwrap().character_data;
+    assert_eq!(imported.character.advancement_mode, Some(AdvancementMode::Milestone));
+    let xml = FightClubExporter::export(&elara);
+    assert!(xml.contains("<advancement>milestone</advancement>") && !xml.contains("<xp>"));
+    let imported = FightClubExporter::import(&xml).unwrap();
+    assert_eq!(imported.character.advancement_mode, Some(AdvancementMode::Milestone));
+
+    let dir = fresh_dir("dnd_advancement_mode_store_test");
+    let store = CharacterStore::new(&dir);
+    store.save(Some("elara"), &elara).unwrap();
+    let mut session = Session {
+        number: 1,
+        date: "2026-03-14".to_string(),
+        party: None,
+        attendees: vec!["elara".to_string()],
+        experience_points: 7500,
+        loot: Vec::new(),
+        milestones: vec!["Cleared Wave Echo Cave".to_string()],
+        notes: None,
+        applied: false,
+    };
+    SessionLog::prepare(&store, &mut session).unwrap();
+    store.save_session(&session).unwrap();
+    let (awards, _) = SessionLog::apply(&store, 1).unwrap();
+    assert_eq!((awards[0].experience_points, awards[0].next_level_at), (20000, None));
+}
+
+#[test]
+fn test_fill_tool_takes_an_advancement_mode() {
+    let dir = fresh_dir("dnd_advancement_mode_tool_test");
+    let server = McpServer::with_output_dir(&dir);
+    let mut elara = serde_json::to_value(milestone_elara()).unwrap();
+    elara["character"]["advancement_mode"] = Value::Null;
+    let call = |arguments: Value| tool_response(&server, "fill_dnd_character_sheet", arguments);
+
+    let failed = call(json!({"character_data": elara, "output_path": "elara.pdf"}));
+    assert_eq!(failed["result"]["structuredContent"]["success"], false);
+    let filled = call(json!({"character_data": elara, "output_path": "elara.pdf", "advancement_mode": "milestone"}));
+    assert_eq!(filled["result"]["structuredContent"]["success"], true);
+    assert_eq!(filled["result"]["structuredContent"]["rule_violations_overridden"], false);
+    assert!(dir.join("elara.pdf").is_file());
+
+    let invalid = call(json!({"character_data": elara, "advancement_mode": "story"}));
+    assert_eq!(invalid["error"]["code"], -32602);
+}
@@ -0,0 +1,127 @@
+use dnd_character_sheet_filler::character_store::CharacterStore;
+use dnd_character_sheet_filler::errors::StoreError;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::json;
+
+mod common;
+use common::{call, fixture, fresh_dir, request};
+
+#[test]
+fn test_store_keeps_a_snapshot_per_save() {
+    let dir = fresh_dir("dnd_character_store_test");
+    let store = CharacterStore::new(dir.join("characters"));
+    assert!(store.list().unwrap().is_empty());
+
+    let mut character = fixture("elara");
+    let saved = store.save(None, &character).unwrap();
+    assert_eq!((saved.id.as_str(), saved.version), ("elara-moonwhisper", 1));
+    assert!(saved.previous.is_none());
+    character.character.level = 6;
+    let saved = store.save(None, &character).unwrap();
+    assert_eq!((saved.id.as_str(), saved.version), ("elara-moonwhisper", 2));
+    assert_eq!(saved.previous.unwrap().character.level, 5);
+
+    assert_eq!(store.versions("elara-moonwhisper").unwrap(), [1, 2]);
+    assert!(dir.join("characters/elara-moonwhisper.json").is_file());
+    assert!(dir.join("characters/snapshots/elara-moonwhisper/0001.json").is_file());
+    let (current, version) = store.load("elara-moonwhisper", None).unwrap();
+    assert_eq!((current.character.level, version), (6, 2));
+    let (first, version) = store.load("elara-moonwhisper", Some(1)).unwrap();
+    assert_eq!((first.character.level, version), (5, 1));
+
+    let listed = store.list().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!((listed[0].name.as_str(), listed[0].level, listed[0].latest_version), ("Elara Moonwhisper", 6, 2));
+
+    assert_eq!(
+        store.load("elara-moonwhisper", Some(3)).unwrap_err(),
+        StoreError::VersionNotFound { id: "elara-moonwhisper".to_string(), version: 3 }
+    );
+    assert_eq!(store.load("../secrets", None).unwrap_err(), StoreError::InvalidId("../secrets".to_string()));
+    assert_eq!(store.delete("elara-moonwhisper").unwrap(), 2);
+    assert_eq!(store.load("elara-moonwhisper", None).unwrap_err(), StoreError::NotFound("elara-moonwhisper".to_string()));
+    assert!(store.list().unwrap().is_empty());
+}
+
+#[test]
+fn test_store_tools_report_changes_between_versions() {
+    let dir = fresh_dir("dnd_character_store_tool_test");
+    let server = McpServer::with_output_dir(&dir);
+
+    let before = serde_json::to_value(fixture("elara")).unwrap();
+    let saved = call(&server, "save_character", json!({"character_data": before, "id": "elara"}));
+    assert_eq!(saved["structuredContent"], json!({"id": "elara", "version": 1, "changes": []}));
+
+    let mut after = before.clone();
+    after["character"]["level"] = json!(6);
+    after["character"]["experience_points"] = json!(14000);
+    after["spells"]["first_level"][2]["prepared"] = json!(true);
+    after["spells"]["third_level"].as_array_mut().unwrap().push(json!({"name": "Hypnotic Pattern", "level": 3, "prepared": true}));
+    after["equipment"]["items"] = json!("Quarterstaff, Spellbook, Component pouch, Rope (50 feet)");
+    after["narrative"] = json!({"bonds": "The Tower of Stars"});
+    let saved = call(&server, "save_character", json!({"character_data": after, "id": "elara"}));
+    assert_eq!(saved["structuredContent"]["version"], 2);
+
+    let expected = json!([
+        "Character / Experience Points: 6500 → 14000",
+        "Character / Level: 5 → 6",
+        "Equipment / Items: added Rope (50 feet); removed Potion of Healing (2)",
+        "Narrative / Bonds: set to \"The Tower of Stars\"",
+        "Spells / First Level / Sleep / Prepared: no → yes",
+        "Spells / Third Level: added Hypnotic Pattern"
+    ]);
+    assert_eq!(saved["structuredContent"]["changes"], expected);
+    let diff = call(&server, "character_diff", json!({"id": "elara"}));
+    assert_eq!(diff["structuredContent"], json!({"id": "elara", "from": 1, "to": 2, "changes": expected}));
+    let diff = call(&server, "character_diff", json!({"id": "elara", "from": 2, "to": 1}));
+    assert_eq!(diff["structuredContent"]["changes"][1], "Character / Level: 6 → 5");
+
+    let loaded = call(&server, "load_character", json!({"id": "elara", "version": 1}));
+    assert_eq!(loaded["structuredContent"]["character_data"]["character"]["level"], 5);
+    let listed = call(&server, "list_characters", json!({}));
+    assert_eq!(listed["structuredContent"]["characters"][0]["id"], "elara");
+    assert_eq!(listed["structuredContent"]["store_dir"], dir.join("characters").display().to_string());
+    // Arguments can be left out altogether
+    let listed_bare = request(&server, "tools/call", json!({"name": "list_characters"}));
+    assert_eq!(listed_bare["result"]["structuredContent"], listed["structuredContent"]);
+
+    let deleted = call(&server, "delete_character", json!({"id": "elara"}));
+    assert_eq!(deleted["structuredContent"], json!({"id": "elara", "deleted_snapshots": 2}));
+    let missing = call(&server, "load_character", json!({"id": "elara"}));
+    assert_eq!(missing["isError"], true);
+    assert_eq!(missing["content"][0]["text"], "Error: No saved character with id 'elara'");
+}
+
+#[test]
+fn test_saves_never_share_a_version_or_a_name() {
+    let dir = fresh_dir("dnd_character_store_race_test");
+    // Separate stores share no lock, like two server processes on one directory
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let dir = dir.clone();
+            std::thread::spawn(move || {
+                let store = CharacterStore::new(dir);
+                (0..5).map(|_| store.save(Some("elara"), &fixture("elara")).unwrap().version).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut versions: Vec<u32> = threads.into_iter().flat_map(|thread| thread.join().unwrap()).collect();
+    versions.sort_unstable();
+    assert_eq!(versions, (1..=40).collect::<Vec<_>>());
+    let store = CharacterStore::new(&dir);
+    assert_eq!(store.versions("elara").unwrap(), versions);
+    assert_eq!(std::fs::read_dir(dir.join("snapshots/elara")).unwrap().count(), 40);
+
+    let mut bob = fixture("grommash");
+    bob.character.name = "Bob".to_string();
+    store.save(None, &bob).unwrap();
+    bob.character.level = 7;
+    assert_eq!(store.save(None, &bob).unwrap().version, 2);
+    let mut other_bob = fixture("elara");
+    other_bob.character.name = "Bob".to_string();
+    assert_eq!(
+        store.save(None, &other_bob).unwrap_err(),
+        StoreError::IdTaken { id: "bob".to_string(), name: "Bob".to_string() }
+    );
+    assert_eq!(store.save(Some("bob-the-wizard"), &other_bob).unwrap().version, 1);
+}
@@ -0,0 +1,164 @@
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+mod common;
+use common::fresh_dir;
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dnd-character-sheet-filler"))
+        .args(args)
+        .env_remove("DND_MCP_CONFIG")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_cli_validate_and_fill() {
+    let dir = fresh_dir("dnd_cli_test");
+
+    let mut character: Value = serde_json::from_str(&fs::read_to_string("docs/test-data.json").unwrap()).unwrap();
+    let valid = dir.join("erilon.json");
+    fs::write(&valid, character.to_string()).unwrap();
+    character["abilities"]["strength"] = 25.into();
+    let invalid = dir.join("broken.json");
+    fs::write(&invalid, character.to_string()).unwrap();
+
+    let output = run(&["validate", valid.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is valid"));
+
+    let output = run(&["validate", invalid.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Strength score 25"));
+
+    // Config supplies the output directory; the file name comes from the JSON file
+    let config = dir.join("dnd-mcp.toml");
+    fs::write(&config, "output_dir = \"out\"\n").unwrap();
+    fs::create_dir_all(dir.join("out")).unwrap();
+    let output = run(&["--config", config.to_str().unwrap(), "fill", valid.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let sheet = dir.join("out/erilon.pdf");
+    assert!(sheet.is_file());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), sheet.to_str().unwrap());
+
+    let output = run(&["read", sheet.to_str().unwrap()]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("= 'Erilon"));
+
+    let output = run(&["--config", config.to_str().unwrap(), "fill", invalid.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--allow-violations"));
+    let output = run(&[
+        "--config",
+        config.to_str().unwrap(),
+        "fill",
+        invalid.to_str().unwrap(),
+        "--allow-violations",
+        "-o",
+        "forced.pdf",
+    ]);
+    assert!(output.status.success());
+    assert!(dir.join("out/forced.pdf").is_file());
+
+    // An explicit -o may lead outside the output directory, which the MCP tools refuse
+    let outside = fresh_dir("dnd_cli_test_outside");
+    for requested in [outside.join("erilon.pdf").display().to_string(), "../erilon.pdf".to_string()] {
+        let output = run(&["--config", config.to_str().unwrap(), "fill", valid.to_str().unwrap(), "-o", &requested]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(outside.join("erilon.pdf").is_file());
+    assert!(dir.join("erilon.pdf").is_file());
+
+    // One character into a -o directory is named after its file, as several would be
+    fs::create_dir_all(dir.join("out/single")).unwrap();
+    for requested in ["single", "single/"] {
+        let output = run(&["--config", config.to_str().unwrap(), "fill", valid.to_str().unwrap(), "-o", requested, "--overwrite"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), dir.join("out/single/erilon.pdf").to_str().unwrap());
+    }
+    let output = run(&["--config", config.to_str().unwrap(), "fill", valid.to_str().unwrap(), "-o", "missing/"]);
+    assert!(!output.status.success());
+    assert!(!dir.join("out/missing").exists());
+}
+
+#[test]
+fn test_cli_fills_a_party_in_parallel() {
+    let dir = fresh_dir("dnd_cli_batch_test");
+    fs::create_dir_all(dir.join("party")).unwrap();
+    fs::create_dir_all(dir.join("out/sheets")).unwrap();
+    let config = dir.join("dnd-mcp.toml");
+    fs::write(&config, "output_dir = \"out\"\n").unwrap();
+
+    let erilon: Value = serde_json::from_str(&fs::read_to_string("docs/test-data.json").unwrap()).unwrap();
+    let mut brakka = erilon.clone();
+    brakka["character"]["name"] = "Brakka Stonefist".into();
+    let mut broken = erilon.clone();
+    broken["abilities"]["strength"] = 25.into();
+    fs::write(dir.join("party/erilon.json"), erilon.to_string()).unwrap();
+    fs::write(dir.join("party/brakka.json"), brakka.to_string()).unwrap();
+    fs::write(dir.join("party/notes.txt"), "not a character").unwrap();
+
+    // A whole directory, flattened, into an output subdirectory
+    let output = run(&[
+        "--config",
+        config.to_str().unwrap(),
+        "fill",
+        dir.join("party").to_str().unwrap(),
+        "-o",
+        "sheets",
+        "--flatten",
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let written: Vec<String> = String::from_utf8_lossy(&output.stdout).lines().map(String::from).collect();
+    assert_eq!(
+        written,
+        [
+            dir.join("out/sheets/brakka.pdf").display().to_string(),
+            dir.join("out/sheets/erilon.pdf").display().to_string(),
+        ]
+    );
+
+    // Flattened sheets have no form left, and the values are drawn into the page
+    let doc = lopdf::Document::load(dir.join("out/sheets/brakka.pdf")).unwrap();
+    assert!(doc.catalog().unwrap().get(b"AcroForm").is_err());
+    let first_page = *doc.get_pages().values().next().unwrap();
+    assert!(doc.get_dictionary(first_page).unwrap().get(b"Annots").is_err());
+    let xobjects = doc
+        .get_page_resources(first_page)
+        .0
+        .unwrap()
+        .get(b"XObject")
+        .unwrap()
+        .as_dict()
+        .unwrap()
+        .clone();
+    let drawn_name = xobjects.iter().any(|(_, form)| {
+        let stream = doc.get_object(form.as_reference().unwrap()).unwrap().as_stream().unwrap();
+        String::from_utf8_lossy(&stream.content).contains("(Brakka Stonefist) Tj")
+    });
+    assert!(drawn_name);
+
+    // Characters from stdin; one failure fails the run but the rest are still filled
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dnd-character-sheet-filler"))
+        .args(["--config", config.to_str().unwrap(), "fill", "-"])
+        .env_remove("DND_MCP_CONFIG")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(Value::Array(vec![brakka, broken]).to_string().as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    assert!(dir.join("out/brakka-stonefist.pdf").is_file());
+    let report = String::from_utf8_lossy(&output.stderr);
+    assert!(report.contains("stdin[1]:\n  error: "), "{}", report);
+    assert!(report.contains("Strength score 25"));
+    assert!(report.contains("1 of 2 character(s) failed"));
+}
@@ -0,0 +1,58 @@
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::dnd_validator::DndValidator;
+use dnd_character_sheet_filler::pdf_filler::PdfFiller;
+use serde_json::json;
+
+fn character_with_combat(combat: serde_json::Value) -> CharacterData {
+    serde_json::from_value(json!({
+        "character": {"name": "Brakka", "class": "Barbarian", "level": 4, "race": "Half-Orc"},
+        "abilities": {"strength": 17, "dexterity": 14, "constitution": 16, "intelligence": 8, "wisdom": 10, "charisma": 10},
+        "combat": combat,
+        "features_traits": {"features": ["Rage"]}
+    }))
+    .expect("valid character JSON")
+}
+
+#[test]
+fn test_mid_combat_snapshot_is_mapped() {
+    let character = character_with_combat(json!({
+        "current_hit_points": 0,
+        "death_save_successes": 2,
+        "death_save_failures": 1,
+        "inspiration": true,
+        "exhaustion_level": 1,
+        "conditions": ["Prone", "Unconscious"]
+    }));
+    let filler = PdfFiller::new(false);
+
+    let checkboxes = filler.get_checkbox_fields(&character);
+    assert_eq!(checkboxes.get("Check Box 12"), Some(&true));
+    assert_eq!(checkboxes.get("Check Box 13"), Some(&true));
+    assert_eq!(checkboxes.get("Check Box 14"), Some(&false));
+    assert_eq!(checkboxes.get("Check Box 15"), Some(&true));
+    assert_eq!(checkboxes.get("Check Box 16"), Some(&false));
+
+    let fields = filler.get_field_values(&character);
+    assert_eq!(fields.get("Inspiration").map(String::as_str), Some("X"));
+    assert!(fields["Features and Traits"]
+        .starts_with("Conditions: Prone, Unconscious, Exhaustion (level 1)\n\nFeatures:"));
+}
+
+#[test]
+fn test_death_saves_and_exhaustion_are_validated() {
+    let character = character_with_combat(json!({
+        "death_save_successes": 3,
+        "death_save_failures": 4,
+        "exhaustion_level": 7
+    }));
+
+    let errors = DndValidator::new(false).validate(&character).unwrap_err();
+    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Death save failures 4 must be between 0 and 3",
+            "Exhaustion level 7 must be between 0 and 6",
+        ]
+    );
+}
@@ -0,0 +1,46 @@
+//! Helpers shared by the integration tests; each test binary uses a subset
+#![allow(dead_code)]
+
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+
+/// An empty directory under the system temp dir, cleared of earlier runs
+pub fn fresh_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A file under tests/fixtures, e.g. "foundry/brakka-actor.json"
+pub fn fixture_text(path: &str) -> String {
+    fs::read_to_string(format!("tests/fixtures/{}", path)).unwrap()
+}
+
+pub fn fixture_json(path: &str) -> Value {
+    serde_json::from_str(&fixture_text(path)).unwrap()
+}
+
+/// `elara` (Wizard 5) or `grommash` (Barbarian 6) from tests/fixtures/fight_club
+pub fn fixture(name: &str) -> CharacterData {
+    serde_json::from_value(fixture_json(&format!("fight_club/{}-character.json", name))).unwrap()
+}
+
+/// The JSON-RPC response to a `method` request
+pub fn request(server: &McpServer, method: &str, params: Value) -> Value {
+    let message = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+    server.handle_message(&message.to_string()).unwrap()
+}
+
+/// The whole JSON-RPC response to a `tools/call`
+pub fn tool_response(server: &McpServer, name: &str, arguments: Value) -> Value {
+    request(server, "tools/call", json!({"name": name, "arguments": arguments}))
+}
+
+/// The `result` of a `tools/call`
+pub fn call(server: &McpServer, name: &str, arguments: Value) -> Value {
+    tool_response(server, name, arguments)["result"].clone()
+}
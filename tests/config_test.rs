@@ -0,0 +1,92 @@
+use dnd_character_sheet_filler::config::ServerConfig;
+use dnd_character_sheet_filler::errors::ConfigError;
+use dnd_character_sheet_filler::logging::{LogLevel, LogTarget};
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::json;
+use std::fs;
+
+mod common;
+
+fn write_config(name: &str, body: &str) -> std::path::PathBuf {
+    let dir = common::fresh_dir(name);
+    let path = dir.join("dnd-mcp.toml");
+    fs::write(&path, body).unwrap();
+    path
+}
+
+#[test]
+fn test_config_file_paths_resolve_relative_to_file() {
+    let path = write_config(
+        "dnd_config_test",
+        r#"
+output_dir = "sheets"
+allow_rule_violations = true
+allowed_output_roots = ["sheets", "/srv/dnd"]
+
+[logging]
+level = "warning"
+file = "logs/server.log"
+max_files = 5
+"#,
+    );
+    let dir = path.parent().unwrap();
+    let config = ServerConfig::from_file(&path).unwrap();
+
+    assert_eq!(config.output_dir(), dir.join("sheets"));
+    assert!(config.allow_rule_violations);
+    assert_eq!(
+        config.resolve_output(None, "bob.pdf").unwrap(),
+        dir.join("sheets/bob.pdf")
+    );
+    assert_eq!(
+        config.resolve_output(Some("/srv/dnd/party/bob.pdf"), "x.pdf").unwrap(),
+        std::path::PathBuf::from("/srv/dnd/party/bob.pdf")
+    );
+    assert!(matches!(
+        config.resolve_output(Some("../escape.pdf"), "x.pdf"),
+        Err(ConfigError::OutputPathTraversal(_))
+    ));
+    assert!(matches!(
+        config.resolve_output(Some("/etc/escape.pdf"), "x.pdf"),
+        Err(ConfigError::OutputNotAllowed(_))
+    ));
+
+    let log_config = config.log_config().unwrap();
+    assert_eq!(log_config.level, LogLevel::Warning);
+    assert_eq!(log_config.target, LogTarget::File(dir.join("logs/server.log")));
+    assert_eq!(log_config.max_files, 5);
+
+    let bad = write_config("dnd_config_test_bad", "output_directory = \"typo\"\n");
+    assert!(matches!(
+        ServerConfig::from_file(&bad),
+        Err(ConfigError::ParseFailed { .. })
+    ));
+}
+
+#[test]
+fn test_mcp_server_honors_config() {
+    let path = write_config(
+        "dnd_config_server_test",
+        "output_dir = \"sheets\"\nallow_rule_violations = true\nallowed_output_roots = [\"sheets\"]\n",
+    );
+    fs::create_dir_all(path.parent().unwrap().join("sheets")).unwrap();
+    let server = McpServer::with_config(ServerConfig::from_file(&path).unwrap()).unwrap();
+    let character = json!({
+        "character": {"name": "Brakka", "class": "Barbarian", "level": 4, "race": "Half-Orc"},
+        "abilities": {"strength": 22, "dexterity": 14, "constitution": 16, "intelligence": 8, "wisdom": 10, "charisma": 10}
+    });
+    let call = |arguments| common::call(&server, "fill_dnd_character_sheet", arguments);
+
+    // Relative output lands in the output directory; violations allowed by default
+    let result = call(json!({"character_data": character, "output_path": "brakka.pdf"}));
+    assert_eq!(result["isError"], false);
+    assert!(path.parent().unwrap().join("sheets/brakka.pdf").is_file());
+    assert_eq!(result["structuredContent"]["rule_violations_overridden"], true);
+
+    let result = call(json!({"character_data": character, "output_path": "/tmp/elsewhere.pdf"}));
+    assert_eq!(result["isError"], true);
+    assert!(result["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("outside the allowed output directories"));
+}
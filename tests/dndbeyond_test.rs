@@ -0,0 +1,91 @@
+use dnd_character_sheet_filler::dndbeyond_importer::DndBeyondImporter;
+use dnd_character_sheet_filler::errors::ImportError;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::json;
+use std::fs;
+
+mod common;
+use common::{call, fixture_json, fresh_dir};
+
+#[test]
+fn test_dndbeyond_import_of_caster_and_multiclass_martial() {
+    let lyra = DndBeyondImporter::import(&fixture_json("dndbeyond/lyra.json")).unwrap();
+    let character = &lyra.character_data;
+    assert_eq!(character.character.class, "Bard");
+    assert_eq!(character.character.subclass.as_deref(), Some("College of Lore"));
+    assert_eq!(character.character.alignment.as_deref(), Some("Chaotic Good"));
+    // Half-elf +2 Cha, +1 Dex, +1 Con and the Actor feat's +1 Cha
+    assert_eq!(json!(character.abilities), json!({"strength": 8, "dexterity": 15, "constitution": 15, "intelligence": 10, "wisdom": 12, "charisma": 18}));
+    let proficiencies = character.proficiencies.as_ref().unwrap();
+    assert_eq!(proficiencies.saving_throws, ["dexterity", "charisma"]);
+    assert_eq!(proficiencies.skills.len(), 7);
+    assert_eq!(proficiencies.other.tools, ["Disguise Kit", "Lute", "Flute", "Viol"]);
+    assert_eq!(proficiencies.other.languages, ["Common", "Elvish", "Sylvan"]);
+    let combat = character.combat.as_ref().unwrap();
+    assert_eq!((combat.hit_point_maximum, combat.current_hit_points), (Some(38), Some(32)));
+    assert_eq!(combat.hit_dice.as_deref(), Some("4d8"));
+    // Bards know their spells, so all of them are ready to cast
+    let spells = character.spells.as_ref().unwrap();
+    assert_eq!(spells.spellcasting_ability.as_deref(), Some("Charisma"));
+    assert!(spells.first_level.iter().all(|spell| spell.prepared));
+    assert_eq!(spells.third_level[0].name, "Hypnotic Pattern");
+    let equipment = character.equipment.as_ref().unwrap();
+    assert_eq!(equipment.items.as_deref(), Some("Rapier, Leather Armor, Lute, Dagger (2), Costume, Potion of Healing (2)"));
+    assert_eq!(equipment.currency.as_ref().unwrap().gp, 148);
+    assert_eq!(character.narrative.as_ref().unwrap().faction_name.as_deref(), Some("Harpers"));
+    let traits = character.features_traits.as_ref().unwrap();
+    assert_eq!(traits.traits.as_deref().unwrap(), ["Darkvision", "Fey Ancestry", "Skill Versatility"]);
+    assert!(traits.features.as_ref().unwrap().contains(&"Cutting Words".to_string()));
+    assert!(!traits.features.as_ref().unwrap().contains(&"Countercharm".to_string()));
+    assert!(lyra.unmapped.contains(&"expertise in Persuasion (recorded as proficiency)".to_string()));
+    assert!(lyra.unmapped.contains(&"modifier from race: Set Base (Darkvision)".to_string()));
+
+    let torvald = DndBeyondImporter::import(&fixture_json("dndbeyond/torvald.json")).unwrap();
+    let character = &torvald.character_data;
+    assert_eq!((character.character.class.as_str(), character.character.level), ("Fighter", 5));
+    assert_eq!(character.character.background.as_deref(), Some("Caravan Guard"));
+    // Gauntlets of Ogre Power set Strength to 19; the ASI and race add to Dexterity
+    assert_eq!((character.abilities.strength, character.abilities.dexterity), (19, 18));
+    let other = &character.proficiencies.as_ref().unwrap().other;
+    assert_eq!(other.tool_expertise, ["Thieves' Tools"]);
+    assert_eq!(other.tools, ["Vehicles (Land)", "Thieves' Tools"]);
+    let combat = character.combat.as_ref().unwrap();
+    assert_eq!((combat.hit_point_maximum, combat.current_hit_points, combat.temporary_hit_points), (Some(51), Some(42), Some(5)));
+    assert_eq!((combat.exhaustion_level, combat.conditions.as_deref()), (Some(1), Some(&["Poisoned".to_string()][..])));
+    let spells = character.spells.as_ref().unwrap();
+    assert_eq!(spells.spellcasting_class, None);
+    assert_eq!(spells.first_level[0].name, "Shield");
+    assert_eq!(
+        torvald.unmapped[..2],
+        ["multiclass: Rogue 1 (the sheet uses Fighter at level 5)", "expertise in Stealth (recorded as proficiency)"]
+    );
+    assert!(torvald.unmapped.contains(&"modifier from item: Resistance (Fire)".to_string()));
+    assert!(torvald.unmapped.contains(&"custom item Caravan Company Signet".to_string()));
+}
+
+#[test]
+fn test_dndbeyond_import_errors_and_tool() {
+    let errors: Vec<String> = DndBeyondImporter::import(&json!({"data": {"name": "Nobody", "classes": []}}))
+        .unwrap_err()
+        .iter()
+        .map(ImportError::to_string)
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            "stats: missing; is this a D&D Beyond character file?",
+            "race: missing; is this a D&D Beyond character file?"
+        ]
+    );
+
+    let dir = fresh_dir("dnd_dndbeyond_tool_test");
+    // The bare character object, without the service's response wrapper
+    fs::write(dir.join("lyra.json"), fixture_json("dndbeyond/lyra.json")["data"].to_string()).unwrap();
+    let server = McpServer::with_output_dir(&dir);
+    let result = call(&server, "import_character_dndbeyond", json!({"path": "lyra.json"}));
+    assert_eq!(result["isError"], false);
+    let output = &result["structuredContent"];
+    assert_eq!(output["character_data"]["character"]["name"], "Lyra Vesper");
+    assert_eq!(output["character_data"]["abilities"]["charisma"], 18);
+    assert!(output["ignored"].as_array().unwrap().contains(&json!("expertise in Deception (recorded as proficiency)")));
+}
@@ -0,0 +1,84 @@
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::dnd_validator::DndValidator;
+use dnd_character_sheet_filler::pdf_filler::PdfFiller;
+use serde_json::json;
+
+fn fighter_with_history(history: serde_json::Value) -> CharacterData {
+    serde_json::from_value(json!({
+        "character": {"name": "Sera", "class": "Fighter", "level": 8, "race": "Human"},
+        "abilities": {"strength": 16, "dexterity": 14, "constitution": 14, "intelligence": 10, "wisdom": 13, "charisma": 8},
+        "proficiencies": {"saving_throws": ["strength", "constitution"], "skills": ["athletics", "perception"]},
+        "level_history": history
+    }))
+    .expect("valid character JSON")
+}
+
+#[test]
+fn test_improvements_flow_into_derived_stats() {
+    let character = fighter_with_history(json!([
+        {"level": 4, "improvement": {"type": "ability_score_improvement", "increases": {"strength": 2}}},
+        {"level": 6, "improvement": {"type": "feat", "name": "Alert"}},
+        {"level": 8, "improvement": {"type": "feat", "name": "Observant", "ability_increases": {"wisdom": 1}}}
+    ]));
+    assert!(DndValidator::new(false).validate(&character).is_ok());
+
+    let fields = PdfFiller::new(false).get_field_values(&character);
+    assert_eq!(fields.get("STR").map(String::as_str), Some("18"));
+    assert_eq!(fields.get("WIS").map(String::as_str), Some("14"));
+    // Dexterity +2 plus Alert's +5
+    assert_eq!(fields.get("Initiative").map(String::as_str), Some("7"));
+    // 10 + Wisdom +2 + proficiency +3 + Observant's +5
+    assert_eq!(fields.get("Passive").map(String::as_str), Some("20"));
+    // 10 + 2 at 1st level, then 6 + 2 for each of the next seven levels
+    assert_eq!(fields.get("HPMax").map(String::as_str), Some("68"));
+    assert_eq!(fields.get("HD").map(String::as_str), Some("d10"));
+    assert!(fields["Features and Traits"]
+        .contains("Feats:\n- Alert: Can't be surprised while conscious\n- Observant"));
+}
+
+#[test]
+fn test_invalid_improvements_are_reported() {
+    let character = fighter_with_history(json!([
+        {"level": 4, "improvement": {"type": "ability_score_improvement", "increases": {"strength": 2, "dexterity": 1}}},
+        {"level": 5, "improvement": {"type": "feat", "name": "Observant", "ability_increases": {"strength": 1}}},
+        {"level": 8, "improvement": {"type": "ability_score_improvement", "increases": {"strength": 2}}}
+    ]));
+
+    let errors = DndValidator::new(false).validate(&character).unwrap_err();
+    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Level 4 improvement must increase ability scores by 2 in total, got 3",
+            "Level 5 improvement is not an Ability Score Improvement level for Fighter",
+            "Level 5 improvement feat 'Observant' does not allow increasing strength",
+            "Strength score 21 exceeds 20 after ability score improvements",
+        ]
+    );
+}
+
+#[test]
+fn test_feat_ability_choices_are_checked() {
+    let character = fighter_with_history(json!([
+        {"level": 4, "improvement": {"type": "feat", "name": "Resilient", "ability_increases": {"constitution": 2}}},
+        {"level": 6, "improvement": {"type": "feat", "name": "Resilient", "ability_increases": {"constitution": 1, "wisdom": 1}}},
+        {"level": 8, "improvement": {"type": "feat", "name": "Skill Expert", "ability_increases": {"strength": 1}}}
+    ]));
+
+    let errors = DndValidator::new(false).validate(&character).unwrap_err();
+    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Level 4 improvement feat 'Resilient' increases one ability score by 1, got constitution +2",
+            "Level 6 improvement feat 'Resilient' increases one ability score by 1, got constitution +1, wisdom +1",
+            "Level 8 improvement feat 'Skill Expert' is not in the feat catalog; describe it in feat_definitions to check its ability increases",
+        ]
+    );
+
+    let character = fighter_with_history(json!([
+        {"level": 4, "improvement": {"type": "feat", "name": "Resilient", "ability_increases": {"constitution": 1}}},
+        {"level": 8, "improvement": {"type": "feat", "name": "Skill Expert"}}
+    ]));
+    assert!(DndValidator::new(false).validate(&character).is_ok());
+}
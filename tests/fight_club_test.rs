@@ -0,0 +1,57 @@
+use dnd_character_sheet_filler::errors::ImportError;
+use dnd_character_sheet_filler::fight_club_exporter::FightClubExporter;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::json;
+use std::fs;
+
+mod common;
+use common::{call, fixture, fixture_text, fresh_dir};
+
+#[test]
+fn test_fight_club_round_trip_for_caster_and_martial() {
+    for name in ["elara", "grommash"] {
+        let character = fixture(name);
+        let xml = fixture_text(&format!("fight_club/{}.xml", name));
+        assert_eq!(FightClubExporter::export(&character), xml, "{} export", name);
+
+        let imported = FightClubExporter::import(&xml).unwrap();
+        assert_eq!(FightClubExporter::export(&imported), xml, "{} re-export", name);
+        assert_eq!(json!(imported.abilities), json!(character.abilities));
+        assert_eq!(json!(imported.level_history), json!(character.level_history));
+        assert_eq!(json!(imported.features_traits), json!(character.features_traits));
+        assert_eq!(json!(imported.equipment), json!(character.equipment));
+        let (ours, theirs) = (imported.proficiencies.unwrap(), character.proficiencies.unwrap());
+        assert_eq!(ours.saving_throws, theirs.saving_throws);
+        assert_eq!(ours.skills.len(), theirs.skills.len());
+        assert_eq!(json!(ours.other), json!(theirs.other));
+        assert_eq!(json!(imported.spells), json!(character.spells));
+    }
+
+    // Proficiency ids follow the skill list: Athletics is 103, Survival 117
+    let grommash = fixture_text("fight_club/grommash.xml");
+    assert!(grommash.contains("<proficiency>103</proficiency>") && grommash.contains("<proficiency>117</proficiency>"));
+}
+
+#[test]
+fn test_fight_club_import_errors_and_tool() {
+    let xml = "<pc version=\"5\">\n  <character>\n    <name>Nyx</name>\n    <abilities>8,18,12,13</abilities>\n    <class>\n      <name>Rogue</name>\n      <level>3</level>\n    </class>\n    <proficiency>42</proficiency>\n  </character>\n</pc>\n";
+    let errors: Vec<String> = FightClubExporter::import(xml).unwrap_err().iter().map(ImportError::to_string).collect();
+    assert_eq!(errors, ["line 4: expected six comma-separated scores (Str, Dex, Con, Int, Wis, Cha)"]);
+
+    let xml = xml.replace("8,18,12,13", "8,18,12,13,10,14,").replace("<level>3", "<level>three");
+    let errors: Vec<String> = FightClubExporter::import(&xml).unwrap_err().iter().map(ImportError::to_string).collect();
+    assert_eq!(
+        errors,
+        ["line 7: expected a number in <level>, found \"three\"", "line 9: unknown proficiency id 42"]
+    );
+    assert!(FightClubExporter::import("<pc><character>").unwrap_err()[0].to_string().starts_with("line 1: "));
+
+    let dir = fresh_dir("dnd_fight_club_tool_test");
+    fs::write(dir.join("elara.xml"), fixture_text("fight_club/elara.xml")).unwrap();
+    let server = McpServer::with_output_dir(&dir);
+    let result = call(&server, "import_character_fight_club", json!({"path": "elara.xml"}));
+    assert_eq!(result["isError"], false);
+    let character = &result["structuredContent"]["character_data"];
+    assert_eq!(character["character"]["name"], "Elara Moonwhisper");
+    assert_eq!(character["spells"]["third_level"][1]["name"], "Fireball");
+}
@@ -0,0 +1,78 @@
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::foundry_exporter::FoundryExporter;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::{json, Value};
+use std::fs;
+
+mod common;
+use common::{call, fixture_json, fresh_dir};
+
+#[test]
+fn test_foundry_actor_round_trip() {
+    let character: CharacterData = serde_json::from_value(fixture_json("foundry/brakka-character.json")).unwrap();
+    let actor = fixture_json("foundry/brakka-actor.json");
+    assert_eq!(FoundryExporter::export(&character), actor);
+
+    let imported = FoundryExporter::import(&actor).unwrap();
+    assert_eq!(FoundryExporter::export(&imported), actor);
+    assert_eq!(json!(imported.abilities), json!(character.abilities));
+    assert_eq!(json!(imported.level_history), json!(character.level_history));
+    assert_eq!(json!(imported.spells), json!(character.spells));
+    assert_eq!(imported.equipment.unwrap().items, character.equipment.unwrap().items);
+    assert_eq!(json!(imported.narrative), json!(character.narrative));
+
+    let dir = fresh_dir("dnd_foundry_tool_test");
+    let server = McpServer::with_output_dir(&dir);
+    let arguments = json!({"character_data": fixture_json("foundry/brakka-character.json"), "output_path": "brakka.json"});
+    let result = call(&server, "export_character_foundry", arguments);
+    assert_eq!(result["isError"], false);
+    assert_eq!(result["structuredContent"]["actor"], actor);
+    let saved: Value = serde_json::from_str(&fs::read_to_string(dir.join("brakka.json")).unwrap()).unwrap();
+    assert_eq!(saved, actor);
+}
+
+#[test]
+fn test_foundry_import_of_actor_exported_by_foundry() {
+    let elara = FoundryExporter::import(&fixture_json("foundry/elara-actor.json")).unwrap();
+
+    assert_eq!(elara.character.class, "Wizard");
+    assert_eq!(elara.character.level, 4);
+    assert_eq!(elara.character.subclass.as_deref(), Some("School of Divination"));
+    assert_eq!(elara.character.race, "High Elf");
+    assert_eq!(elara.abilities.intelligence, 17);
+    assert!(elara.level_history.is_none());
+
+    let proficiencies = elara.proficiencies.unwrap();
+    assert_eq!(proficiencies.saving_throws, ["intelligence", "wisdom"]);
+    // Half proficiency (Jack of All Trades) doesn't count
+    assert_eq!(proficiencies.skills, ["arcana", "history", "investigation", "perception"]);
+    assert_eq!(proficiencies.other.languages, ["Common", "Elvish", "Draconic"]);
+    assert_eq!(proficiencies.other.weapons, ["Dagger", "Quarterstaff", "Light crossbows"]);
+    assert_eq!(proficiencies.other.tools, ["Calligrapher's supplies"]);
+
+    let combat = elara.combat.unwrap();
+    assert_eq!(combat.armor_class, None);
+    assert_eq!((combat.hit_point_maximum, combat.current_hit_points, combat.temporary_hit_points), (Some(22), Some(17), Some(5)));
+    assert_eq!(combat.initiative, Some(7));
+    assert_eq!(combat.exhaustion_level, Some(1));
+    assert_eq!(combat.conditions, None);
+
+    let spells = elara.spells.unwrap();
+    assert_eq!(spells.spellcasting_ability.as_deref(), Some("Intelligence"));
+    let first: Vec<(&str, bool)> = spells.first_level.iter().map(|s| (s.name.as_str(), s.prepared)).collect();
+    assert_eq!(first, [("Detect Magic", true), ("Shield", true), ("Sleep", false)]);
+    assert!(spells.cantrips[0].prepared);
+
+    let features = elara.features_traits.unwrap();
+    assert_eq!(features.features.unwrap(), ["Arcane Recovery: Recover spell slots on a short rest."]);
+    assert_eq!(features.traits.unwrap(), ["Fey Ancestry"]);
+    assert_eq!(features.additional.unwrap(), ["Alert: +5 to initiative."]);
+
+    let equipment = elara.equipment.unwrap();
+    assert_eq!(equipment.items.as_deref(), Some("Quarterstaff, Spellbook, Potion of Healing (2)"));
+    assert_eq!(equipment.currency.unwrap().gp, 37);
+    assert_eq!(
+        elara.narrative.unwrap().backstory.as_deref(),
+        Some("Apprenticed to a Neverwinter archivist.\n\nLeft after finding her name in a forbidden book.")
+    );
+}
@@ -0,0 +1,60 @@
+use dnd_character_sheet_filler::html_exporter::HtmlExporter;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::json;
+use std::fs;
+
+mod common;
+use common::{call, fixture, fresh_dir};
+
+#[test]
+fn test_html_sheet_for_martial_and_caster() {
+    let grommash = fixture("grommash");
+    let html = HtmlExporter::export(&grommash);
+
+    assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">"));
+    // Self-contained: the stylesheet is inline and nothing is fetched
+    assert!(html.contains("<style>") && html.contains("@media print"));
+    assert!(!html.contains("<link") && !html.contains("src=") && !html.contains("url("));
+    assert!(html.contains("<title>Grommash</title>"));
+    assert!(html.contains("<dd>Barbarian 6 (Path of the Berserker)</dd>"));
+    // Scores include the level-history ASI; dots mark proficiency
+    assert!(html.contains("<span class=\"label\">Strength</span><span class=\"modifier\">+3</span><span class=\"score\">17</span>"));
+    assert!(html.contains("<li><span class=\"dot\">●</span><span class=\"bonus\">+6</span><span>Strength</span></li>"));
+    assert!(html.contains("<li><span class=\"dot\">○</span><span class=\"bonus\">+1</span><span>Stealth <span class=\"ability-name\">(Dex)</span></span></li>"));
+    assert!(html.contains("<div class=\"stat\"><span class=\"value\">67</span><span class=\"label\">Hit Point Maximum</span></div>"));
+    assert!(html.contains("<tr><td>Greataxe</td><td>+6</td><td>1d12+3 slashing</td><td>Heavy, Two-handed</td></tr>"));
+    assert!(html.contains("<li>Explorer's pack (bedroll, rope)</li>"));
+    assert!(html.contains("<h3>Feats</h3>\n<ul class=\"list\">\n<li>Tough</li>"));
+    assert!(!html.contains("class=\"page spellcasting\""));
+
+    let elara = fixture("elara");
+    let html = HtmlExporter::export(&elara);
+    assert!(html.contains("<tr><td>Spell attack</td><td>+7</td><td></td><td></td></tr>"));
+    assert!(html.contains("<section class=\"page spellcasting\">"));
+    assert!(html.contains("<div><dt>Spell Save DC</dt><dd>15</dd></div>"));
+    assert!(html.contains("<h3>Level 1 <span class=\"slots\">4 slots</span></h3>"));
+    assert!(html.contains("<li><span class=\"dot\">●</span>Shield</li>\n<li><span class=\"dot\">○</span>Sleep</li>"));
+    assert!(html.contains("<h3>Cantrips</h3>\n<ul class=\"spells\">\n<li>Fire Bolt</li>"));
+}
+
+#[test]
+fn test_html_party_page_tool() {
+    let dir = fresh_dir("dnd_html_tool_test");
+    let server = McpServer::with_output_dir(&dir);
+    let result = call(&server, "export_character_html", json!({
+        "character_data": fixture("grommash"),
+        "party": [fixture("elara")],
+        "party_name": "The <Iron> Company",
+        "output_path": "party.html"
+    }));
+    assert_eq!(result["isError"], false);
+    let html = result["structuredContent"]["html"].as_str().unwrap();
+    assert_eq!(fs::read_to_string(dir.join("party.html")).unwrap(), html);
+
+    assert!(html.contains("<title>The &lt;Iron&gt; Company</title>"));
+    assert!(html.contains("<tr><td><a href=\"#character-grommash\">Grommash</a></td>"));
+    assert!(html.contains("<a href=\"#character-elara-moonwhisper\">Elara Moonwhisper</a>"));
+    let grommash = html.find("<article class=\"sheet\" id=\"character-grommash\">").unwrap();
+    let elara = html.find("<article class=\"sheet\" id=\"character-elara-moonwhisper\">").unwrap();
+    assert!(html.find("party-summary").unwrap() < grommash && grommash < elara);
+}
@@ -0,0 +1,160 @@
+use dnd_character_sheet_filler::http_transport::{HttpConfig, HttpTransport};
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+fn start_server(bearer_token: Option<&str>) -> SocketAddr {
+    let config = HttpConfig {
+        bind_address: "127.0.0.1:0".to_string(),
+        bearer_token: bearer_token.map(str::to_string),
+    };
+    let transport = HttpTransport::bind(McpServer::with_output_dir(std::env::temp_dir()), &config).unwrap();
+    let addr = transport.local_addr().unwrap();
+    thread::spawn(move || transport.run());
+    addr
+}
+
+/// Send a raw HTTP/1.0 POST (no chunked encoding to decode) and return (status, headers, body)
+fn post(addr: SocketAddr, headers: &[(&str, &str)], body: &str) -> (u16, String, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut request = format!(
+        "POST /mcp HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        addr,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes()).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, head.to_string(), body.to_string())
+}
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"1.0"}}}"#;
+
+#[test]
+fn test_http_json_and_sse_responses() {
+    let addr = start_server(None);
+
+    let (status, head, body) = post(addr, &[("Accept", "application/json")], INITIALIZE);
+    assert_eq!(status, 200);
+    assert!(head.to_lowercase().contains("content-type: application/json"));
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+
+    let list = r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#;
+    let (status, head, body) = post(addr, &[("Accept", "application/json, text/event-stream")], list);
+    assert_eq!(status, 200);
+    assert!(head.to_lowercase().contains("content-type: text/event-stream"));
+    let data = body
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .unwrap();
+    let response: Value = serde_json::from_str(data).unwrap();
+    assert_eq!(response["id"], 2);
+    assert!(response["result"]["tools"].as_array().unwrap().len() > 1);
+
+    let notification = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+    let (status, _, _) = post(addr, &[], notification);
+    assert_eq!(status, 202);
+
+    let (status, _, _) = post(addr, &[("Origin", "http://evil.example")], INITIALIZE);
+    assert_eq!(status, 403);
+}
+
+#[test]
+fn test_http_bearer_token_required() {
+    let addr = start_server(Some("s3cret"));
+
+    let (status, head, _) = post(addr, &[], INITIALIZE);
+    assert_eq!(status, 401);
+    assert!(head.contains("WWW-Authenticate: Bearer"));
+
+    let (status, _, _) = post(addr, &[("Authorization", "Bearer wrong")], INITIALIZE);
+    assert_eq!(status, 401);
+
+    let (status, _, body) = post(addr, &[("Authorization", "Bearer s3cret")], INITIALIZE);
+    assert_eq!(status, 200);
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["result"]["serverInfo"]["name"], "D&D Assistant");
+}
+
+#[test]
+fn test_http_sessions_keep_their_own_log_level() {
+    let addr = start_server(None);
+    let session_id = |head: &str| {
+        head.lines()
+            .find_map(|line| line.strip_prefix("Mcp-Session-Id: "))
+            .unwrap()
+            .to_string()
+    };
+    let (_, first, _) = post(addr, &[], INITIALIZE);
+    let (_, second, _) = post(addr, &[], INITIALIZE);
+    let (first, second) = (session_id(&first), session_id(&second));
+    assert_ne!(first, second);
+
+    let set_level = r#"{"jsonrpc":"2.0","id":2,"method":"logging/setLevel","params":{"level":"debug"}}"#;
+    let (status, _, _) = post(addr, &[("Mcp-Session-Id", &first)], set_level);
+    assert_eq!(status, 200);
+
+    // Only the session that asked for debug messages gets them
+    let list = r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#;
+    let sse = ("Accept", "text/event-stream");
+    let (_, _, body) = post(addr, &[sse, ("Mcp-Session-Id", &first)], list);
+    assert!(body.contains("notifications/message"));
+    let (_, _, body) = post(addr, &[sse, ("Mcp-Session-Id", &second)], list);
+    assert!(!body.contains("notifications/message"));
+    // Without a session, setLevel has nothing to set
+    post(addr, &[], set_level);
+    let (_, _, body) = post(addr, &[sse], list);
+    assert!(!body.contains("notifications/message"));
+}
+
+#[test]
+fn test_http_refuses_bodies_over_the_limit() {
+    let addr = start_server(None);
+    let mut stream = TcpStream::connect(addr).unwrap();
+    // Refused from Content-Length alone, before the body is read
+    let request = format!(
+        "POST /mcp HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{{}}",
+        addr,
+        20 * 1024 * 1024 + 1
+    );
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.0 413"), "{}", response);
+}
+
+#[test]
+fn test_http_needs_a_token_beyond_loopback() {
+    let config = |bearer_token: Option<&str>| HttpConfig {
+        bind_address: "0.0.0.0:0".to_string(),
+        bearer_token: bearer_token.map(str::to_string),
+    };
+    let error = HttpTransport::bind(McpServer::new(), &config(None)).err().unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+    assert!(HttpTransport::bind(McpServer::new(), &config(Some("s3cret"))).is_ok());
+}
+
+#[test]
+fn test_session_log_levels_are_capped() {
+    let server = McpServer::new();
+    let set_level = r#"{"jsonrpc":"2.0","id":2,"method":"logging/setLevel","params":{"level":"debug"}}"#;
+    let list = r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#;
+    for session in 0..=1024 {
+        server.handle_session_message(Some(&session.to_string()), set_level);
+    }
+    // The least recently used session made room for the newest
+    assert!(server.handle_session_message(Some("0"), list).1.is_empty());
+    assert!(!server.handle_session_message(Some("1"), list).1.is_empty());
+    assert!(!server.handle_session_message(Some("1024"), list).1.is_empty());
+}
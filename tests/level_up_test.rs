@@ -0,0 +1,112 @@
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::level_up::{LevelUpChoices, LevelUpEngine};
+use serde_json::json;
+
+fn fighter(level: u8) -> CharacterData {
+    serde_json::from_value(json!({
+        "character": {"name": "Sera", "class": "Fighter", "level": level, "subclass": "Champion", "race": "Human"},
+        "abilities": {"strength": 16, "dexterity": 14, "constitution": 14, "intelligence": 10, "wisdom": 12, "charisma": 8},
+        "combat": {"hit_point_maximum": 28, "current_hit_points": 20, "hit_dice": "3d10", "hit_dice_total": 3}
+    }))
+    .expect("valid character JSON")
+}
+
+fn choices(value: serde_json::Value) -> LevelUpChoices {
+    serde_json::from_value(value).expect("valid choices JSON")
+}
+
+#[test]
+fn test_missing_choices_are_reported_as_pending() {
+    let character = fighter(3);
+    let outcome = LevelUpEngine::level_up(&character, &LevelUpChoices::default()).unwrap();
+
+    assert!(!outcome.is_complete());
+    assert!(outcome.changes.is_empty());
+    assert_eq!(outcome.character.character.level, 3);
+    let pending: Vec<&str> = outcome.pending_choices.iter().map(|c| c.choice.as_str()).collect();
+    assert_eq!(pending, vec!["hit_points", "improvement"]);
+}
+
+#[test]
+fn test_level_up_applies_choices_and_reports_diff() {
+    let character = fighter(3);
+    let outcome = LevelUpEngine::level_up(
+        &character,
+        &choices(json!({
+            "hit_points": {"method": "average"},
+            "improvement": {"type": "ability_score_improvement", "increases": {"constitution": 2}}
+        })),
+    )
+    .unwrap();
+
+    assert!(outcome.is_complete());
+    let leveled = &outcome.character;
+    assert_eq!(leveled.character.level, 4);
+    let combat = leveled.combat.as_ref().unwrap();
+    // 6 average + 3 Constitution, plus 1 retroactive hit point for each of the 3 earlier levels
+    assert_eq!(combat.hit_point_maximum, Some(40));
+    assert_eq!(combat.current_hit_points, Some(32));
+    assert_eq!(combat.hit_dice.as_deref(), Some("4d10"));
+
+    let paths: Vec<&str> = outcome.changes.iter().map(|c| c.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "character.level",
+            "combat.current_hit_points",
+            "combat.hit_dice",
+            "combat.hit_dice_total",
+            "combat.hit_point_maximum",
+            "level_history",
+            "derived.constitution",
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_roll_is_rejected() {
+    let error = LevelUpEngine::level_up(
+        &fighter(4),
+        &choices(json!({"hit_points": {"method": "roll", "value": 11}})),
+    )
+    .unwrap_err();
+    assert_eq!(error.to_string(), "Hit point roll 11 is not possible on a d10");
+}
+
+#[test]
+fn test_new_spells_follow_the_class_spellcasting() {
+    let learn = |class: &str, level: u8, spell_level: u8| {
+        let mut character = fighter(level);
+        character.character.class = class.to_string();
+        character.character.subclass = Some("The Fiend".to_string());
+        LevelUpEngine::level_up(
+            &character,
+            &choices(json!({
+                "hit_points": {"method": "average"},
+                "new_spells": [{"name": "Test Spell", "level": spell_level, "prepared": true}]
+            })),
+        )
+        .map(|outcome| outcome.character.spells.unwrap().level_mut(spell_level).unwrap().len())
+        .map_err(|e| e.to_string())
+    };
+
+    assert_eq!(learn("Barbarian", 1, 0).unwrap_err(), "Barbarian level 2 has no spellcasting to learn new spells with");
+    assert_eq!(learn("Fighter", 1, 1).unwrap_err(), "Fighter level 2 has no spellcasting to learn new spells with");
+    assert_eq!(
+        learn("Wizard", 4, 10).unwrap_err(),
+        "Spell 'Test Spell' has level 10; spell levels run from 0 (cantrip) to 9"
+    );
+    // Pact Magic slots reach 3rd level at Warlock 5; Mystic Arcanum adds a 6th-level spell at 11
+    assert_eq!(learn("Warlock", 4, 3), Ok(1));
+    assert_eq!(
+        learn("Warlock", 4, 4).unwrap_err(),
+        "Spell 'Test Spell' is level 4 but the highest castable spell level is 3"
+    );
+    assert_eq!(learn("Warlock", 10, 6), Ok(1));
+    assert_eq!(learn("Warlock", 12, 7), Ok(1));
+    assert!(learn("Warlock", 12, 8).is_err());
+
+    // Multiclassing is out of scope, so there is no class to choose
+    let error = serde_json::from_value::<LevelUpChoices>(json!({"class": "Wizard"})).unwrap_err();
+    assert!(error.to_string().starts_with("unknown field `class`"), "{}", error);
+}
@@ -0,0 +1,72 @@
+use dnd_character_sheet_filler::logging::{LogConfig, LogLevel, LogTarget, Logger};
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::{json, Value};
+use std::fs;
+
+mod common;
+use common::fresh_dir;
+
+fn message(id: u64, method: &str, params: Value) -> String {
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}).to_string()
+}
+
+#[test]
+fn test_set_level_forwards_log_notifications() {
+    let server = McpServer::with_output_dir(std::env::temp_dir());
+    let bad_call = message(
+        2,
+        "tools/call",
+        json!({"name": "fill_dnd_character_sheet", "arguments": {"character_data": {"character": "oops"}}}),
+    );
+
+    // Nothing is forwarded until the client asks for it
+    let (_, notifications) = server.handle_message_with_logs(&bad_call);
+    assert!(notifications.is_empty());
+
+    let (response, _) = server.handle_message_with_logs(&message(1, "logging/setLevel", json!({"level": "warning"})));
+    assert_eq!(response.unwrap()["result"], json!({}));
+
+    let (response, notifications) = server.handle_message_with_logs(&bad_call);
+    assert_eq!(response.unwrap()["error"]["code"], -32602);
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0]["method"], "notifications/message");
+    assert_eq!(notifications[0]["params"]["level"], "warning");
+    assert_eq!(notifications[0]["params"]["logger"], "mcp_server");
+    assert!(notifications[0]["params"]["data"]
+        .as_str()
+        .unwrap()
+        .starts_with("Character data parse error"));
+
+    let (response, _) = server.handle_message_with_logs(&message(3, "logging/setLevel", json!({"level": "loud"})));
+    assert_eq!(response.unwrap()["error"]["code"], -32602);
+
+    assert_eq!("WARN".parse::<LogLevel>().unwrap(), LogLevel::Warning);
+    assert!(LogLevel::Debug < LogLevel::Emergency);
+}
+
+#[test]
+fn test_log_file_rotation() {
+    let dir = fresh_dir("dnd_logging_test");
+    let path = dir.join("server.log");
+
+    Logger::init(LogConfig {
+        level: LogLevel::Info,
+        target: LogTarget::File(path.clone()),
+        max_file_bytes: 300,
+        max_files: 2,
+    })
+    .unwrap();
+
+    for i in 0..20 {
+        Logger::info("logging_test", &format!("message number {} with some padding text", i));
+    }
+    Logger::debug("logging_test", "below the configured level");
+
+    let current = fs::read_to_string(&path).unwrap();
+    assert!(current.contains("INFO      logging_test: message number 19"));
+    assert!(!current.contains("below the configured level"));
+    assert!(current.len() <= 300);
+    assert!(dir.join("server.log.1").exists());
+    assert!(dir.join("server.log.2").exists());
+    assert!(!dir.join("server.log.3").exists());
+}
@@ -0,0 +1,77 @@
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::errors::ImportError;
+use dnd_character_sheet_filler::markdown_exporter::MarkdownExporter;
+use dnd_character_sheet_filler::markdown_importer::MarkdownImporter;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use dnd_character_sheet_filler::schema::example_character;
+use serde_json::json;
+use std::fs;
+
+mod common;
+use common::fresh_dir;
+
+fn example() -> CharacterData {
+    serde_json::from_value(example_character()).unwrap()
+}
+
+#[test]
+fn test_markdown_round_trip() {
+    let mut character = example();
+    character.level_history = Some(serde_json::from_value(json!([
+        {"level": 4, "improvement": {"type": "ability_score_improvement", "increases": {"dexterity": 2}}},
+        {"level": 8, "improvement": {"type": "feat", "name": "Resilient", "ability_increases": {"wisdom": 1}}}
+    ])).unwrap());
+    let markdown = MarkdownExporter::export(&character);
+
+    let import = MarkdownImporter::parse(&markdown).unwrap();
+    assert!(import.ignored.is_empty(), "{:?}", import.ignored);
+    assert_eq!(import.character_data.abilities.dexterity, character.abilities.dexterity);
+    assert_eq!(MarkdownExporter::export(&import.character_data), markdown);
+
+    // Hand-edited files: abbreviations, no Base column, extra sections
+    let edited = "# Nyx\n\n| Field | Value |\n|---|---|\n| Class | Rogue |\n| Level | 5 |\n| Race | Tiefling |\n| Deity | Tymora |\n\n\
+        ## Ability Scores\n\n| Ability | Score |\n|---|---|\n| STR | 8 |\n| DEX | 18 |\n| CON | 12 |\n| INT | 13 |\n| WIS | 10 |\n| CHA | 14 |\n\n\
+        ## Advancement\n\n| Level | Improvement |\n|---|---|\n| 4 | Ability Score Improvement: Dexterity +2 |\n\n\
+        ## Session Notes\n\nOwes the guild 50 gp.\n";
+    let import = MarkdownImporter::parse(edited).unwrap();
+    assert_eq!(import.character_data.abilities.dexterity, 16);
+    assert_eq!(import.ignored, ["line 27: section 'Session Notes'", "line 8: row 'Deity'"]);
+}
+
+#[test]
+fn test_markdown_errors_point_at_lines() {
+    let markdown = "# Nyx\n\n| Field | Value |\n|---|---|\n| Class | Rogue |\n| Level | five |\n\n\
+        ## Ability Scores\n\n| Ability | Score |\n|---|---|\n| Strength | 8 |\n| Luck | 12 |\n\n\
+        ## Skills\n\n| Skill | Proficient |\n|---|---|\n| Stealth | ● |\n| Lockpicking | ● |\n";
+    let errors = MarkdownImporter::parse(markdown).unwrap_err();
+    let messages: Vec<String> = errors.iter().map(ImportError::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "line 6: expected a number for Level, found 'five'",
+            "line 1: missing '| Race | ... |' row in the table under the title",
+            "line 13: unknown ability 'luck'",
+            "line 8: missing a score for Dexterity",
+            "line 8: missing a score for Constitution",
+            "line 8: missing a score for Intelligence",
+            "line 8: missing a score for Wisdom",
+            "line 8: missing a score for Charisma",
+            "line 20: unknown skill 'Lockpicking'",
+        ]
+    );
+
+    // The tool reads files from the output directory
+    let dir = fresh_dir("dnd_markdown_import_tool_test");
+    fs::write(dir.join("broken.md"), markdown).unwrap();
+    fs::write(dir.join("valid.md"), MarkdownExporter::export(&example())).unwrap();
+    let server = McpServer::with_output_dir(&dir);
+    let call = |path: &str| common::call(&server, "import_character_markdown", json!({"path": path}));
+
+    let result = call("broken.md");
+    assert_eq!(result["isError"], true);
+    assert_eq!(result["structuredContent"]["errors"][0], "line 6: expected a number for Level, found 'five'");
+    let result = call("valid.md");
+    assert_eq!(result["isError"], false);
+    assert_eq!(result["structuredContent"]["character_data"]["character"]["name"], example().character.name);
+    assert_eq!(call("../../etc/passwd")["isError"], true);
+}
@@ -0,0 +1,87 @@
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::markdown_exporter::MarkdownExporter;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::{json, Value};
+use std::fs;
+
+mod common;
+use common::{call, fresh_dir};
+
+fn brakka() -> Value {
+    json!({
+        "character": {"name": "Brakka", "class": "Fighter", "subclass": "Eldritch Knight", "level": 8, "race": "Half-Orc",
+                      "background": "Soldier", "experience_points": 34000},
+        "abilities": {"strength": 16, "dexterity": 12, "constitution": 14, "intelligence": 14, "wisdom": 10, "charisma": 8},
+        "proficiencies": {
+            "saving_throws": ["strength", "constitution"],
+            "skills": ["athletics", "intimidation"],
+            "languages": ["Common", "Orc"],
+            "armor": ["All armor", "Shields"]
+        },
+        "combat": {"armor_class": 18, "speed": 30, "current_hit_points": 60, "conditions": ["Poisoned"]},
+        "spells": {
+            "spellcasting_class": "Fighter", "spellcasting_ability": "Intelligence",
+            "cantrips": [{"name": "Booming Blade", "level": 0, "prepared": true}],
+            "first_level": [
+                {"name": "Shield", "level": 1, "prepared": true},
+                {"name": "Absorb Elements", "level": 1, "prepared": false}
+            ],
+            "second_level": [], "third_level": [], "fourth_level": [], "fifth_level": [],
+            "sixth_level": [], "seventh_level": [], "eighth_level": [], "ninth_level": []
+        },
+        "equipment": {
+            "currency": {"cp": 5, "sp": 0, "ep": 0, "gp": 120, "pp": 1},
+            "items": "Longsword, Explorer's pack (rope, torches), Shield"
+        },
+        "features_traits": {"features": ["Second Wind", "Action Surge | once per rest"]},
+        "level_history": [
+            {"level": 6, "improvement": {"type": "feat", "name": "Resilient", "ability_increases": {"wisdom": 1}}},
+            {"level": 4, "improvement": {"type": "ability_score_improvement", "increases": {"strength": 2}}}
+        ],
+        "narrative": {"ideals": "Glory.", "backstory": "Raised in the pits.\n\nEscaped at sixteen.", "faction_name": "Zhentarim"}
+    })
+}
+
+#[test]
+fn test_markdown_sheet_layout() {
+    let character: CharacterData = serde_json::from_value(brakka()).unwrap();
+    let markdown = MarkdownExporter::export(&character);
+
+    assert!(markdown.starts_with("# Brakka\n\n| Field | Value |\n|---|---|\n| Class | Fighter |\n| Subclass | Eldritch Knight |\n"));
+    // Scores include level-history increases; Base keeps the original
+    assert!(markdown.contains("| Strength | 18 | +4 | 16 |"));
+    assert!(markdown.contains("| Wisdom | 11 | +0 | 10 |"));
+    assert!(markdown.contains("| Strength | ● | +7 |"));
+    assert!(markdown.contains("| Dexterity | ○ | +1 |"));
+    assert!(markdown.contains("| Athletics | Str | ● | +7 |"));
+    assert!(markdown.contains("| Conditions | Poisoned |"));
+    assert!(markdown.contains("- **Armor:** All armor, Shields\n- **Languages:** Common, Orc\n"));
+
+    assert!(markdown.contains("### Cantrips\n\n- Booming Blade\n"));
+    assert!(markdown.contains("### Level 1 (4 slots)\n\n- [x] Shield\n- [ ] Absorb Elements\n"));
+    assert!(!markdown.contains("### Level 2"));
+
+    assert!(markdown.contains("- Action Surge | once per rest\n"));
+    assert!(markdown.contains("| 4 |  | Ability Score Improvement: Strength +2 |\n| 6 |  | Feat: Resilient (Wisdom +1) |"));
+    assert!(markdown.contains("| 5 | 0 | 0 | 120 | 1 |"));
+    assert!(markdown.contains("- Longsword\n- Explorer's pack (rope, torches)\n- Shield\n"));
+    assert!(markdown.contains("### Ideals\n\nGlory.\n"));
+    assert!(markdown.contains("## Backstory\n\nRaised in the pits.\n\nEscaped at sixteen.\n"));
+    assert!(markdown.ends_with("## Allies & Organizations\n\n**Faction:** Zhentarim\n"));
+}
+
+#[test]
+fn test_export_character_markdown_tool() {
+    let dir = fresh_dir("dnd_markdown_tool_test");
+    let server = McpServer::with_output_dir(&dir);
+
+    let arguments = json!({"character_data": brakka(), "output_path": "character-level-8.md"});
+    let result = call(&server, "export_character_markdown", arguments);
+    assert_eq!(result["isError"], false);
+
+    let markdown = result["structuredContent"]["markdown"].as_str().unwrap();
+    assert!(markdown.starts_with("# Brakka\n"));
+    let saved = dir.join("character-level-8.md");
+    assert_eq!(result["structuredContent"]["output_file"], saved.display().to_string());
+    assert_eq!(fs::read_to_string(&saved).unwrap(), markdown);
+}
@@ -0,0 +1,90 @@
+use dnd_character_sheet_filler::config::ServerConfig;
+use dnd_character_sheet_filler::errors::{ConfigError, OutputError};
+use dnd_character_sheet_filler::sheet_writer::SheetWriter;
+use std::fs;
+
+mod common;
+use common::fresh_dir;
+
+#[test]
+fn test_existing_sheets_are_suffixed_unless_overwriting() {
+    let dir = fresh_dir("dnd_sheet_writer_test");
+    let path = dir.join("brakka.pdf");
+
+    assert_eq!(SheetWriter::write(&path, b"first", false).unwrap(), path);
+    assert_eq!(SheetWriter::write(&path, b"second", false).unwrap(), dir.join("brakka (2).pdf"));
+    assert_eq!(SheetWriter::write(&path, b"third", false).unwrap(), dir.join("brakka (3).pdf"));
+    assert_eq!(fs::read(&path).unwrap(), b"first");
+
+    assert_eq!(SheetWriter::write(&path, b"replaced", true).unwrap(), path);
+    assert_eq!(fs::read(&path).unwrap(), b"replaced");
+
+    // No temp files are left behind
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["brakka (2).pdf", "brakka (3).pdf", "brakka.pdf"]);
+
+    let missing = dir.join("party").join("brakka.pdf");
+    match SheetWriter::write(&missing, b"x", false) {
+        Err(error @ OutputError::DirectoryMissing(_)) => {
+            assert_eq!(error.to_string(), format!("Output directory {} does not exist", dir.join("party").display()));
+        }
+        other => panic!("expected a missing directory error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_output_paths_are_confined_to_output_dir() {
+    let root = fresh_dir("dnd_output_confinement_test");
+    let outside = fresh_dir("dnd_output_confinement_outside");
+    fs::create_dir_all(root.join("sheets/party")).unwrap();
+    let config = ServerConfig {
+        output_dir: Some(root.join("sheets")),
+        ..ServerConfig::default()
+    };
+
+    assert_eq!(
+        config.resolve_output(Some("party/bob.pdf"), "x.pdf").unwrap(),
+        root.join("sheets/party/bob.pdf")
+    );
+    assert_eq!(
+        config.resolve_output(Some(root.join("sheets/bob.pdf").to_str().unwrap()), "x.pdf").unwrap(),
+        root.join("sheets/bob.pdf")
+    );
+    assert!(matches!(
+        config.resolve_output(Some("party/../../bob.pdf"), "x.pdf"),
+        Err(ConfigError::OutputPathTraversal(_))
+    ));
+    assert!(matches!(
+        config.resolve_output(Some(outside.join("bob.pdf").to_str().unwrap()), "x.pdf"),
+        Err(ConfigError::OutputNotAllowed(_))
+    ));
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&outside, root.join("sheets/link")).unwrap();
+        assert!(matches!(
+            config.resolve_output(Some("link/bob.pdf"), "x.pdf"),
+            Err(ConfigError::OutputNotAllowed(_))
+        ));
+
+        // The file itself may be the symlink, for reading or writing
+        fs::write(outside.join("notes.json"), "{}").unwrap();
+        std::os::unix::fs::symlink(outside.join("notes.json"), root.join("sheets/notes.json")).unwrap();
+        assert!(matches!(
+            config.resolve_input("notes.json"),
+            Err(ConfigError::InputNotAllowed(_))
+        ));
+        assert!(matches!(
+            config.resolve_output(Some("notes.json"), "x.pdf"),
+            Err(ConfigError::OutputNotAllowed(_))
+        ));
+        // ...unless it stays inside the roots
+        fs::write(root.join("sheets/real.json"), "{}").unwrap();
+        std::os::unix::fs::symlink(root.join("sheets/real.json"), root.join("sheets/alias.json")).unwrap();
+        assert_eq!(config.resolve_input("alias.json").unwrap(), root.join("sheets/alias.json"));
+    }
+}
@@ -0,0 +1,172 @@
+use base64::{engine::general_purpose, Engine as _};
+use dnd_character_sheet_filler::character_model::{CharacterData, ImageSource};
+use dnd_character_sheet_filler::config::ServerConfig;
+use dnd_character_sheet_filler::pdf_filler::PdfFiller;
+use lopdf::{Document, Object};
+
+mod common;
+use common::fresh_dir;
+
+const TEMPLATE: &[u8] = include_bytes!("../docs/5E_CharacterSheet_Fillable.pdf");
+
+fn page_two_character() -> CharacterData {
+    serde_json::from_str(
+        r#"{
+            "character": {"name": "Erilon", "class": "Wizard", "level": 3, "race": "High Elf"},
+            "abilities": {"strength": 10, "dexterity": 14, "constitution": 12, "intelligence": 16, "wisdom": 12, "charisma": 10},
+            "appearance": {"age": "112", "height": "5'9\"", "weight": "130 lb", "eyes": "Grey", "skin": "Pale", "hair": "Silver"},
+            "narrative": {"backstory": "Raised in the archives of Candlekeep.", "allies": "The Harpers", "faction_name": "Harpers"},
+            "features_traits": {"additional": ["Fey Ancestry", "Trance"]},
+            "equipment": {"treasure": "A sapphire worth 50 gp"}
+        }"#,
+    )
+    .expect("valid character JSON")
+}
+
+fn tiny_png() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 2);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255u8; 16]).unwrap();
+    }
+    bytes
+}
+
+#[test]
+fn test_page_two_fields_are_mapped() {
+    let fields = PdfFiller::new(false).get_field_values(&page_two_character());
+
+    assert_eq!(fields.get("CharacterName 2").map(String::as_str), Some("Erilon"));
+    assert_eq!(fields.get("Age").map(String::as_str), Some("112"));
+    assert_eq!(fields.get("Hair").map(String::as_str), Some("Silver"));
+    assert_eq!(fields.get("Allies").map(String::as_str), Some("The Harpers"));
+    assert_eq!(fields.get("FactionName").map(String::as_str), Some("Harpers"));
+    assert_eq!(fields.get("Feat+Traits").map(String::as_str), Some("- Fey Ancestry\n- Trance"));
+    assert_eq!(fields.get("Treasure").map(String::as_str), Some("A sapphire worth 50 gp"));
+    assert!(fields.get("Backstory").unwrap().contains("Candlekeep"));
+}
+
+#[test]
+fn test_portrait_is_embedded_from_base64() {
+    let mut character = page_two_character();
+    character.images = Some(serde_json::from_value(serde_json::json!({
+        "portrait": {"base64": general_purpose::STANDARD.encode(tiny_png())}
+    })).unwrap());
+    assert!(matches!(
+        character.images.as_ref().unwrap().portrait,
+        Some(ImageSource::Base64(_))
+    ));
+
+    let output = fresh_dir("page_two_portrait_test").join("erilon.pdf");
+    let result = PdfFiller::new(false)
+        .with_overwrite(true)
+        .fill_character_sheet_from_bytes(&character, TEMPLATE, output.to_str().unwrap())
+        .expect("sheet fills");
+
+    let doc = Document::load(&result.output_file).unwrap();
+    let portrait_has_appearance = doc.objects.values().any(|object| match object {
+        Object::Dictionary(dict) => {
+            let is_portrait = |dict: &lopdf::Dictionary| {
+                matches!(dict.get(b"T"), Ok(Object::String(name, _)) if name.as_slice() == b"CHARACTER IMAGE")
+            };
+            let parent_is_portrait = match dict.get(b"Parent") {
+                Ok(Object::Reference(parent)) => doc.get_dictionary(*parent).map(is_portrait).unwrap_or(false),
+                _ => false,
+            };
+            (is_portrait(dict) || parent_is_portrait) && dict.get(b"AP").is_ok()
+        }
+        _ => false,
+    });
+
+    assert!(portrait_has_appearance);
+}
+
+#[test]
+fn test_missing_portrait_file_is_reported() {
+    let mut character = page_two_character();
+    character.images = Some(serde_json::from_value(serde_json::json!({
+        "portrait": {"path": "does/not/exist.png"}
+    })).unwrap());
+
+    let output = fresh_dir("page_two_missing_portrait_test").join("erilon.pdf");
+    let result = PdfFiller::new(false).fill_character_sheet_from_bytes(
+        &character,
+        TEMPLATE,
+        output.to_str().unwrap(),
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert_eq!(error, "PDF image error: Image file not found: does/not/exist.png");
+}
+
+#[test]
+fn test_tool_image_paths_are_confined_to_output_dir() {
+    let root = fresh_dir("page_two_image_roots_test");
+    std::fs::create_dir_all(root.join("sheets")).unwrap();
+    std::fs::write(root.join("sheets/erilon.png"), tiny_png()).unwrap();
+    std::fs::write(root.join("secret.png"), tiny_png()).unwrap();
+    let config = ServerConfig {
+        output_dir: Some(root.join("sheets")),
+        ..ServerConfig::default()
+    };
+    let filler = PdfFiller::new(false).with_image_roots(&config);
+    let fill = |path: &str| {
+        let mut character = page_two_character();
+        character.images = Some(serde_json::from_value(serde_json::json!({"portrait": {"path": path}})).unwrap());
+        let output = root.join("sheets/erilon.pdf");
+        filler.fill_character_sheet_from_bytes(&character, TEMPLATE, output.to_str().unwrap())
+    };
+
+    // Relative paths are under the output directory, as for the other tool reads
+    assert!(fill("erilon.png").is_ok());
+    let outside = root.join("secret.png").display().to_string();
+    for path in [outside.as_str(), "../secret.png"] {
+        let error = fill(path).unwrap_err().to_string();
+        assert!(error.contains("only files in the output directory or allowed_output_roots can be read"), "{}", error);
+    }
+}
+
+#[test]
+fn test_jpeg_portrait_size_is_read_past_fill_bytes_and_standalone_markers() {
+    // SOI, fill bytes before a short APP0, RST0 and TEM (no length), then a
+    // baseline frame header for a 16x32 RGB image
+    let jpeg: Vec<u8> = [
+        &[0xFF, 0xD8][..],
+        &[0xFF, 0xFF, 0xFF, 0xE0, 0x00, 0x04, 0xFF, 0xC0],
+        &[0xFF, 0xD0, 0xFF, 0x01],
+        &[0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00, 0x20, 0x00, 0x10, 0x03],
+        &[0x01, 0x22, 0x00, 0x02, 0x11, 0x01, 0x03, 0x11, 0x01],
+        &[0xFF, 0xD9],
+    ]
+    .concat();
+    let mut character = page_two_character();
+    character.images = Some(serde_json::from_value(serde_json::json!({
+        "portrait": {"base64": general_purpose::STANDARD.encode(&jpeg)}
+    })).unwrap());
+
+    let output = fresh_dir("page_two_jpeg_test").join("erilon.pdf");
+    let result = PdfFiller::new(false)
+        .fill_character_sheet_from_bytes(&character, TEMPLATE, output.to_str().unwrap())
+        .expect("sheet fills");
+    let doc = Document::load(&result.output_file).unwrap();
+    let jpeg_size = doc.objects.values().find_map(|object| match object {
+        Object::Stream(stream) if matches!(stream.dict.get(b"Filter"), Ok(Object::Name(name)) if name.as_slice() == b"DCTDecode") => {
+            Some((stream.dict.get(b"Width").ok()?.as_i64().ok()?, stream.dict.get(b"Height").ok()?.as_i64().ok()?))
+        }
+        _ => None,
+    });
+    assert_eq!(jpeg_size, Some((16, 32)));
+
+    // A segment running past the end of the file leaves no frame header to find
+    character.images = Some(serde_json::from_value(serde_json::json!({
+        "portrait": {"base64": general_purpose::STANDARD.encode([0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x00, 0x12])}
+    })).unwrap());
+    let error = PdfFiller::new(false)
+        .with_overwrite(true)
+        .fill_character_sheet_from_bytes(&character, TEMPLATE, output.to_str().unwrap())
+        .unwrap_err();
+    assert_eq!(error.to_string(), "PDF image error: JPEG image has no frame header");
+}
@@ -0,0 +1,119 @@
+use dnd_character_sheet_filler::character_store::CharacterStore;
+use dnd_character_sheet_filler::errors::StoreError;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use dnd_character_sheet_filler::party::Party;
+use lopdf::Document;
+use serde_json::json;
+use std::fs;
+use std::io::{Cursor, Read};
+
+mod common;
+use common::{call, fixture, fresh_dir};
+
+#[test]
+fn test_party_roster_in_store() {
+    let dir = fresh_dir("dnd_party_store_test");
+    let store = CharacterStore::new(&dir);
+
+    let mut party = Party::new("The Iron Company");
+    assert_eq!(party.add_member("Sam", "grommash"), None);
+    assert_eq!(party.add_member("Priya", "elara-moonwhisper"), None);
+    // Player names match regardless of case; re-adding switches character
+    assert_eq!(party.character_for("priya"), Some("elara-moonwhisper"));
+    assert_eq!(party.add_member("SAM", "torvald"), Some("grommash".to_string()));
+    assert_eq!(party.member("sam"), Some(("SAM", "torvald")));
+    assert_eq!(party.remove_member("Nobody"), None);
+    party.treasure.push("Deed to Tresendar Manor".to_string());
+    party.experience_points = 1200;
+
+    assert_eq!(store.save_party(&party).unwrap(), "the-iron-company");
+    assert!(dir.join("parties/the-iron-company.json").is_file());
+    let loaded = store.load_party("The Iron Company").unwrap();
+    assert_eq!(loaded.members.len(), 2);
+    assert_eq!(loaded.treasure, ["Deed to Tresendar Manor"]);
+    assert_eq!(loaded.experience_points, 1200);
+    assert_eq!(store.parties().unwrap()[0].0, "the-iron-company");
+    // One damaged file doesn't hide the other parties
+    fs::write(dir.join("parties/broken.json"), "{not json").unwrap();
+    let parties: Vec<String> = store.parties().unwrap().into_iter().map(|(id, _)| id).collect();
+    assert_eq!(parties, ["the-iron-company"]);
+    assert_eq!(store.load_party("the-lost-mine").unwrap_err(), StoreError::PartyNotFound("the-lost-mine".to_string()));
+    // Parties aren't listed as characters
+    assert!(store.list().unwrap().is_empty());
+}
+
+#[test]
+fn test_party_tools_fill_every_member() {
+    let dir = fresh_dir("dnd_party_tool_test");
+    let server = McpServer::with_output_dir(&dir);
+    for name in ["grommash", "elara"] {
+        let character_data = serde_json::to_value(fixture(name)).unwrap();
+        call(&server, "save_character", json!({"character_data": character_data, "id": name}));
+    }
+
+    let missing = call(&server, "add_party_member", json!({"party": "The Iron Company", "player": "Sam", "character_id": "torvald"}));
+    assert_eq!(missing["content"][0]["text"], "Error: No saved character with id 'torvald'");
+    call(&server, "add_party_member", json!({"party": "The Iron Company", "player": "Sam", "character_id": "grommash"}));
+    let added = call(&server, "add_party_member", json!({"party": "the-iron-company", "player": "Priya", "character_id": "elara"}));
+    assert_eq!(added["structuredContent"]["id"], "the-iron-company");
+    assert_eq!(added["structuredContent"]["party"]["members"], json!({"Priya": "elara", "Sam": "grommash"}));
+
+    let found = call(&server, "find_party_character", json!({"player": "priya"}));
+    assert_eq!(found["structuredContent"]["party"], "the-iron-company");
+    assert_eq!(found["structuredContent"]["player"], "Priya");
+    assert_eq!(found["structuredContent"]["character_data"]["character"]["name"], "Elara Moonwhisper");
+
+    // One flattened PDF with both three-page sheets
+    let filled = call(&server, "fill_party_sheets", json!({"party": "The Iron Company"}));
+    assert_eq!(filled["structuredContent"]["success"], true, "{}", filled);
+    assert_eq!(filled["structuredContent"]["output_file"], dir.join("the-iron-company.pdf").display().to_string());
+    let combined = Document::load(dir.join("the-iron-company.pdf")).unwrap();
+    assert_eq!(combined.get_pages().len(), 6);
+    assert!(combined.catalog().unwrap().get(b"AcroForm").is_err());
+
+    let zipped = call(&server, "fill_party_sheets", json!({"party": "The Iron Company", "format": "zip", "output_path": "party.zip"}));
+    assert_eq!(zipped["structuredContent"]["members"][1]["character_id"], "grommash");
+    let mut archive = zip::ZipArchive::new(Cursor::new(fs::read(dir.join("party.zip")).unwrap())).unwrap();
+    assert_eq!(archive.file_names().collect::<Vec<_>>().len(), 2);
+    let mut sheet = Vec::new();
+    archive.by_name("elara.pdf").unwrap().read_to_end(&mut sheet).unwrap();
+    assert!(Document::load_mem(&sheet).unwrap().catalog().unwrap().get(b"AcroForm").is_ok());
+
+    let removed = call(&server, "remove_party_member", json!({"party": "The Iron Company", "player": "SAM"}));
+    assert_eq!(removed["structuredContent"]["party"]["members"], json!({"Priya": "elara"}));
+    let absent = call(&server, "find_party_character", json!({"player": "Sam"}));
+    assert_eq!(absent["content"][0]["text"], "Error: Sam is not in any party");
+}
+
+#[test]
+fn test_roster_changes_keep_session_awards() {
+    let dir = fresh_dir("dnd_party_race_test");
+    let server = std::sync::Arc::new(McpServer::with_output_dir(&dir));
+    for name in ["grommash", "elara"] {
+        let character_data = serde_json::to_value(fixture(name)).unwrap();
+        call(&server, "save_character", json!({"character_data": character_data, "id": name}));
+        call(&server, "add_party_member", json!({"party": "Iron", "player": name, "character_id": name}));
+    }
+    for _ in 0..6 {
+        let session = json!({"party": "Iron", "attendees": ["elara"], "experience_points": 10, "loot": [{"items": ["Gem"]}]});
+        call(&server, "record_session", session);
+    }
+
+    // Roster edits racing the applies, each saving the whole party file
+    let threads: Vec<_> = (1..=6)
+        .map(|number| {
+            let server = std::sync::Arc::clone(&server);
+            std::thread::spawn(move || {
+                call(&server, "apply_session", json!({"number": number}));
+                call(&server, "add_party_member", json!({"party": "Iron", "player": "Sam", "character_id": "grommash"}));
+                call(&server, "remove_party_member", json!({"party": "Iron", "player": "Sam"}));
+            })
+        })
+        .collect();
+    threads.into_iter().for_each(|thread| thread.join().unwrap());
+
+    let party = &call(&server, "get_party", json!({"party": "Iron"}))["structuredContent"]["party"];
+    assert_eq!(party["experience_points"], 60);
+    assert_eq!(party["treasure"].as_array().unwrap().len(), 6);
+    assert_eq!(party["members"], json!({"elara": "elara", "grommash": "grommash"}));
+}
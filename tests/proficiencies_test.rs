@@ -0,0 +1,55 @@
+use dnd_character_sheet_filler::character_model::Proficiencies;
+use dnd_character_sheet_filler::proficiency_handler::ProficiencyHandler;
+
+fn parse(json: &str) -> Proficiencies {
+    serde_json::from_str(json).expect("valid proficiencies JSON")
+}
+
+#[test]
+fn test_legacy_proficiencies_still_parse() {
+    let proficiencies = parse(r#"{"saving_throws": ["strength"], "skills": ["athletics"]}"#);
+    let merged = ProficiencyHandler::merge(&proficiencies);
+    assert!(merged.languages.is_empty());
+    assert_eq!(ProficiencyHandler::format_other_proficiencies(&merged), "");
+}
+
+#[test]
+fn test_sources_are_merged_and_deduplicated() {
+    let proficiencies = parse(
+        r#"{
+            "saving_throws": [],
+            "skills": [],
+            "languages": ["Common"],
+            "sources": [
+                {"source": "race", "name": "High Elf", "languages": ["common", "Elvish"], "weapons": ["Longsword"]},
+                {"source": "class", "name": "Fighter", "armor": ["Light armor", "Shields"], "weapons": ["longsword", "Martial weapons"]},
+                {"source": "background", "name": "Criminal", "tools": ["Dice set"], "tool_expertise": ["Thieves' tools"]}
+            ]
+        }"#,
+    );
+
+    let merged = ProficiencyHandler::merge(&proficiencies);
+    assert_eq!(merged.languages, vec!["Common", "Elvish"]);
+    assert_eq!(merged.weapons, vec!["Longsword", "Martial weapons"]);
+    assert_eq!(merged.tools, vec!["Dice set", "Thieves' tools"]);
+}
+
+#[test]
+fn test_format_groups_and_marks_expertise() {
+    let proficiencies = parse(
+        r#"{
+            "saving_throws": [],
+            "skills": [],
+            "armor": ["Light armor"],
+            "tools": ["Thieves' tools"],
+            "tool_expertise": ["thieves' tools"],
+            "languages": ["Common", "Thieves' Cant"]
+        }"#,
+    );
+
+    let text = ProficiencyHandler::format_other_proficiencies(&ProficiencyHandler::merge(&proficiencies));
+    assert_eq!(
+        text,
+        "Armor: Light armor\nTools: Thieves' tools (expertise)\nLanguages: Common, Thieves' Cant"
+    );
+}
@@ -0,0 +1,67 @@
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::json;
+
+mod common;
+use common::request;
+
+#[test]
+fn test_prompts_are_listed_and_embed_rules() {
+    let server = McpServer::new();
+
+    let list = request(&server, "prompts/list", json!({}));
+    let names: Vec<&str> = list["result"]["prompts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["create_character", "level_up", "review_sheet"]);
+
+    let prompt = request(
+        &server,
+        "prompts/get",
+        json!({"name": "create_character", "arguments": {"class": "Druid", "race": "Gnome"}}),
+    );
+    let messages = prompt["result"]["messages"].as_array().unwrap();
+    let text = messages[0]["content"]["text"].as_str().unwrap();
+    assert!(text.starts_with("Help me create a new D&D 5e character (race: Gnome, class: Druid)."));
+    assert!(text.contains("\"level_history\""));
+    // Just the guide's requirement list, without the campaign's own house rules
+    assert!(text.contains("\n\nKey requirements:\n- All spell levels"));
+    assert!(text.contains("must be PNG or JPEG\n\ncharacter_data JSON Schema:"));
+    assert!(!text.contains("XP should be ignored"));
+    assert_eq!(messages[1]["content"]["resource"]["uri"], "dnd://rules/dnd-rules.md");
+}
+
+#[test]
+fn test_review_sheet_includes_validator_findings() {
+    let server = McpServer::new();
+    let character = json!({
+        "character": {"name": "Brakka", "class": "Barbarian", "level": 4, "race": "Half-Orc"},
+        "abilities": {"strength": 22, "dexterity": 14, "constitution": 16, "intelligence": 8, "wisdom": 10, "charisma": 10}
+    });
+
+    let prompt = request(
+        &server,
+        "prompts/get",
+        json!({"name": "review_sheet", "arguments": {"character_data": character.to_string()}}),
+    );
+    let text = prompt["result"]["messages"][0]["content"]["text"].as_str().unwrap();
+    assert!(text.contains("- Strength score 22 must be between 1 and 20"));
+    assert!(text.contains("hit point maximum 45"));
+
+    let missing = request(&server, "prompts/get", json!({"name": "level_up", "arguments": {}}));
+    assert_eq!(missing["error"]["message"], "Missing required argument: character_data");
+
+    let mut capped = character.clone();
+    capped["character"]["level"] = 20.into();
+    let maxed = request(
+        &server,
+        "prompts/get",
+        json!({"name": "level_up", "arguments": {"character_data": capped.to_string()}}),
+    );
+    assert_eq!(
+        maxed["error"]["message"],
+        "Invalid argument 'character_data': Character is already level 20 and cannot level up"
+    );
+}
@@ -0,0 +1,56 @@
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::{json, Value};
+use std::fs;
+
+mod common;
+use common::{fresh_dir, request};
+
+#[test]
+fn test_resources_list_and_read_catalogs() {
+    let output_dir = fresh_dir("dnd_resources_test");
+    fs::write(output_dir.join("sera_fighter_4.pdf"), b"%PDF-1.5 test").unwrap();
+    let server = McpServer::with_output_dir(&output_dir);
+
+    let list = request(&server, "resources/list", json!({}));
+    let uris: Vec<&str> = list["result"]["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["uri"].as_str().unwrap())
+        .collect();
+    for uri in [
+        "dnd://templates/5e-character-sheet.pdf",
+        "dnd://rules/dnd-rules.md",
+        "dnd://classes/wizard",
+        "dnd://races/half-orc",
+        "dnd://spells/hunters-mark",
+        "dnd://sheets/sera_fighter_4.pdf",
+    ] {
+        assert!(uris.contains(&uri), "missing {}", uri);
+    }
+
+    let wizard = request(&server, "resources/read", json!({"uri": "dnd://classes/wizard"}));
+    let contents = &wizard["result"]["contents"][0];
+    assert_eq!(contents["mimeType"], "application/json");
+    let class: Value = serde_json::from_str(contents["text"].as_str().unwrap()).unwrap();
+    assert_eq!(class["hit_die"], 6);
+
+    let rules = request(&server, "resources/read", json!({"uri": "dnd://rules/dnd-rules.md"}));
+    assert!(rules["result"]["contents"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("Combat State Errors"));
+
+    let sheet = request(&server, "resources/read", json!({"uri": "dnd://sheets/sera_fighter_4.pdf"}));
+    assert_eq!(sheet["result"]["contents"][0]["blob"], "JVBERi0xLjUgdGVzdA==");
+}
+
+#[test]
+fn test_unknown_resources_are_rejected() {
+    let server = McpServer::with_output_dir(fresh_dir("dnd_unknown_resources_test"));
+
+    for uri in ["dnd://spells/not-a-spell", "dnd://sheets/../etc/passwd", "file:///etc/passwd"] {
+        let response = request(&server, "resources/read", json!({"uri": uri}));
+        assert_eq!(response["error"]["code"], -32002, "{}", uri);
+    }
+}
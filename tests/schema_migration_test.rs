@@ -0,0 +1,79 @@
+use dnd_character_sheet_filler::character_model::{CharacterData, CURRENT_SCHEMA_VERSION};
+use dnd_character_sheet_filler::errors::MigrationError;
+use dnd_character_sheet_filler::schema_migration::SchemaMigration;
+use serde_json::{json, Value};
+use std::fs;
+use std::process::Command;
+
+mod common;
+use common::{fixture_text, fresh_dir};
+
+fn fixture(version: u32) -> String {
+    fixture_text(&format!("schema_versions/v{}.json", version))
+}
+
+#[test]
+fn test_every_schema_version_migrates_to_current() {
+    let current: Value = serde_json::from_str(&fixture(CURRENT_SCHEMA_VERSION)).unwrap();
+    for version in 1..=CURRENT_SCHEMA_VERSION {
+        let mut value: Value = serde_json::from_str(&fixture(version)).unwrap();
+        assert_eq!(SchemaMigration::migrate(&mut value), Ok(version as u64), "v{}", version);
+        assert_eq!(value, current, "v{} migrated", version);
+
+        // Deserializing migrates too, and what's written back is the current layout
+        let character: CharacterData = serde_json::from_str(&fixture(version)).unwrap();
+        assert_eq!(character.schema_version, CURRENT_SCHEMA_VERSION);
+        let written = serde_json::to_value(&character).unwrap();
+        assert_eq!(written["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+        assert_eq!(written["spells"]["ninth_level"], json!([]));
+        assert_eq!(written["spells"]["first_level"][1]["name"], "Magic Missile");
+    }
+
+    let mut newer = json!({"schema_version": CURRENT_SCHEMA_VERSION + 1});
+    assert_eq!(
+        SchemaMigration::migrate(&mut newer),
+        Err(MigrationError::NewerVersion { found: CURRENT_SCHEMA_VERSION as u64 + 1, current: CURRENT_SCHEMA_VERSION })
+    );
+    let error = serde_json::from_value::<CharacterData>(json!({"schema_version": "two"})).unwrap_err();
+    assert_eq!(error.to_string(), "schema_version must be a whole number from 1, found \"two\"");
+}
+
+#[test]
+fn test_cli_migrate_rewrites_files_with_backups() {
+    let dir = fresh_dir("dnd_migrate_cli_test");
+    fs::write(dir.join("thorin.json"), fixture(1)).unwrap();
+    fs::write(dir.join("current.json"), fixture(CURRENT_SCHEMA_VERSION)).unwrap();
+    let party = format!("[{}, {}]", fixture(1), fixture(CURRENT_SCHEMA_VERSION));
+    fs::write(dir.join("party.json"), &party).unwrap();
+    fs::write(dir.join("future.json"), r#"{"schema_version": 99}"#).unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_dnd-character-sheet-filler"))
+            .arg("migrate")
+            .args(args)
+            .env_remove("DND_MCP_CONFIG")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[dir.join("thorin.json").to_str().unwrap(), "--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("thorin.json: schema_version 1 -> 2 (dry run, not written)\n"));
+    assert_eq!(fs::read_to_string(dir.join("thorin.json")).unwrap(), fixture(1));
+
+    // A directory migrates every *.json in it; the newer file fails and is left alone
+    let output = run(&[dir.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("current.json: already at schema_version 2\n"));
+    assert!(stdout.contains("thorin.json: schema_version 1 -> 2 (backup: "));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("future.json: error: character 1: schema_version 99 is newer"));
+
+    assert_eq!(fs::read_to_string(dir.join("thorin.json")).unwrap(), fixture(CURRENT_SCHEMA_VERSION));
+    assert_eq!(fs::read_to_string(dir.join("thorin.json.v1.bak")).unwrap(), fixture(1));
+    assert_eq!(fs::read_to_string(dir.join("party.json.v1.bak")).unwrap(), party);
+    let migrated: Value = serde_json::from_str(&fs::read_to_string(dir.join("party.json")).unwrap()).unwrap();
+    assert_eq!(migrated[0], migrated[1]);
+    assert_eq!(fs::read_to_string(dir.join("future.json")).unwrap(), r#"{"schema_version": 99}"#);
+    assert!(!dir.join("current.json.v2.bak").exists());
+}
@@ -0,0 +1,48 @@
+use dnd_character_sheet_filler::character_model::CharacterData;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use dnd_character_sheet_filler::schema::SchemaGenerator;
+use serde_json::json;
+
+mod common;
+use common::request;
+
+#[test]
+fn test_character_data_schema_is_generated_from_model() {
+    let schema = SchemaGenerator::character_data();
+
+    assert_eq!(schema["required"], json!(["character", "abilities"]));
+    let abilities = &schema["properties"]["abilities"];
+    assert_eq!(abilities["properties"]["wisdom"]["maximum"], json!(30));
+    assert_eq!(
+        schema["properties"]["character"]["properties"]["level"]["minimum"],
+        json!(1)
+    );
+    // Inlined so it can be embedded in a tool's inputSchema
+    assert!(!schema.to_string().contains("$ref"));
+
+    let example = schema["examples"][0].clone();
+    serde_json::from_value::<CharacterData>(example).expect("schema example parses as CharacterData");
+}
+
+#[test]
+fn test_tools_list_advertises_full_schema() {
+    let server = McpServer::new();
+    let response = request(&server, "tools/list", json!({}));
+
+    let tools = response["result"]["tools"].as_array().unwrap();
+    for tool in tools {
+        // The import tools, load_character and find_party_character return
+        // character_data rather than taking it; the other store tools work
+        // on ids alone
+        let character_data = match tool["name"].as_str() {
+            Some("import_character_markdown" | "import_character_fight_club" | "import_character_dndbeyond" | "load_character" | "find_party_character") => &tool["outputSchema"]["properties"]["character_data"],
+            Some("list_characters" | "delete_character" | "character_diff" | "add_party_member" | "remove_party_member" | "get_party" | "fill_party_sheets" | "record_session" | "apply_session") => continue,
+            _ => &tool["inputSchema"]["properties"]["character_data"],
+        };
+        let spells_required = character_data["properties"]["spells"]["required"]
+            .as_array()
+            .unwrap();
+        assert!(spells_required.contains(&json!("ninth_level")));
+        assert!(character_data["description"].is_string());
+    }
+}
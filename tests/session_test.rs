@@ -0,0 +1,188 @@
+use dnd_character_sheet_filler::character_model::Currency;
+use dnd_character_sheet_filler::character_store::CharacterStore;
+use dnd_character_sheet_filler::dnd_validator::DndValidator;
+use dnd_character_sheet_filler::errors::StoreError;
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use dnd_character_sheet_filler::session_log::{Loot, Session, SessionLog};
+use serde_json::json;
+
+mod common;
+use common::{call, fixture, fresh_dir};
+
+#[test]
+fn test_applying_a_session_awards_xp_and_loot_once() {
+    let dir = fresh_dir("dnd_session_store_test");
+    let store = CharacterStore::new(&dir);
+    store.save(Some("elara"), &fixture("elara")).unwrap();
+
+    assert_eq!(DndValidator::experience_for_level(6), Some(14000));
+    assert_eq!(DndValidator::experience_for_level(21), None);
+    assert!(!DndValidator::ready_to_level_up(&fixture("elara")));
+
+    let mut session = Session {
+        number: 1,
+        date: "March 14th".to_string(),
+        party: None,
+        attendees: vec!["elara".to_string(), "elara".to_string()],
+        experience_points: 7500,
+        loot: vec![Loot {
+            character_id: Some("elara".to_string()),
+            currency: Some(Currency { gp: 50, ..Currency::default() }),
+            items: vec!["Wand of Magic Missiles".to_string()],
+        }],
+        milestones: vec!["Cleared Wave Echo Cave".to_string()],
+        notes: None,
+        applied: false,
+    };
+    assert_eq!(
+        SessionLog::prepare(&store, &mut session).unwrap_err(),
+        StoreError::InvalidSession("date 'March 14th' is not YYYY-MM-DD".to_string())
+    );
+    session.date = "2026-03-14".to_string();
+    SessionLog::prepare(&store, &mut session).unwrap();
+    assert_eq!(session.attendees, ["elara"]);
+    store.save_session(&session).unwrap();
+
+    let (awards, party) = SessionLog::apply(&store, 1).unwrap();
+    assert!(party.is_none());
+    assert_eq!(awards.len(), 1);
+    assert_eq!((awards[0].experience_points, awards[0].version), (14000, 2));
+    assert!(awards[0].ready_to_level_up);
+    assert_eq!(awards[0].loot, ["50 gp", "Wand of Magic Missiles"]);
+
+    let (elara, _) = store.load("elara", None).unwrap();
+    let equipment = elara.equipment.unwrap();
+    assert_eq!(equipment.currency.unwrap().gp, 87);
+    assert!(equipment.items.unwrap().ends_with("Potion of Healing (2), Wand of Magic Missiles"));
+    assert!(store.load_session(1).unwrap().applied);
+    assert_eq!(SessionLog::apply(&store, 1).unwrap_err(), StoreError::SessionApplied(1));
+    assert!(store.list().unwrap()[0].ready_to_level_up);
+}
+
+#[test]
+fn test_session_tools_use_player_names_and_party_treasure() {
+    let dir = fresh_dir("dnd_session_tool_test");
+    let server = McpServer::with_output_dir(&dir);
+    for name in ["grommash", "elara"] {
+        let character_data = serde_json::to_value(fixture(name)).unwrap();
+        call(&server, "save_character", json!({"character_data": character_data, "id": name}));
+    }
+    call(&server, "add_party_member", json!({"party": "The Iron Company", "player": "Sam", "character_id": "grommash"}));
+    call(&server, "add_party_member", json!({"party": "The Iron Company", "player": "Priya", "character_id": "elara"}));
+
+    let recorded = call(&server, "record_session", json!({
+        "date": "2026-03-14",
+        "party": "The Iron Company",
+        "attendees": ["Sam", "priya"],
+        "experience_points": 7500,
+        "loot": [
+            {"character_id": "Sam", "items": ["Bracers of Defense"]},
+            {"currency": {"cp": 0, "sp": 0, "ep": 0, "gp": 200, "pp": 0}, "items": ["Deed to Tresendar Manor"]}
+        ],
+        "milestones": ["Cleared Wave Echo Cave"]
+    }));
+    let session = &recorded["structuredContent"]["session"];
+    assert_eq!(session["number"], 1);
+    assert_eq!(session["party"], "the-iron-company");
+    assert_eq!(session["attendees"], json!(["grommash", "elara"]));
+    assert_eq!(session["loot"][0]["character_id"], "grommash");
+    assert_eq!(recorded["structuredContent"]["awards"], json!([]));
+
+    let applied = call(&server, "apply_session", json!({"number": 1}));
+    let output = &applied["structuredContent"];
+    assert_eq!(output["session"]["applied"], true);
+    assert_eq!(output["awards"][0]["character_id"], "grommash");
+    assert_eq!(output["awards"][0]["experience_points"], 21500);
+    assert_eq!(output["awards"][0]["ready_to_level_up"], false);
+    assert_eq!(output["awards"][0]["next_level_at"], 23000);
+    assert_eq!(output["awards"][0]["loot"], json!(["Bracers of Defense"]));
+    assert_eq!(output["awards"][1]["ready_to_level_up"], true);
+    assert_eq!(output["party"]["treasure"], json!(["Deed to Tresendar Manor"]));
+    assert_eq!(output["party"]["currency"]["gp"], 200);
+    assert_eq!(output["party"]["experience_points"], 7500);
+
+    let diff = call(&server, "character_diff", json!({"id": "elara"}));
+    assert_eq!(diff["structuredContent"]["changes"], json!(["Character / Experience Points: 6500 → 14000"]));
+    let again = call(&server, "apply_session", json!({"number": 1}));
+    assert_eq!(again["content"][0]["text"], "Error: Session 1 has already been applied");
+    let next = call(&server, "record_session", json!({"date": "2026-03-21", "attendees": ["nobody"]}));
+    assert_eq!(next["content"][0]["text"], "Error: No saved character with id 'nobody'");
+}
+
+#[test]
+fn test_a_session_is_never_applied_twice() {
+    let dir = fresh_dir("dnd_session_apply_once_test");
+    let store = std::sync::Arc::new(CharacterStore::new(&dir));
+    store.save(Some("elara"), &fixture("elara")).unwrap();
+    store.save(Some("grommash"), &fixture("grommash")).unwrap();
+    for number in [1, 2] {
+        let mut session = Session {
+            number,
+            date: "2026-03-14".to_string(),
+            party: None,
+            attendees: vec!["elara".to_string(), "grommash".to_string()],
+            experience_points: 100,
+            loot: Vec::new(),
+            milestones: Vec::new(),
+            notes: None,
+            applied: false,
+        };
+        SessionLog::prepare(&store, &mut session).unwrap();
+        store.save_session(&session).unwrap();
+    }
+
+    let applies: Vec<_> = (0..4)
+        .map(|_| {
+            let store = std::sync::Arc::clone(&store);
+            std::thread::spawn(move || SessionLog::apply(&store, 1).is_ok())
+        })
+        .collect();
+    let applied = applies.into_iter().map(|apply| apply.join().unwrap()).filter(|ok| *ok).count();
+    assert_eq!(applied, 1);
+    assert_eq!(store.load("elara", None).unwrap().0.character.experience_points, Some(6600));
+
+    // Grommash's snapshots can't be written, so session 2 fails after Elara's award
+    std::fs::remove_dir_all(dir.join("snapshots/grommash")).unwrap();
+    std::fs::write(dir.join("snapshots/grommash"), "").unwrap();
+    assert!(matches!(SessionLog::apply(&store, 2), Err(StoreError::WriteFailed { .. })));
+    assert!(store.load_session(2).unwrap().applied);
+    assert_eq!(SessionLog::apply(&store, 2).unwrap_err(), StoreError::SessionApplied(2));
+    assert_eq!(store.load("elara", None).unwrap().0.character.experience_points, Some(6700));
+
+    // Sessions recorded at the same time get numbers of their own
+    std::fs::remove_file(dir.join("snapshots/grommash")).unwrap();
+    let session = |number| Session {
+        number,
+        date: "2026-03-21".to_string(),
+        party: None,
+        attendees: vec!["elara".to_string()],
+        experience_points: 100,
+        loot: Vec::new(),
+        milestones: Vec::new(),
+        notes: None,
+        applied: false,
+    };
+    let records: Vec<_> = (0..6)
+        .map(|_| {
+            let store = std::sync::Arc::clone(&store);
+            std::thread::spawn(move || {
+                let mut recorded = session(0);
+                SessionLog::record(&store, &mut recorded, None).map(|_| recorded.number)
+            })
+        })
+        .collect();
+    let mut numbers: Vec<u32> = records.into_iter().map(|record| record.join().unwrap().unwrap()).collect();
+    numbers.sort_unstable();
+    assert_eq!(numbers, [3, 4, 5, 6, 7, 8]);
+
+    // Recording again while a session is applied never makes it applicable twice
+    let rerecord = {
+        let store = std::sync::Arc::clone(&store);
+        std::thread::spawn(move || SessionLog::record(&store, &mut session(3), Some(3)))
+    };
+    let _ = SessionLog::apply(&store, 3);
+    let _ = rerecord.join().unwrap();
+    let _ = SessionLog::apply(&store, 3);
+    assert_eq!(SessionLog::record(&store, &mut session(3), Some(3)), Err(StoreError::SessionApplied(3)));
+    assert_eq!(store.load("elara", None).unwrap().0.character.experience_points, Some(6800));
+}
@@ -0,0 +1,61 @@
+use dnd_character_sheet_filler::mcp_server::McpServer;
+use serde_json::{json, Value};
+
+mod common;
+use common::{call, fresh_dir, request};
+
+fn character(strength: u8) -> Value {
+    json!({
+        "character": {"name": "Brakka", "class": "Barbarian", "level": 4, "race": "Half-Orc"},
+        "abilities": {"strength": strength, "dexterity": 14, "constitution": 16, "intelligence": 8, "wisdom": 10, "charisma": 10}
+    })
+}
+
+#[test]
+fn test_fill_returns_structured_content_and_embedded_pdf() {
+    let output_dir = fresh_dir("dnd_structured_output_test");
+    let output_path = output_dir.join("brakka.pdf");
+    let server = McpServer::with_output_dir(&output_dir);
+
+    let result = call(
+        &server,
+        "fill_dnd_character_sheet",
+        json!({
+            "character_data": character(22),
+            "output_path": output_path.to_str().unwrap(),
+            "return_pdf_content": true,
+            "allow_rule_violations": true,
+            "overwrite": true
+        }),
+    );
+
+    assert_eq!(result["isError"], false);
+    let structured = &result["structuredContent"];
+    assert_eq!(structured["success"], true);
+    assert_eq!(structured["calculated_fields"]["strength_modifier"], "6");
+    assert_eq!(structured["rule_violations_overridden"], true);
+    assert_eq!(structured["validation_errors"][0]["severity"], "warning");
+    assert!(structured["file_size"].as_u64().unwrap() > 0);
+
+    let embedded = &result["content"][1]["resource"];
+    assert_eq!(embedded["uri"], "dnd://sheets/brakka.pdf");
+    assert!(embedded["blob"].as_str().unwrap().starts_with("JVBER"));
+}
+
+#[test]
+fn test_rule_violations_are_reported_in_structured_content() {
+    let server = McpServer::new();
+    let result = call(&server, "fill_dnd_character_sheet", json!({"character_data": character(22)}));
+
+    assert_eq!(result["isError"], true);
+    assert_eq!(result["structuredContent"]["success"], false);
+    assert_eq!(
+        result["structuredContent"]["validation_errors"],
+        json!([{"error": "Strength score 22 must be between 1 and 20", "severity": "error"}])
+    );
+
+    let tools = request(&server, "tools/list", json!({}));
+    for tool in tools["result"]["tools"].as_array().unwrap() {
+        assert_eq!(tool["outputSchema"]["type"], "object");
+    }
+}